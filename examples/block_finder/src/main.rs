@@ -1,5 +1,5 @@
 use silverfish::{Name, Region, Result};
-use std::{env::args, fs::File, path::PathBuf};
+use std::env::args;
 
 // block_finder <region_file> <block_id>
 fn main() -> Result<()> {
@@ -8,17 +8,8 @@ fn main() -> Result<()> {
     let block_id = args.get(2).expect("No block_id file given");
     let block_name = Name::new_id(block_id.as_str()).into_namespaced();
 
-    // we expect the region file to follow this format "r.x.x.mca"
-    let name = PathBuf::from(region)
-        .file_name()
-        .unwrap()
-        .to_string_lossy()
-        .into_owned();
-    let region_coordinates = name.split('.').collect::<Vec<&str>>();
-    let region_x = region_coordinates.get(1).unwrap().parse::<i32>().unwrap();
-    let region_z = region_coordinates.get(2).unwrap().parse::<i32>().unwrap();
-
-    let region = Region::from_region(&mut File::open(region)?, (region_x, region_z))?;
+    // the region coordinates are read from the "r.x.z.mca" file name
+    let region = Region::open(region)?;
 
     // searches through all chunks, one at a time.
     let mut found = None;
@@ -0,0 +1,440 @@
+//! `block_entity` contains functions to read & write the block entities within a [`Region`].
+//!
+//! Block entities holds the extra data some blocks needs, like the items in a chest or the text on a sign.
+
+use crate::{
+    BLOCKS_PER_REGION, Block, ChunkData, Coords, Error, Region, Result, nbt::set_tag,
+    region::is_valid_chunk,
+};
+use simdnbt::owned::{NbtCompound, NbtList, NbtTag};
+
+impl Region {
+    /// Returns the block entity at the specified coordinates *(local to within the region)*.
+    ///
+    /// Only block entities that have been written to the chunk NBT are returned.
+    ///
+    /// ## Example
+    /// ```
+    /// # use silverfish::Region;
+    /// # let region = Region::default();
+    /// let chest = region.get_block_entity((5, 97, 385))?;
+    /// assert!(chest.is_none());
+    /// # Ok::<(), silverfish::Error>(())
+    /// ```
+    pub fn get_block_entity<C: Into<Coords>>(&self, coords: C) -> Result<Option<NbtCompound>> {
+        let coords: Coords = coords.into();
+        let position = self.global_block_position(&coords);
+        let chunk = match self.get_chunk(
+            (coords.x / ChunkData::WIDTH as u32) as u8,
+            (coords.z / ChunkData::WIDTH as u32) as u8,
+        )? {
+            Some(chunk) => chunk,
+            None => return Ok(None),
+        };
+
+        let block_entities = match chunk.nbt.list("block_entities") {
+            Some(NbtList::Compound(c)) => c,
+            Some(NbtList::Empty) | None => return Ok(None),
+            Some(_) => return Err(Error::InvalidNbtList("block_entities")),
+        };
+
+        for block_entity in block_entities {
+            if block_entity_position(block_entity)? == position {
+                return Ok(Some(block_entity.clone()));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Sets the block entity at the specified coordinates *(local to within the region)*.
+    ///
+    /// The `x`, `y` & `z` tags are filled in with the global coordinates,
+    /// and if the NBT doesn't have an `id`, it's worked out from the block at those coordinates.
+    /// Replaces any block entity already at those coordinates.
+    ///
+    /// Writes the changes directly to the NBT.
+    /// A block written at the same coordinates via [`Region::write_blocks`] removes the block entity again,
+    /// use [`Region::set_block_with_entity`] to place both at once.
+    ///
+    /// ## Example
+    /// ```
+    /// # use silverfish::Region;
+    /// # use simdnbt::owned::{NbtCompound, NbtTag};
+    /// # let mut region = Region::default();
+    /// region.set_block((5, 97, 385), "minecraft:chest")?;
+    /// region.write_blocks()?;
+    ///
+    /// let lock = NbtCompound::from_values(vec![("CustomName".into(), NbtTag::String("\"Loot\"".into()))]);
+    /// region.set_block_entity((5, 97, 385), lock)?;
+    /// # Ok::<(), silverfish::Error>(())
+    /// ```
+    pub fn set_block_entity<C: Into<Coords>>(
+        &mut self,
+        coords: C,
+        block_entity: NbtCompound,
+    ) -> Result<()> {
+        let coords: Coords = coords.into();
+        let block_entity = self.fill_block_entity(&coords, block_entity, None)?;
+        let position = self.global_block_position(&coords);
+
+        let chunk_coords = (
+            (coords.x / ChunkData::WIDTH as u32) as u8,
+            (coords.z / ChunkData::WIDTH as u32) as u8,
+        );
+        let mut chunk = self.get_chunk_mut(chunk_coords.0, chunk_coords.1)?;
+        is_valid_chunk(&chunk.nbt, chunk_coords)?;
+
+        let block_entities = block_entities_mut(&mut chunk.nbt)?;
+        block_entities.retain(|be| block_entity_position(be).ok() != Some(position));
+        block_entities.push(block_entity);
+
+        Ok(())
+    }
+
+    /// Removes the block entity at the specified coordinates *(local to within the region)*, returning it if it existed.
+    ///
+    /// Writes the changes directly to the NBT.
+    pub fn remove_block_entity<C: Into<Coords>>(
+        &mut self,
+        coords: C,
+    ) -> Result<Option<NbtCompound>> {
+        let coords: Coords = coords.into();
+        let position = self.global_block_position(&coords);
+
+        let chunk_coords = (
+            (coords.x / ChunkData::WIDTH as u32) as u8,
+            (coords.z / ChunkData::WIDTH as u32) as u8,
+        );
+
+        // only get it mutably if it exists, so missing chunks aren't created
+        if self.get_chunk(chunk_coords.0, chunk_coords.1)?.is_none() {
+            return Ok(None);
+        }
+        let mut chunk = self.get_chunk_mut_unmarked(chunk_coords.0, chunk_coords.1)?;
+
+        let block_entities = match chunk.nbt.list_mut("block_entities") {
+            Some(NbtList::Compound(c)) => c,
+            Some(NbtList::Empty) | None => return Ok(None),
+            Some(_) => return Err(Error::InvalidNbtList("block_entities")),
+        };
+
+        for i in 0..block_entities.len() {
+            if block_entity_position(&block_entities[i])? == position {
+                let block_entity = block_entities.remove(i);
+                chunk.mark_modified();
+                return Ok(Some(block_entity));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Set a block together with it's block entity at the specified coordinates *(local to within the region)*.
+    ///
+    /// Works just like [`Region::set_block`], both the block and the block entity are written on [`Region::write_blocks`].
+    /// The block entity gets it's `x`, `y`, `z` & `id` filled in like with [`Region::set_block_entity`].
+    ///
+    /// Returns [`None`] if a buffered block already exists at those coordinates.
+    ///
+    /// ## Example
+    /// ```
+    /// # use silverfish::{Block, Region};
+    /// # use simdnbt::owned::{NbtCompound, NbtTag};
+    /// # let mut region = Region::default();
+    /// let text = NbtCompound::from_values(vec![("is_waxed".into(), NbtTag::Byte(1))]);
+    /// region.set_block_with_entity((5, 97, 385), Block::new("oak_sign"), text)?;
+    /// region.write_blocks()?;
+    ///
+    /// let sign = region.get_block_entity((5, 97, 385))?.unwrap();
+    /// assert_eq!(sign.string("id").unwrap().to_str(), "minecraft:sign");
+    /// # Ok::<(), silverfish::Error>(())
+    /// ```
+    pub fn set_block_with_entity<C: Into<Coords>, B: Into<Block>>(
+        &mut self,
+        coords: C,
+        block: B,
+        block_entity: NbtCompound,
+    ) -> Result<Option<()>> {
+        let coords: Coords = coords.into();
+        let block: Block = block.into();
+        let block_entity = self.fill_block_entity(&coords, block_entity, Some(&block))?;
+
+        if self.set_block(coords, block)?.is_none() {
+            return Ok(None);
+        }
+
+        let mut chunk = self.get_chunk_mut_unmarked(
+            (coords.x / ChunkData::WIDTH as u32) as u8,
+            (coords.z / ChunkData::WIDTH as u32) as u8,
+        )?;
+        chunk.pending_block_entities.push(block_entity);
+
+        Ok(Some(()))
+    }
+
+    /// Fills in the `x`, `y`, `z` & `id` tags of a block entity.
+    ///
+    /// The `id` is only set if missing, taken from `block` or the block at the coordinates.
+    fn fill_block_entity(
+        &self,
+        coords: &Coords,
+        mut block_entity: NbtCompound,
+        block: Option<&Block>,
+    ) -> Result<NbtCompound> {
+        let (x, y, z) = self.global_block_position(coords);
+        set_tag(&mut block_entity, "x", NbtTag::Int(x));
+        set_tag(&mut block_entity, "y", NbtTag::Int(y));
+        set_tag(&mut block_entity, "z", NbtTag::Int(z));
+
+        if block_entity.string("id").is_none() {
+            let block = match block {
+                Some(block) => block.clone(),
+                None => self.get_block(*coords)?,
+            };
+            let name = block.name.to_str();
+            let id = block_entity_id(&name).ok_or(Error::UnknownBlockEntity(name.to_string()))?;
+            set_tag(&mut block_entity, "id", NbtTag::String(id.into()));
+        }
+
+        Ok(block_entity)
+    }
+
+    /// Converts coordinates local to this region into global block coordinates.
+    pub(crate) fn global_block_position(&self, coords: &Coords) -> (i32, i32, i32) {
+        (
+            self.region_coords.0 * BLOCKS_PER_REGION as i32 + coords.x as i32,
+            coords.y,
+            self.region_coords.1 * BLOCKS_PER_REGION as i32 + coords.z as i32,
+        )
+    }
+}
+
+/// Returns the global `x`, `y` & `z` of a block entity.
+pub(crate) fn block_entity_position(block_entity: &NbtCompound) -> Result<(i32, i32, i32)> {
+    Ok((
+        block_entity.int("x").ok_or(Error::MissingNbtTag("x"))?,
+        block_entity.int("y").ok_or(Error::MissingNbtTag("y"))?,
+        block_entity.int("z").ok_or(Error::MissingNbtTag("z"))?,
+    ))
+}
+
+/// Returns the chunk's block entities mutably.
+///
+/// An empty list is turned into a compound list first, since an empty list can't be pushed to.
+pub(crate) fn block_entities_mut(chunk: &mut NbtCompound) -> Result<&mut Vec<NbtCompound>> {
+    let list = chunk
+        .list_mut("block_entities")
+        .ok_or(Error::MissingNbtTag("block_entities"))?;
+    if matches!(list, NbtList::Empty) {
+        *list = NbtList::Compound(vec![]);
+    }
+
+    match list {
+        NbtList::Compound(c) => Ok(c),
+        _ => Err(Error::InvalidNbtList("block_entities")),
+    }
+}
+
+/// Returns the block entity id Minecraft uses for a block, if the block has a block entity.
+pub(crate) fn block_entity_id(name: &str) -> Option<&'static str> {
+    let id = name.strip_prefix("minecraft:").unwrap_or(name);
+    let block_entity = match id {
+        "chest" => "minecraft:chest",
+        "trapped_chest" => "minecraft:trapped_chest",
+        "ender_chest" => "minecraft:ender_chest",
+        "furnace" => "minecraft:furnace",
+        "blast_furnace" => "minecraft:blast_furnace",
+        "smoker" => "minecraft:smoker",
+        "barrel" => "minecraft:barrel",
+        "hopper" => "minecraft:hopper",
+        "dispenser" => "minecraft:dispenser",
+        "dropper" => "minecraft:dropper",
+        "crafter" => "minecraft:crafter",
+        "brewing_stand" => "minecraft:brewing_stand",
+        "beacon" => "minecraft:beacon",
+        "conduit" => "minecraft:conduit",
+        "enchanting_table" => "minecraft:enchanting_table",
+        "spawner" => "minecraft:mob_spawner",
+        "trial_spawner" => "minecraft:trial_spawner",
+        "vault" => "minecraft:vault",
+        "jukebox" => "minecraft:jukebox",
+        "lectern" => "minecraft:lectern",
+        "bell" => "minecraft:bell",
+        "beehive" | "bee_nest" => "minecraft:beehive",
+        "campfire" | "soul_campfire" => "minecraft:campfire",
+        "daylight_detector" => "minecraft:daylight_detector",
+        "comparator" => "minecraft:comparator",
+        "command_block" | "chain_command_block" | "repeating_command_block" => {
+            "minecraft:command_block"
+        }
+        "structure_block" => "minecraft:structure_block",
+        "jigsaw" => "minecraft:jigsaw",
+        "end_gateway" => "minecraft:end_gateway",
+        "end_portal" => "minecraft:end_portal",
+        "moving_piston" => "minecraft:piston",
+        "sculk_sensor" => "minecraft:sculk_sensor",
+        "calibrated_sculk_sensor" => "minecraft:calibrated_sculk_sensor",
+        "sculk_catalyst" => "minecraft:sculk_catalyst",
+        "sculk_shrieker" => "minecraft:sculk_shrieker",
+        "chiseled_bookshelf" => "minecraft:chiseled_bookshelf",
+        "decorated_pot" => "minecraft:decorated_pot",
+        "suspicious_sand" | "suspicious_gravel" => "minecraft:brushable_block",
+        "creaking_heart" => "minecraft:creaking_heart",
+        "shulker_box" => "minecraft:shulker_box",
+        "skeleton_skull"
+        | "skeleton_wall_skull"
+        | "wither_skeleton_skull"
+        | "wither_skeleton_wall_skull"
+        | "zombie_head"
+        | "zombie_wall_head"
+        | "player_head"
+        | "player_wall_head"
+        | "creeper_head"
+        | "creeper_wall_head"
+        | "dragon_head"
+        | "dragon_wall_head"
+        | "piglin_head"
+        | "piglin_wall_head" => "minecraft:skull",
+        id if id.contains(':') => return None,
+        id if id.ends_with("_hanging_sign") => "minecraft:hanging_sign",
+        id if id.ends_with("_sign") => "minecraft:sign",
+        id if id.ends_with("_bed") => "minecraft:bed",
+        id if id.ends_with("_banner") => "minecraft:banner",
+        id if id.ends_with("_shulker_box") => "minecraft:shulker_box",
+        _ => return None,
+    };
+
+    Some(block_entity)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn chest_items(count: i8) -> NbtCompound {
+        let item = NbtCompound::from_values(vec![
+            ("id".into(), NbtTag::String("minecraft:diamond".into())),
+            ("count".into(), NbtTag::Byte(count)),
+            ("Slot".into(), NbtTag::Byte(0)),
+        ]);
+        NbtCompound::from_values(vec![(
+            "Items".into(),
+            NbtTag::List(NbtList::Compound(vec![item])),
+        )])
+    }
+
+    #[test]
+    fn block_entity_ids() {
+        assert_eq!(block_entity_id("minecraft:chest"), Some("minecraft:chest"));
+        assert_eq!(block_entity_id("oak_wall_sign"), Some("minecraft:sign"));
+        assert_eq!(
+            block_entity_id("minecraft:cherry_hanging_sign"),
+            Some("minecraft:hanging_sign")
+        );
+        assert_eq!(
+            block_entity_id("minecraft:zombie_head"),
+            Some("minecraft:skull")
+        );
+        assert_eq!(
+            block_entity_id("minecraft:wither_skeleton_wall_skull"),
+            Some("minecraft:skull")
+        );
+        assert_eq!(block_entity_id("minecraft:piston_head"), None);
+        assert_eq!(block_entity_id("minecraft:stone"), None);
+        assert_eq!(block_entity_id("custom:chest"), None);
+    }
+
+    #[test]
+    fn set_and_get_block_entity() -> Result<()> {
+        let mut region = Region::full_empty((-1, 2));
+        region.set_block((3, 64, 20), "minecraft:chest")?;
+        region.write_blocks()?;
+
+        region.set_block_entity((3, 64, 20), chest_items(5))?;
+        let chest = region.get_block_entity((3, 64, 20))?.unwrap();
+        assert_eq!(chest.string("id").unwrap().to_str(), "minecraft:chest");
+        assert_eq!(chest.int("x"), Some(-509));
+        assert_eq!(chest.int("y"), Some(64));
+        assert_eq!(chest.int("z"), Some(1044));
+
+        // replaces the old one
+        region.set_block_entity((3, 64, 20), chest_items(9))?;
+        let chest = region.get_block_entity((3, 64, 20))?.unwrap();
+        let items = chest.list("Items").unwrap().compounds().unwrap();
+        assert_eq!(items[0].byte("count"), Some(9));
+
+        assert!(region.remove_block_entity((3, 64, 20))?.is_some());
+        assert!(region.get_block_entity((3, 64, 20))?.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_block_entity() {
+        let mut region = Region::default();
+        assert!(
+            region
+                .set_block_entity((3, 64, 20), NbtCompound::new())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn piston_head_has_no_block_entity() -> Result<()> {
+        let mut region = Region::default();
+        let piston_head = Block::new("minecraft:piston_head");
+        assert!(
+            region
+                .set_block_with_entity((40, -20, 40), piston_head, NbtCompound::new())
+                .is_err()
+        );
+
+        region.write_blocks()?;
+        assert!(region.get_block_entity((40, -20, 40))?.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn set_block_with_entity() -> Result<()> {
+        let mut region = Region::default();
+        region.set_block_with_entity((40, -20, 40), "minecraft:barrel", chest_items(1))?;
+        assert!(region.get_block_entity((40, -20, 40))?.is_none());
+
+        region.write_blocks()?;
+        assert_eq!(
+            region.get_block((40, -20, 40))?,
+            Block::new("minecraft:barrel")
+        );
+        let barrel = region.get_block_entity((40, -20, 40))?.unwrap();
+        assert_eq!(barrel.string("id").unwrap().to_str(), "minecraft:barrel");
+
+        // writing another block over it removes the block entity
+        region.set_block((40, -20, 40), "minecraft:stone")?;
+        region.write_blocks()?;
+        assert!(region.get_block_entity((40, -20, 40))?.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn only_removes_block_entities_at_written_blocks() -> Result<()> {
+        let mut region = Region::default();
+        region.set_block_with_entity((1, 10, 1), "minecraft:chest", chest_items(1))?;
+        region.set_block_with_entity((1, 42, 1), "minecraft:chest", chest_items(2))?;
+        region.write_blocks()?;
+
+        // same x/z and same y within the section, but a different section
+        region.set_block((1, 26, 1), "minecraft:stone")?;
+        region.write_blocks()?;
+        assert!(region.get_block_entity((1, 10, 1))?.is_some());
+        assert!(region.get_block_entity((1, 42, 1))?.is_some());
+
+        region.set_section((0, 0), 2, "minecraft:stone")?;
+        assert!(region.get_block_entity((1, 10, 1))?.is_some());
+        assert!(region.get_block_entity((1, 42, 1))?.is_none());
+
+        Ok(())
+    }
+}
@@ -4,6 +4,7 @@
 
 use crate::{
     BiomeCell, Block, BlockWithCoordinate, Coords, NbtString, Result, biome::BiomeCellWithId,
+    Compression, poi::PoiChange, region::read_chunk_nbt, region_file::RawChunk,
 };
 use ahash::AHashMap;
 use fixedbitset::FixedBitSet;
@@ -42,6 +43,21 @@ pub struct ChunkData {
     pub(crate) dirty_blocks: bool,
     /// If this is unmarked, the biome write logic will skip this one.  
     pub(crate) dirty_biomes: bool,
+
+    /// The chunk exactly as it was read from the region file.  
+    ///
+    /// Kept until the chunk gets modified so it can be written back without re-encoding it.  
+    pub(crate) raw: Option<RawChunk>,
+    /// The compression the chunk was read with, `None` for new chunks.  
+    pub(crate) compression: Option<Compression>,
+    /// When the chunk was last saved according to the region file header, `0` for new chunks.  
+    pub(crate) timestamp: u32,
+
+    /// Point of interest changes made while writing blocks,
+    /// waiting to be applied to the region's [`PoiRegion`](crate::PoiRegion).  
+    pub(crate) poi_changes: Vec<PoiChange>,
+    /// Block entities set together with their block, added to the NBT on the next block write.  
+    pub(crate) pending_block_entities: Vec<NbtCompound>,
 }
 
 impl ChunkData {
@@ -184,6 +200,53 @@ impl ChunkData {
             seen_biomes: ChunkData::biome_bitset(world_height_count),
             dirty_blocks: false,
             dirty_biomes: false,
+            raw: None,
+            compression: None,
+            timestamp: 0,
+            poi_changes: Vec::new(),
+            pending_block_entities: Vec::new(),
         }
     }
+
+    /// Creates a new [`ChunkData`] from a chunk read from a region file, keeping the original bytes around.  
+    pub(crate) fn from_raw(raw: RawChunk, world_height: Range<isize>) -> Result<ChunkData> {
+        let nbt = read_chunk_nbt(&raw.decompress()?)?;
+        let mut chunk = ChunkData::new(nbt, world_height);
+        chunk.compression = Some(raw.compression);
+        chunk.timestamp = raw.timestamp;
+        chunk.raw = Some(raw);
+
+        Ok(chunk)
+    }
+
+    /// Marks the chunk as modified, so it gets re-encoded on [`Region::write`](crate::Region::write).  
+    ///
+    /// Chunks that were never modified are written back exactly as they were read.  
+    /// This is done automatically by all write functions & [`Region::get_chunk_mut`](crate::Region::get_chunk_mut),
+    /// so you only need this if you modify [`ChunkData::nbt`] through [`Region::chunks`](crate::Region::chunks).  
+    pub fn mark_modified(&mut self) {
+        self.raw = None;
+    }
+
+    /// Returns the [`Compression`] the chunk was read with.  
+    ///
+    /// `None` if the chunk wasn't read from a region file.  
+    pub fn compression(&self) -> Option<Compression> {
+        self.compression
+    }
+
+    /// Returns when the chunk was last saved, in seconds since the unix epoch.  
+    ///
+    /// This is the timestamp from the region file header and is `0` for chunks that wasn't read from one.  
+    /// Modified chunks gets their timestamp set to the current time when the region is written.  
+    pub fn timestamp(&self) -> u32 {
+        self.timestamp
+    }
+
+    /// Returns `true` if the chunk has been modified since it was read from the region file.  
+    ///
+    /// Chunks that wasn't read from a region file are always modified.  
+    pub fn is_modified(&self) -> bool {
+        self.raw.is_none()
+    }
 }
@@ -0,0 +1,843 @@
+//! `clipboard` contains the [`Clipboard`], which copies a cuboid out of a [`Region`] to paste it somewhere else.
+
+use crate::{
+    BLOCKS_PER_REGION, BiomeCell, Block, ChunkData, Coords, Error, Mirror, NbtString, Region,
+    Result, Rotation, World,
+    block_entity::block_entity_position,
+    data::decode_data,
+    heightmap::is_air,
+    legacy::{rename_block, renamed_since},
+    region::get_block_bit_count,
+    to_region_coords, to_region_local,
+};
+use ahash::AHashMap;
+use simdnbt::owned::{NbtCompound, NbtList};
+
+/// A cuboid of blocks, biomes & block entities copied from a [`Region`].
+///
+/// All coordinates within the clipboard are relative to it's smallest corner.
+#[derive(Debug, Clone)]
+pub struct Clipboard {
+    pub(crate) size: (u32, u32, u32),
+    pub(crate) data_version: i32,
+    pub(crate) palette: Vec<Block>,
+    /// Indexes into `palette`, ordered `y`, `z` then `x`
+    pub(crate) blocks: Vec<u32>,
+    /// One biome per `4x4x4` cell, starting from the smallest corner, empty if the clipboard has no biomes
+    pub(crate) biomes: Vec<NbtString>,
+    pub(crate) block_entities: AHashMap<(u32, u32, u32), NbtCompound>,
+}
+
+/// How a [`Clipboard`] is placed with [`Clipboard::paste`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PasteOptions {
+    /// Rotates the clipboard around it's smallest corner, it still fills the area starting at the paste coordinates.
+    pub rotation: Rotation,
+    /// Mirrors the clipboard, this is done before rotating.
+    pub mirror: Mirror,
+    /// If air in the clipboard should be skipped, keeping the blocks that are already there.
+    pub skip_air: bool,
+}
+
+impl PasteOptions {
+    /// If the block shouldn't be pasted at all.
+    pub(crate) fn skips(&self, block: &Block) -> bool {
+        let name = block.name.to_str();
+        self.skip_air && is_air(name.strip_prefix("minecraft:").unwrap_or(&name))
+    }
+}
+
+impl Clipboard {
+    /// Copies the cuboid between two corners *(inclusive & local to within the region)*.
+    ///
+    /// Sections that are missing from a chunk are copied as air.
+    ///
+    /// ## Example
+    /// ```
+    /// # use silverfish::{Clipboard, Region};
+    /// # let region = Region::default();
+    /// let clipboard = Clipboard::copy(&region, (10, 64, 10), (20, 70, 15))?;
+    /// assert_eq!(clipboard.size(), (11, 7, 6));
+    /// # Ok::<(), silverfish::Error>(())
+    /// ```
+    pub fn copy<C: Into<Coords>>(region: &Region, from: C, to: C) -> Result<Self> {
+        let (from, to): (Coords, Coords) = (from.into(), to.into());
+        let min = Coords::new(from.x.min(to.x), from.y.min(to.y), from.z.min(to.z));
+        let max = Coords::new(from.x.max(to.x), from.y.max(to.y), from.z.max(to.z));
+        if max.x >= BLOCKS_PER_REGION || max.z >= BLOCKS_PER_REGION {
+            return Err(Error::CoordinatesOutOfRegionBounds(max.x, max.z));
+        }
+
+        let size = (
+            max.x - min.x + 1,
+            (max.y - min.y + 1) as u32,
+            max.z - min.z + 1,
+        );
+        let mut clipboard = Clipboard {
+            size,
+            data_version: Region::MIN_DATA_VERSION,
+            palette: vec![Block::new("minecraft:air")],
+            blocks: vec![0; (size.0 * size.1 * size.2) as usize],
+            biomes: vec![],
+            block_entities: AHashMap::new(),
+        };
+
+        let mut palette_lookup = AHashMap::new();
+        palette_lookup.insert(clipboard.palette[0].clone(), 0);
+        let width = ChunkData::WIDTH as u32;
+        for chunk_x in (min.x / width)..=(max.x / width) {
+            for chunk_z in (min.z / width)..=(max.z / width) {
+                let chunk = region
+                    .get_chunk(chunk_x as u8, chunk_z as u8)?
+                    .ok_or(Error::NoChunk(chunk_x as u8, chunk_z as u8))?;
+                if let Some(data_version) = chunk.nbt.int("DataVersion") {
+                    clipboard.data_version = clipboard.data_version.max(data_version);
+                }
+
+                clipboard.copy_chunk(
+                    &chunk.nbt,
+                    (chunk_x, chunk_z),
+                    (&min, &max),
+                    &mut palette_lookup,
+                )?;
+            }
+        }
+
+        clipboard.copy_biomes(region, &min)?;
+        clipboard.copy_block_entities(region, &min, &max)?;
+
+        Ok(clipboard)
+    }
+
+    /// Copies all blocks within a chunk that are inside the cuboid.
+    fn copy_chunk(
+        &mut self,
+        chunk: &NbtCompound,
+        (chunk_x, chunk_z): (u32, u32),
+        (min, max): (&Coords, &Coords),
+        palette_lookup: &mut AHashMap<Block, u32>,
+    ) -> Result<()> {
+        let width = ChunkData::WIDTH as u32;
+        let sections = chunk
+            .list("sections")
+            .ok_or(Error::MissingNbtTag("sections"))?
+            .compounds()
+            .ok_or(Error::InvalidNbtType("sections"))?;
+
+        // the part of the cuboid within this chunk
+        let x_range = min.x.max(chunk_x * width)..=max.x.min(chunk_x * width + width - 1);
+        let z_range = min.z.max(chunk_z * width)..=max.z.min(chunk_z * width + width - 1);
+
+        let mut indexes: [i64; Region::BLOCK_DATA_LEN] = [0; Region::BLOCK_DATA_LEN];
+
+        for section in sections {
+            let section_y = section.byte("Y").ok_or(Error::MissingNbtTag("Y"))? as i32;
+            let bottom = section_y * width as i32;
+            let y_range = min.y.max(bottom)..=max.y.min(bottom + width as i32 - 1);
+            if y_range.is_empty() {
+                continue;
+            }
+
+            let state = section
+                .compound("block_states")
+                .ok_or(Error::MissingNbtTag("block_states"))?;
+            let palette = state
+                .list("palette")
+                .ok_or(Error::MissingNbtTag("palette"))?
+                .compounds()
+                .ok_or(Error::InvalidNbtType("palette"))?;
+
+            // maps the section palette onto the clipboard palette
+            let mut section_palette = Vec::with_capacity(palette.len());
+            for block in palette {
+                let block = Block::from_compound(block)?;
+                let next = self.palette.len() as u32;
+                let index = *palette_lookup.entry(block.clone()).or_insert_with(|| {
+                    self.palette.push(block);
+                    next
+                });
+                section_palette.push(index);
+            }
+
+            let _ = decode_data(
+                &mut indexes,
+                get_block_bit_count(palette.len()),
+                state.long_array("data"),
+            );
+
+            for y in y_range {
+                for z in z_range.clone() {
+                    for x in x_range.clone() {
+                        let section_index =
+                            (x % width) + (z % width) * width + (y - bottom) as u32 * width * width;
+                        let palette_index = indexes[section_index as usize];
+                        let block = *section_palette
+                            .get(palette_index as usize)
+                            .ok_or(Error::InvalidPaletteIndex(palette_index))?;
+
+                        let index = self.index((x - min.x, (y - min.y) as u32, z - min.z));
+                        self.blocks[index] = block;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Copies one biome for every `4x4x4` cell of the cuboid.
+    fn copy_biomes(&mut self, region: &Region, min: &Coords) -> Result<()> {
+        let cell_size = BiomeCell::CELL_SIZE as u32;
+        let cells = self.biome_size();
+
+        let mut samples = Vec::with_capacity((cells.0 * cells.1 * cells.2) as usize);
+        for y in 0..cells.1 {
+            for z in 0..cells.2 {
+                for x in 0..cells.0 {
+                    samples.push(BiomeCell::from_coordinates(Coords::new(
+                        min.x + x * cell_size,
+                        min.y + (y * cell_size) as i32,
+                        min.z + z * cell_size,
+                    )));
+                }
+            }
+        }
+
+        let found: AHashMap<BiomeCell, NbtString> = region
+            .get_biomes(samples.clone())?
+            .into_iter()
+            .map(|b| (b.cell, b.id))
+            .collect();
+        self.biomes = samples
+            .iter()
+            .map(|cell| found.get(cell).cloned())
+            .collect::<Option<_>>()
+            .ok_or(Error::MissingNbtTag("biomes"))?;
+
+        Ok(())
+    }
+
+    /// Copies all block entities that are inside the cuboid.
+    fn copy_block_entities(&mut self, region: &Region, min: &Coords, max: &Coords) -> Result<()> {
+        let (min_x, min_y, min_z) = region.global_block_position(min);
+        let (max_x, max_y, max_z) = region.global_block_position(max);
+
+        let width = ChunkData::WIDTH as u32;
+        for chunk_x in (min.x / width)..=(max.x / width) {
+            for chunk_z in (min.z / width)..=(max.z / width) {
+                let chunk = region
+                    .get_chunk(chunk_x as u8, chunk_z as u8)?
+                    .ok_or(Error::NoChunk(chunk_x as u8, chunk_z as u8))?;
+
+                let block_entities = match chunk.nbt.list("block_entities") {
+                    Some(NbtList::Compound(c)) => c,
+                    Some(NbtList::Empty) | None => continue,
+                    Some(_) => return Err(Error::InvalidNbtList("block_entities")),
+                };
+
+                for block_entity in block_entities {
+                    let (x, y, z) = block_entity_position(block_entity)?;
+                    if (min_x..=max_x).contains(&x)
+                        && (min_y..=max_y).contains(&y)
+                        && (min_z..=max_z).contains(&z)
+                    {
+                        self.block_entities.insert(
+                            ((x - min_x) as u32, (y - min_y) as u32, (z - min_z) as u32),
+                            block_entity.clone(),
+                        );
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Pastes the clipboard with it's smallest corner at `to` *(local to within the region)*.
+    ///
+    /// The blocks & biomes are set like with [`Region::set_block`] & [`Region::set_biome`],
+    /// so call [`Region::write_blocks`] & [`Region::write_biomes`] afterwards.
+    /// Block entities are pasted together with their block like with [`Region::set_block_with_entity`].
+    /// Blocks renamed since the clipboard's [`Clipboard::data_version`] get their name in the chunk they're pasted into.
+    ///
+    /// ## Example
+    /// ```
+    /// # use silverfish::{Block, Clipboard, PasteOptions, Region, Rotation};
+    /// # let mut region = Region::default();
+    /// region.set_block((10, 64, 10), Block::new_with_props("oak_stairs", [("facing", "north")]))?;
+    /// region.write_blocks()?;
+    ///
+    /// let clipboard = Clipboard::copy(&region, (10, 64, 10), (12, 64, 10))?;
+    /// let options = PasteOptions {
+    ///     rotation: Rotation::Clockwise90,
+    ///     ..Default::default()
+    /// };
+    /// clipboard.paste(&mut region, (100, 64, 100), options)?;
+    /// region.write_blocks()?;
+    /// region.write_biomes()?;
+    ///
+    /// let stairs = region.get_block((100, 64, 100))?;
+    /// assert_eq!(stairs, Block::new_with_props("minecraft:oak_stairs", [("facing", "east")]));
+    /// # Ok::<(), silverfish::Error>(())
+    /// ```
+    pub fn paste<C: Into<Coords>>(
+        &self,
+        region: &mut Region,
+        to: C,
+        options: PasteOptions,
+    ) -> Result<()> {
+        let to: Coords = to.into();
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        let size = self.pasted_size(options.rotation);
+        let max = (to.x + size.0 - 1, to.z + size.2 - 1);
+        if max.0 >= BLOCKS_PER_REGION || max.1 >= BLOCKS_PER_REGION {
+            return Err(Error::CoordinatesOutOfRegionBounds(max.0, max.1));
+        }
+
+        self.paste_into(region, (to.x as i32, to.y, to.z as i32), options)
+    }
+
+    /// Pastes the clipboard with it's smallest corner at `to` *(global coordinates)*.
+    ///
+    /// Works just like [`Clipboard::paste`] but across region borders,
+    /// call [`World::write_blocks`] & [`World::write_biomes`] or [`World::save`] afterwards.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// # use silverfish::{Clipboard, PasteOptions, Region, World};
+    /// # let region = Region::default();
+    /// let mut world = World::open("saves/New World")?;
+    /// let clipboard = Clipboard::copy(&region, (0, 64, 0), (40, 90, 40))?;
+    /// clipboard.paste_world(&mut world, (-500, 64, 1200), PasteOptions::default())?;
+    /// world.save()?;
+    /// # Ok::<(), silverfish::Error>(())
+    /// ```
+    pub fn paste_world(
+        &self,
+        world: &mut World,
+        to: (i32, i32, i32),
+        options: PasteOptions,
+    ) -> Result<()> {
+        self.paste_into(world, to, options)
+    }
+
+    pub(crate) fn paste_into<T: PasteTarget>(
+        &self,
+        target: &mut T,
+        to: (i32, i32, i32),
+        options: PasteOptions,
+    ) -> Result<()> {
+        // there's no corner to paste at if any side is 0 long
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        let palette: Vec<Block> = self
+            .palette
+            .iter()
+            .map(|b| b.clone().mirror(options.mirror).rotate(options.rotation))
+            .collect();
+
+        for y in 0..self.size.1 {
+            for z in 0..self.size.2 {
+                for x in 0..self.size.0 {
+                    let block = &palette[self.blocks[self.index((x, y, z))] as usize];
+                    if options.skips(block) {
+                        continue;
+                    }
+
+                    let (dest_x, dest_z) = transform_xz((x, z), self.size, options)?;
+                    target.paste_block(
+                        (to.0 + dest_x as i32, to.1 + y as i32, to.2 + dest_z as i32),
+                        block.clone(),
+                        self.block_entities.get(&(x, y, z)).cloned(),
+                        self.data_version,
+                    )?;
+                }
+            }
+        }
+
+        self.paste_biomes(target, to, options)
+    }
+
+    /// Sets the biome of every cell the pasted clipboard touches, from the copied cell closest to it's center.
+    fn paste_biomes<T: PasteTarget>(
+        &self,
+        target: &mut T,
+        to: (i32, i32, i32),
+        options: PasteOptions,
+    ) -> Result<()> {
+        if self.biomes.is_empty() {
+            return Ok(());
+        }
+
+        let cell_size = BiomeCell::CELL_SIZE as i64;
+        let size = self.pasted_size(options.rotation);
+        let min = (to.0 as i64, to.1 as i64, to.2 as i64);
+        let max = (
+            min.0 + size.0 as i64 - 1,
+            min.1 + size.1 as i64 - 1,
+            min.2 + size.2 as i64 - 1,
+        );
+
+        let aligned = |v: i64| v.div_euclid(cell_size) * cell_size;
+        for cell_y in (aligned(min.1)..=max.1).step_by(cell_size as usize) {
+            for cell_z in (aligned(min.2)..=max.2).step_by(cell_size as usize) {
+                for cell_x in (aligned(min.0)..=max.0).step_by(cell_size as usize) {
+                    let center = |cell: i64, min: i64, max: i64| {
+                        ((cell + cell_size / 2).clamp(min, max) - min) as u32
+                    };
+                    let (x, z) = inverse_transform_xz(
+                        (center(cell_x, min.0, max.0), center(cell_z, min.2, max.2)),
+                        self.size,
+                        options,
+                    )?;
+                    let y = center(cell_y, min.1, max.1);
+
+                    let biome = self.get_biome((x, y, z)).ok_or(Error::OutOfBounds {
+                        len: self.biomes.len(),
+                        index: (x + z * self.size.0 + y * self.size.0 * self.size.2) as usize,
+                    })?;
+                    target.paste_biome(
+                        (cell_x as i32, cell_y as i32, cell_z as i32),
+                        biome.clone(),
+                    )?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the size of the clipboard as `(x, y, z)`.
+    pub fn size(&self) -> (u32, u32, u32) {
+        self.size
+    }
+
+    /// Returns `true` if the clipboard has no blocks, which is the case if any side is 0 long.
+    pub fn is_empty(&self) -> bool {
+        self.size.0 == 0 || self.size.1 == 0 || self.size.2 == 0
+    }
+
+    /// The `DataVersion` of the chunks the clipboard was copied from.
+    pub fn data_version(&self) -> i32 {
+        self.data_version
+    }
+
+    /// Returns the size the clipboard takes up when pasted with a rotation.
+    pub fn pasted_size(&self, rotation: Rotation) -> (u32, u32, u32) {
+        rotated_size(self.size, rotation)
+    }
+
+    /// Returns the block at the coordinates *(relative to the clipboard's smallest corner)*.
+    pub fn get_block(&self, (x, y, z): (u32, u32, u32)) -> Option<&Block> {
+        if x >= self.size.0 || y >= self.size.1 || z >= self.size.2 {
+            return None;
+        }
+        Some(&self.palette[self.blocks[self.index((x, y, z))] as usize])
+    }
+
+    /// Returns the block entity at the coordinates *(relative to the clipboard's smallest corner)*.
+    ///
+    /// The `x`, `y` & `z` tags are still the ones from where it was copied, they're replaced when pasted.
+    pub fn get_block_entity(&self, coords: (u32, u32, u32)) -> Option<&NbtCompound> {
+        self.block_entities.get(&coords)
+    }
+
+    /// Returns the biome at the coordinates *(relative to the clipboard's smallest corner)*.
+    pub fn get_biome(&self, (x, y, z): (u32, u32, u32)) -> Option<&NbtString> {
+        let cell_size = BiomeCell::CELL_SIZE as u32;
+        let cells = self.biome_size();
+        let (x, y, z) = (x / cell_size, y / cell_size, z / cell_size);
+        if x >= cells.0 || y >= cells.1 || z >= cells.2 {
+            return None;
+        }
+        self.biomes
+            .get((x + z * cells.0 + y * cells.0 * cells.2) as usize)
+    }
+
+    pub(crate) fn index(&self, (x, y, z): (u32, u32, u32)) -> usize {
+        (x + z * self.size.0 + y * self.size.0 * self.size.2) as usize
+    }
+
+    pub(crate) fn biome_size(&self) -> (u32, u32, u32) {
+        let cell_size = BiomeCell::CELL_SIZE as u32;
+        (
+            self.size.0.div_ceil(cell_size),
+            self.size.1.div_ceil(cell_size),
+            self.size.2.div_ceil(cell_size),
+        )
+    }
+}
+
+/// Something a [`Clipboard`] can be pasted into, with coordinates local to the target.
+pub(crate) trait PasteTarget {
+    /// Sets a block saved in `data_version`, renamed to it's name in the chunk it's pasted into.
+    fn paste_block(
+        &mut self,
+        coords: (i32, i32, i32),
+        block: Block,
+        block_entity: Option<NbtCompound>,
+        data_version: i32,
+    ) -> Result<()>;
+
+    fn paste_biome(&mut self, coords: (i32, i32, i32), biome: NbtString) -> Result<()>;
+}
+
+impl PasteTarget for Region {
+    fn paste_block(
+        &mut self,
+        coords: (i32, i32, i32),
+        block: Block,
+        block_entity: Option<NbtCompound>,
+        data_version: i32,
+    ) -> Result<()> {
+        let coords = Coords::new(coords.0 as u32, coords.1, coords.2 as u32);
+        let block = match renamed_since(&block, data_version) {
+            true => {
+                let chunk_version = self
+                    .get_chunk(
+                        (coords.x / ChunkData::WIDTH as u32) as u8,
+                        (coords.z / ChunkData::WIDTH as u32) as u8,
+                    )?
+                    .and_then(|chunk| chunk.nbt.int("DataVersion"))
+                    // new chunks are created with the oldest supported version
+                    .unwrap_or(Region::MIN_DATA_VERSION);
+                rename_block(block, data_version, chunk_version)
+            }
+            false => block,
+        };
+
+        match block_entity {
+            Some(block_entity) => self.set_block_with_entity(coords, block, block_entity)?,
+            None => self.set_block(coords, block)?,
+        };
+
+        Ok(())
+    }
+
+    fn paste_biome(&mut self, coords: (i32, i32, i32), biome: NbtString) -> Result<()> {
+        self.set_biome(
+            Coords::new(coords.0 as u32, coords.1, coords.2 as u32),
+            biome,
+        )?;
+        Ok(())
+    }
+}
+
+impl PasteTarget for World {
+    fn paste_block(
+        &mut self,
+        coords: (i32, i32, i32),
+        block: Block,
+        block_entity: Option<NbtCompound>,
+        data_version: i32,
+    ) -> Result<()> {
+        let local = to_region_local(coords);
+        self.get_region_mut(to_region_coords(coords))?.paste_block(
+            (local.x as i32, local.y, local.z as i32),
+            block,
+            block_entity,
+            data_version,
+        )
+    }
+
+    fn paste_biome(&mut self, coords: (i32, i32, i32), biome: NbtString) -> Result<()> {
+        self.set_biome(coords, biome)?;
+        Ok(())
+    }
+}
+
+/// Returns the size a cuboid of `size` takes up once rotated.
+pub(crate) fn rotated_size(size: (u32, u32, u32), rotation: Rotation) -> (u32, u32, u32) {
+    match rotation.quarter_turns() % 2 {
+        0 => size,
+        _ => (size.2, size.1, size.0),
+    }
+}
+
+/// Returns how many blocks a schematic of `size` contains.
+///
+/// Errors if any side is 0 long or if the blocks can't be indexed with a `u32`.
+pub(crate) fn schematic_volume(size: (u32, u32, u32)) -> Result<usize> {
+    if size.0 == 0 || size.1 == 0 || size.2 == 0 {
+        return Err(Error::InvalidSchematic(format!(
+            "the size {size:?} is empty"
+        )));
+    }
+
+    (size.0 as usize)
+        .checked_mul(size.1 as usize)
+        .and_then(|v| v.checked_mul(size.2 as usize))
+        .filter(|v| *v <= u32::MAX as usize)
+        .ok_or_else(|| Error::InvalidSchematic(format!("the size {size:?} is too large")))
+}
+
+/// Mirrors & then rotates `x` & `z` within a cuboid of `size`, so the result still starts at `0, 0`.
+///
+/// Errors if the coordinates are outside of the cuboid.
+pub(crate) fn transform_xz(
+    (x, z): (u32, u32),
+    size: (u32, u32, u32),
+    options: PasteOptions,
+) -> Result<(u32, u32)> {
+    if x >= size.0 || z >= size.2 {
+        return Err(Error::InvalidSchematic(format!(
+            "{:?} is outside of the size {size:?}",
+            (x, z)
+        )));
+    }
+    // can't underflow since both are within the size
+    let (flip_x, flip_z) = (|x: u32| size.0 - 1 - x, |z: u32| size.2 - 1 - z);
+
+    let (x, z) = match options.mirror {
+        Mirror::None => (x, z),
+        Mirror::LeftRight => (x, flip_z(z)),
+        Mirror::FrontBack => (flip_x(x), z),
+    };
+
+    Ok(match options.rotation {
+        Rotation::None => (x, z),
+        Rotation::Clockwise90 => (flip_z(z), x),
+        Rotation::Clockwise180 => (flip_x(x), flip_z(z)),
+        Rotation::CounterClockwise90 => (z, flip_x(x)),
+    })
+}
+
+/// The inverse of [`transform_xz`], turns pasted coordinates back into clipboard coordinates.
+fn inverse_transform_xz(
+    (x, z): (u32, u32),
+    size: (u32, u32, u32),
+    options: PasteOptions,
+) -> Result<(u32, u32)> {
+    let rotated = rotated_size(size, options.rotation);
+    let unrotate = PasteOptions {
+        rotation: Rotation::from_quarter_turns(4 - options.rotation.quarter_turns()),
+        mirror: Mirror::None,
+        skip_air: false,
+    };
+    let unmirror = PasteOptions {
+        rotation: Rotation::None,
+        ..options
+    };
+
+    transform_xz(transform_xz((x, z), rotated, unrotate)?, size, unmirror)
+}
+
+/// The region the schematic formats are tested with.
+///
+/// It has upside down stairs facing east at `0, 0, 0`, a chest named `"Loot"` at `1, 1, 1`
+/// and a desert around `4, 0, 0`.
+#[cfg(test)]
+pub(crate) fn test_region() -> Result<Region> {
+    use simdnbt::owned::NbtTag;
+
+    let mut region = Region::default();
+    region.set_block(
+        (0, 0, 0),
+        Block::new_with_props("oak_stairs", [("facing", "east"), ("half", "top")]),
+    )?;
+    let name = NbtCompound::from_values(vec![(
+        "CustomName".into(),
+        NbtTag::String("\"Loot\"".into()),
+    )]);
+    region.set_block_with_entity((1, 1, 1), Block::new("chest"), name)?;
+    region.set_biome((4, 0, 0), "minecraft:desert")?;
+    region.write_blocks()?;
+    region.write_biomes()?;
+
+    Ok(region)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn copy_paste() -> Result<()> {
+        let mut region = Region::default();
+        region.set_block((10, 64, 10), "minecraft:stone")?;
+        region.set_block((12, 65, 11), "minecraft:dirt")?;
+        region.write_blocks()?;
+
+        let clipboard = Clipboard::copy(&region, (12, 65, 11), (10, 64, 10))?;
+        assert_eq!(clipboard.size(), (3, 2, 2));
+        assert_eq!(
+            clipboard.get_block((0, 0, 0)),
+            Some(&Block::new("minecraft:stone"))
+        );
+        assert_eq!(
+            clipboard.get_block((2, 1, 1)),
+            Some(&Block::new("minecraft:dirt"))
+        );
+        assert_eq!(clipboard.get_block((3, 0, 0)), None);
+
+        let mut target = Region::default();
+        clipboard.paste(&mut target, (100, -10, 200), PasteOptions::default())?;
+        target.write_blocks()?;
+        assert_eq!(
+            target.get_block((100, -10, 200))?,
+            Block::new("minecraft:stone")
+        );
+        assert_eq!(
+            target.get_block((102, -9, 201))?,
+            Block::new("minecraft:dirt")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn paste_empty() -> Result<()> {
+        let clipboard = Clipboard {
+            size: (0, 2, 3),
+            data_version: Region::MIN_DATA_VERSION,
+            palette: vec![],
+            blocks: vec![],
+            biomes: vec![NbtString::from_str("minecraft:plains")?],
+            block_entities: AHashMap::new(),
+        };
+        assert!(clipboard.is_empty());
+
+        let mut region = Region::default();
+        for rotation in [Rotation::None, Rotation::Clockwise90] {
+            let options = PasteOptions {
+                rotation,
+                ..Default::default()
+            };
+            clipboard.paste(&mut region, (0, 0, 0), options)?;
+        }
+        region.write_blocks()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn paste_rotated_and_mirrored() -> Result<()> {
+        let mut region = Region::default();
+        region.set_block(
+            (0, 0, 0),
+            Block::new_with_props("minecraft:oak_stairs", [("facing", "north")]),
+        )?;
+        region.set_block((2, 0, 0), "minecraft:stone")?;
+        region.write_blocks()?;
+        let clipboard = Clipboard::copy(&region, (0, 0, 0), (2, 0, 1))?;
+        assert_eq!(clipboard.pasted_size(Rotation::Clockwise90), (2, 1, 3));
+
+        let mut target = Region::default();
+        let options = PasteOptions {
+            rotation: Rotation::Clockwise90,
+            ..Default::default()
+        };
+        clipboard.paste(&mut target, (50, 0, 50), options)?;
+        target.write_blocks()?;
+        // north west corner ends up in the north east corner
+        assert_eq!(
+            target.get_block((51, 0, 50))?,
+            Block::new_with_props("minecraft:oak_stairs", [("facing", "east")])
+        );
+        assert_eq!(
+            target.get_block((51, 0, 52))?,
+            Block::new("minecraft:stone")
+        );
+
+        let mut target = Region::default();
+        let options = PasteOptions {
+            mirror: Mirror::FrontBack,
+            ..Default::default()
+        };
+        clipboard.paste(&mut target, (50, 0, 50), options)?;
+        target.write_blocks()?;
+        assert_eq!(
+            target.get_block((52, 0, 50))?,
+            Block::new_with_props("minecraft:oak_stairs", [("facing", "north")])
+        );
+        assert_eq!(
+            target.get_block((50, 0, 50))?,
+            Block::new("minecraft:stone")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn transform_round_trip() {
+        let size = (5, 1, 3);
+        for rotation in [
+            Rotation::None,
+            Rotation::Clockwise90,
+            Rotation::Clockwise180,
+            Rotation::CounterClockwise90,
+        ] {
+            for mirror in [Mirror::None, Mirror::LeftRight, Mirror::FrontBack] {
+                let options = PasteOptions {
+                    rotation,
+                    mirror,
+                    skip_air: false,
+                };
+                for x in 0..size.0 {
+                    for z in 0..size.2 {
+                        let pasted = transform_xz((x, z), size, options).unwrap();
+                        assert_eq!(inverse_transform_xz(pasted, size, options).unwrap(), (x, z));
+                    }
+                }
+            }
+        }
+
+        // coordinates outside of the cuboid can't be flipped
+        assert!(transform_xz((5, 0), size, PasteOptions::default()).is_err());
+        assert!(transform_xz((0, 0), (0, 1, 0), PasteOptions::default()).is_err());
+    }
+
+    #[test]
+    fn skip_air() -> Result<()> {
+        let mut region = Region::default();
+        region.set_block((0, 0, 0), "minecraft:stone")?;
+        region.write_blocks()?;
+        let clipboard = Clipboard::copy(&region, (0, 0, 0), (1, 0, 0))?;
+
+        let mut target = Region::default();
+        target.set_block((21, 0, 20), "minecraft:gold_block")?;
+        target.write_blocks()?;
+
+        let options = PasteOptions {
+            skip_air: true,
+            ..Default::default()
+        };
+        clipboard.paste(&mut target, (20, 0, 20), options)?;
+        target.write_blocks()?;
+        assert_eq!(
+            target.get_block((20, 0, 20))?,
+            Block::new("minecraft:stone")
+        );
+        assert_eq!(
+            target.get_block((21, 0, 20))?,
+            Block::new("minecraft:gold_block")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn block_entities_and_biomes() -> Result<()> {
+        let clipboard = Clipboard::copy(&test_region()?, (0, 0, 0), (7, 3, 3))?;
+        assert!(clipboard.get_block_entity((1, 1, 1)).is_some());
+        assert_eq!(*clipboard.get_biome((4, 0, 0)).unwrap(), "minecraft:desert");
+
+        let mut target = Region::empty((1, 2));
+        clipboard.paste(&mut target, (40, 68, 40), PasteOptions::default())?;
+        target.write_blocks()?;
+        target.write_biomes()?;
+
+        let chest = target.get_block_entity((41, 69, 41))?.unwrap();
+        assert_eq!(chest.int("x"), Some(512 + 41));
+        assert_eq!(chest.int("z"), Some(1024 + 41));
+        assert_eq!(target.get_biome((44, 68, 40))?, "minecraft:desert");
+        assert_ne!(target.get_biome((40, 68, 40))?, "minecraft:desert");
+
+        Ok(())
+    }
+}
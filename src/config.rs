@@ -1,6 +1,7 @@
 //! `config` contains the [`Config`] used in [`crate::Region`].  
 
-use std::ops::Range;
+use crate::{BlockRegistry, Compression};
+use std::{ops::Range, sync::Arc};
 
 /// A config used for dictating how [`crate::Region`] should write blocks.  
 #[derive(Debug, Clone)]
@@ -11,6 +12,32 @@ pub struct Config {
     pub create_chunk_if_missing: bool,
     /// If it should flag the chunks for Minecraft to re-calculate lighting when first loaded ingame  
     pub update_lighting: bool,
+    /// Forces every chunk to be written with this [`Compression`].  
+    ///
+    /// If `None`, chunks keeps whatever compression they were read with  
+    /// and new chunks uses [`Compression::Zlib`].  
+    pub compression: Option<Compression>,
+    /// Keeps the points of interest *(beds, bells, workstations etc.)* in sync with the blocks that are written.  
+    ///
+    /// Needs a [`PoiRegion`](crate::PoiRegion) attached via [`Region::set_poi_region`](crate::Region::set_poi_region).  
+    pub update_poi: bool,
+    /// Recomputes the chunk heightmaps from the written blocks instead of clearing them.  
+    ///
+    /// Slower since every column has to be looked at, but the chunks are correct without Minecraft having to rebuild them.  
+    pub update_heightmaps: bool,
+    /// Computes the sky and block light of changed chunks after writing, instead of leaving it to Minecraft.  
+    ///
+    /// See [`Region::compute_lighting`](crate::Region::compute_lighting), light doesn't spread across region borders,
+    /// so chunks on the border are left for the game to relight.  
+    pub compute_lighting: bool,
+    /// Checks every block given to [`Region::set_block`](crate::Region::set_block) against this [`BlockRegistry`].  
+    ///
+    /// Unknown blocks, properties or values returns an error instead of being written to the palette.  
+    pub registry: Option<Arc<BlockRegistry>>,
+    /// Fills in any missing block properties from their default state in [`Config::registry`] before they're written.  
+    ///
+    /// Keeps the palette from having both `furnace` and `furnace[facing=north,lit=false]` for the same block.  
+    pub fill_default_properties: bool,
 
     /// How tall/deep the world is, defaults to `-64..=320`
     ///
@@ -23,6 +50,12 @@ impl Default for Config {
         Self {
             create_chunk_if_missing: false,
             update_lighting: true,
+            compression: None,
+            update_poi: false,
+            update_heightmaps: false,
+            compute_lighting: false,
+            registry: None,
+            fill_default_properties: false,
             world_height: Config::DEFAULT_WORLD_HEIGHT,
         }
     }
@@ -48,6 +81,12 @@ impl Config {
         Self {
             create_chunk_if_missing,
             update_lighting,
+            compression: None,
+            update_poi: false,
+            update_heightmaps: false,
+            compute_lighting: false,
+            registry: None,
+            fill_default_properties: false,
             world_height,
         }
     }
@@ -0,0 +1,545 @@
+//! `cuboid` contains the [`Cuboid`] and the [`Region`] operations that fill, replace or outline one.
+
+use crate::{
+    BLOCKS_PER_REGION, Block, ChunkData, Coords, Error, Region, Result, data::decode_data,
+    region::get_block_bit_count,
+};
+use simdnbt::owned::NbtCompound;
+use std::ops::RangeInclusive;
+
+/// A cuboid of blocks *(local to within a region)*, both corners are inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cuboid {
+    /// The smallest corner
+    pub min: Coords,
+    /// The largest corner
+    pub max: Coords,
+}
+
+impl<C: Into<Coords>> From<(C, C)> for Cuboid {
+    fn from((from, to): (C, C)) -> Self {
+        Self::new(from, to)
+    }
+}
+
+impl Cuboid {
+    /// Creates a [`Cuboid`] between two corners, which can be given in any order.
+    pub fn new<C: Into<Coords>>(from: C, to: C) -> Self {
+        let (from, to): (Coords, Coords) = (from.into(), to.into());
+        Self {
+            min: Coords::new(from.x.min(to.x), from.y.min(to.y), from.z.min(to.z)),
+            max: Coords::new(from.x.max(to.x), from.y.max(to.y), from.z.max(to.z)),
+        }
+    }
+
+    /// Returns the size of the cuboid as `(x, y, z)`.
+    pub fn size(&self) -> (u32, u32, u32) {
+        (
+            self.max.x - self.min.x + 1,
+            (self.max.y - self.min.y + 1) as u32,
+            self.max.z - self.min.z + 1,
+        )
+    }
+
+    /// Returns how many blocks are within the cuboid.
+    pub fn volume(&self) -> u64 {
+        let (x, y, z) = self.size();
+        x as u64 * y as u64 * z as u64
+    }
+
+    /// If the coordinates are within the cuboid.
+    pub fn contains<C: Into<Coords>>(&self, coords: C) -> bool {
+        let coords: Coords = coords.into();
+        (self.min.x..=self.max.x).contains(&coords.x)
+            && (self.min.y..=self.max.y).contains(&coords.y)
+            && (self.min.z..=self.max.z).contains(&coords.z)
+    }
+
+    /// Returns the cuboid without it's outermost layer of blocks, [`None`] if nothing is left.
+    pub fn interior(&self) -> Option<Cuboid> {
+        let (x, y, z) = self.size();
+        if x <= 2 || y <= 2 || z <= 2 {
+            return None;
+        }
+
+        Some(Cuboid {
+            min: Coords::new(self.min.x + 1, self.min.y + 1, self.min.z + 1),
+            max: Coords::new(self.max.x - 1, self.max.y - 1, self.max.z - 1),
+        })
+    }
+
+    /// The four vertical sides of the cuboid, these overlap at the corners.
+    fn walls(&self) -> [Cuboid; 4] {
+        let (min, max) = (self.min, self.max);
+        [
+            Cuboid::new(min, Coords::new(min.x, max.y, max.z)),
+            Cuboid::new(Coords::new(max.x, min.y, min.z), max),
+            Cuboid::new(min, Coords::new(max.x, max.y, min.z)),
+            Cuboid::new(Coords::new(min.x, min.y, max.z), max),
+        ]
+    }
+
+    /// The parts of the cuboid within each section it touches.
+    fn sections(&self) -> impl Iterator<Item = SectionPart> {
+        let width = ChunkData::WIDTH as u32;
+        let clamp = |min: u32, max: u32, start: u32| min.max(start)..=max.min(start + width - 1);
+        let cuboid = *self;
+
+        (cuboid.min.x / width..=cuboid.max.x / width).flat_map(move |chunk_x| {
+            (cuboid.min.z / width..=cuboid.max.z / width).flat_map(move |chunk_z| {
+                let min_y = cuboid.min.y.div_euclid(width as i32);
+                let max_y = cuboid.max.y.div_euclid(width as i32);
+                (min_y..=max_y).map(move |section_y| {
+                    let bottom = section_y * width as i32;
+                    SectionPart {
+                        chunk: (chunk_x as u8, chunk_z as u8),
+                        section_y: section_y as i8,
+                        x: clamp(cuboid.min.x, cuboid.max.x, chunk_x * width),
+                        y: cuboid.min.y.max(bottom)..=cuboid.max.y.min(bottom + width as i32 - 1),
+                        z: clamp(cuboid.min.z, cuboid.max.z, chunk_z * width),
+                    }
+                })
+            })
+        })
+    }
+
+    fn check_bounds(&self) -> Result<()> {
+        if self.max.x >= BLOCKS_PER_REGION || self.max.z >= BLOCKS_PER_REGION {
+            return Err(Error::CoordinatesOutOfRegionBounds(self.max.x, self.max.z));
+        }
+
+        Ok(())
+    }
+}
+
+/// The part of a [`Cuboid`] within a single section.
+struct SectionPart {
+    chunk: (u8, u8),
+    section_y: i8,
+    x: RangeInclusive<u32>,
+    y: RangeInclusive<i32>,
+    z: RangeInclusive<u32>,
+}
+
+impl SectionPart {
+    /// If the entire section is within the cuboid.
+    fn is_full(&self) -> bool {
+        let width = ChunkData::WIDTH as u32;
+        self.x.end() - self.x.start() + 1 == width
+            && (self.y.end() - self.y.start() + 1) as u32 == width
+            && self.z.end() - self.z.start() + 1 == width
+    }
+
+    fn coords(&self) -> impl Iterator<Item = Coords> {
+        let (x, z) = (self.x.clone(), self.z.clone());
+        self.y.clone().flat_map(move |y| {
+            let x = x.clone();
+            z.clone()
+                .flat_map(move |z| x.clone().map(move |x| Coords::new(x, y, z)))
+        })
+    }
+}
+
+impl Region {
+    /// Fills a cuboid with a single [`Block`].
+    ///
+    /// Sections that are entirely within the cuboid are set directly with [`Region::set_sections`],
+    /// the rest is set like with [`Region::set_block`] so call [`Region::write_blocks`] afterwards.
+    /// Blocks within the cuboid that are still in the buffer are discarded first, so the last fill always wins.
+    ///
+    /// ## Example
+    /// ```
+    /// # use silverfish::{Cuboid, Region};
+    /// # let mut region = Region::default();
+    /// region.fill(Cuboid::new((0, -64, 0), (40, 20, 40)), "stone")?;
+    /// region.fill(((10, 21, 10), (12, 23, 12)), "glass")?;
+    /// region.write_blocks()?;
+    /// # Ok::<(), silverfish::Error>(())
+    /// ```
+    pub fn fill<C: Into<Cuboid>, B: Into<Block>>(&mut self, cuboid: C, block: B) -> Result<()> {
+        let cuboid: Cuboid = cuboid.into();
+        cuboid.check_bounds()?;
+        let block: Block = block.into();
+
+        let world_height = self.get_config().get_world_height().clone();
+        let mut sections = vec![];
+        for part in cuboid.sections() {
+            self.discard_pending_blocks(&part)?;
+
+            let bottom = part.section_y as isize * ChunkData::WIDTH as isize;
+            if part.is_full()
+                && world_height.start <= bottom
+                && bottom + ChunkData::WIDTH as isize <= world_height.end
+            {
+                sections.push((part.chunk, part.section_y, block.clone()));
+                continue;
+            }
+
+            for coords in part.coords() {
+                self.set_block(coords, block.clone())?;
+            }
+        }
+
+        if !sections.is_empty() {
+            self.set_sections(sections)?;
+        }
+
+        Ok(())
+    }
+
+    /// Removes the buffered blocks within a section part, so they don't overwrite what's set over them.
+    fn discard_pending_blocks(&self, part: &SectionPart) -> Result<()> {
+        let mut chunk = self.get_chunk_mut_unmarked(part.chunk.0, part.chunk.1)?;
+        let chunk = &mut *chunk;
+        let Some(pending_blocks) = chunk.pending_blocks.get_mut(&part.section_y) else {
+            return Ok(());
+        };
+
+        let width = ChunkData::WIDTH as u32;
+        let mut discarded = vec![];
+        pending_blocks.retain(|block| {
+            let coords = block.coordinates;
+            let within = part.x.contains(&(part.chunk.0 as u32 * width + coords.x))
+                && part.y.contains(&coords.y)
+                && part.z.contains(&(part.chunk.1 as u32 * width + coords.z));
+            if within {
+                discarded.push(coords);
+            }
+            !within
+        });
+
+        for coords in discarded {
+            let index = chunk.get_block_index(&coords);
+            chunk.seen_blocks.set(index, false);
+        }
+
+        Ok(())
+    }
+
+    /// Replaces every block within a cuboid that matches `from` with `to`.
+    ///
+    /// `from` matches every state of the same block that has the properties it has,
+    /// so `oak_stairs` without properties matches any oak stairs while one with `half=top` only matches upside down ones.
+    ///
+    /// The blocks are compared against the chunk NBT, so call [`Region::write_blocks`] before and after.
+    /// Sections where every block matches and that are entirely within the cuboid are set directly.
+    ///
+    /// ## Example
+    /// ```
+    /// # use silverfish::Region;
+    /// # let mut region = Region::default();
+    /// region.fill(((0, 0, 0), (20, 5, 20)), "dirt")?;
+    /// region.write_blocks()?;
+    ///
+    /// region.replace(((0, 5, 0), (20, 5, 20)), "dirt", "grass_block")?;
+    /// region.write_blocks()?;
+    /// # Ok::<(), silverfish::Error>(())
+    /// ```
+    pub fn replace<C: Into<Cuboid>, B: Into<Block>>(
+        &mut self,
+        cuboid: C,
+        from: B,
+        to: B,
+    ) -> Result<()> {
+        let cuboid: Cuboid = cuboid.into();
+        cuboid.check_bounds()?;
+        let (from, to): (Block, Block) = (from.into(), to.into());
+
+        let mut sections = vec![];
+        let mut blocks = vec![];
+        for part in cuboid.sections() {
+            let chunk = self
+                .get_chunk(part.chunk.0, part.chunk.1)?
+                .ok_or(Error::NoChunk(part.chunk.0, part.chunk.1))?;
+            let Some(section) = find_section(&chunk.nbt, part.section_y)? else {
+                continue;
+            };
+
+            let state = section
+                .compound("block_states")
+                .ok_or(Error::MissingNbtTag("block_states"))?;
+            let palette = state
+                .list("palette")
+                .ok_or(Error::MissingNbtTag("palette"))?
+                .compounds()
+                .ok_or(Error::InvalidNbtType("palette"))?;
+            let matching = palette
+                .iter()
+                .map(|b| Ok(matches(&from, &Block::from_compound(b)?)))
+                .collect::<Result<Vec<bool>>>()?;
+
+            if !matching.contains(&true) {
+                continue;
+            }
+            if part.is_full() && !matching.contains(&false) {
+                sections.push((part.chunk, part.section_y, to.clone()));
+                continue;
+            }
+
+            let mut indexes: [i64; Region::BLOCK_DATA_LEN] = [0; Region::BLOCK_DATA_LEN];
+            let _ = decode_data(
+                &mut indexes,
+                get_block_bit_count(palette.len()),
+                state.long_array("data"),
+            );
+
+            let width = ChunkData::WIDTH as u32;
+            for coords in part.coords() {
+                let section_index = (coords.x % width)
+                    + (coords.z % width) * width
+                    + coords.y.rem_euclid(width as i32) as u32 * width * width;
+                let palette_index = indexes[section_index as usize];
+                let matches = *matching
+                    .get(palette_index as usize)
+                    .ok_or(Error::InvalidPaletteIndex(palette_index))?;
+                if matches {
+                    blocks.push(coords);
+                }
+            }
+        }
+
+        for coords in blocks {
+            self.set_block(coords, to.clone())?;
+        }
+        if !sections.is_empty() {
+            self.set_sections(sections)?;
+        }
+
+        Ok(())
+    }
+
+    /// Sets the four vertical sides of a cuboid, leaving the top, bottom & inside as is.
+    ///
+    /// The blocks are set like with [`Region::set_block`], so call [`Region::write_blocks`] afterwards.
+    ///
+    /// ## Example
+    /// ```
+    /// # use silverfish::Region;
+    /// # let mut region = Region::default();
+    /// region.walls(((0, 64, 0), (9, 67, 9)), "oak_planks")?;
+    /// region.write_blocks()?;
+    /// # Ok::<(), silverfish::Error>(())
+    /// ```
+    pub fn walls<C: Into<Cuboid>, B: Into<Block>>(&mut self, cuboid: C, block: B) -> Result<()> {
+        let cuboid: Cuboid = cuboid.into();
+        let block: Block = block.into();
+        for wall in cuboid.walls() {
+            self.fill(wall, block.clone())?;
+        }
+
+        Ok(())
+    }
+
+    /// Sets all six sides of a cuboid, leaving the inside as is.
+    ///
+    /// The blocks are set like with [`Region::set_block`], so call [`Region::write_blocks`] afterwards.
+    pub fn outline<C: Into<Cuboid>, B: Into<Block>>(&mut self, cuboid: C, block: B) -> Result<()> {
+        let cuboid: Cuboid = cuboid.into();
+        let block: Block = block.into();
+        self.walls(cuboid, block.clone())?;
+
+        let (min, max) = (cuboid.min, cuboid.max);
+        self.fill(
+            Cuboid::new(min, Coords::new(max.x, min.y, max.z)),
+            block.clone(),
+        )?;
+        self.fill(Cuboid::new(Coords::new(min.x, max.y, min.z), max), block)
+    }
+
+    /// Sets all six sides of a cuboid and fills the inside with air, like `/fill ... hollow`.
+    ///
+    /// The inside is filled like with [`Region::fill`], so call [`Region::write_blocks`] afterwards.
+    ///
+    /// ## Example
+    /// ```
+    /// # use silverfish::Region;
+    /// # let mut region = Region::default();
+    /// region.hollow(((0, 0, 0), (47, 47, 47)), "glass")?;
+    /// region.write_blocks()?;
+    /// # Ok::<(), silverfish::Error>(())
+    /// ```
+    pub fn hollow<C: Into<Cuboid>, B: Into<Block>>(&mut self, cuboid: C, block: B) -> Result<()> {
+        let cuboid: Cuboid = cuboid.into();
+        self.outline(cuboid, block)?;
+        if let Some(interior) = cuboid.interior() {
+            self.fill(interior, "minecraft:air")?;
+        }
+
+        Ok(())
+    }
+}
+
+fn find_section(chunk: &NbtCompound, section_y: i8) -> Result<Option<&NbtCompound>> {
+    let sections = chunk
+        .list("sections")
+        .ok_or(Error::MissingNbtTag("sections"))?
+        .compounds()
+        .ok_or(Error::InvalidNbtType("sections"))?;
+
+    for section in sections {
+        if section.byte("Y").ok_or(Error::MissingNbtTag("Y"))? == section_y {
+            return Ok(Some(section));
+        }
+    }
+
+    Ok(None)
+}
+
+/// If the block has the same name as the pattern and all of it's properties.
+fn matches(pattern: &Block, block: &Block) -> bool {
+    if pattern.name.into_cow_namespaced() != block.name.into_cow_namespaced() {
+        return false;
+    }
+
+    let Some(pattern_properties) = &pattern.properties else {
+        return true;
+    };
+    pattern_properties.iter().all(|(key, value)| {
+        block
+            .properties
+            .as_ref()
+            .and_then(|props| props.get(key))
+            .is_some_and(|v| v == value)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn block_at(region: &Region, coords: (u32, i32, u32)) -> Result<String> {
+        Ok(region.get_block(coords)?.to_string())
+    }
+
+    #[test]
+    fn cuboid() {
+        let cuboid = Cuboid::new((10, 5, 3), (2, -4, 7));
+        assert_eq!(cuboid.min, (2, -4, 3));
+        assert_eq!(cuboid.max, (10, 5, 7));
+        assert_eq!(cuboid.size(), (9, 10, 5));
+        assert_eq!(cuboid.volume(), 450);
+        assert!(cuboid.contains((2, 0, 7)));
+        assert!(!cuboid.contains((2, 6, 7)));
+        assert_eq!(cuboid.interior(), Some(Cuboid::new((3, -3, 4), (9, 4, 6))));
+        assert_eq!(Cuboid::new((0, 0, 0), (1, 5, 5)).interior(), None);
+    }
+
+    #[test]
+    fn sections() {
+        let cuboid = Cuboid::new((8, -20, 0), (31, 15, 15));
+        let parts: Vec<SectionPart> = cuboid.sections().collect();
+        // 2 chunks with 3 sections each
+        assert_eq!(parts.len(), 6);
+        let full: Vec<_> = parts
+            .iter()
+            .filter(|p| p.is_full())
+            .map(|p| (p.chunk, p.section_y))
+            .collect();
+        assert_eq!(full, vec![((1, 0), -1), ((1, 0), 0)]);
+        assert_eq!(parts[0].coords().count(), 8 * 4 * 16);
+    }
+
+    #[test]
+    fn fill() -> Result<()> {
+        let mut region = Region::default();
+        region.fill(((0, 0, 0), (20, 15, 15)), "stone")?;
+
+        // the first section is set right away, the rest is still buffered
+        assert_eq!(block_at(&region, (5, 5, 5))?, "minecraft:stone");
+        assert_eq!(block_at(&region, (20, 5, 5))?, "minecraft:air");
+
+        region.write_blocks()?;
+        assert_eq!(block_at(&region, (20, 15, 15))?, "minecraft:stone");
+        assert_eq!(block_at(&region, (21, 15, 15))?, "minecraft:air");
+        assert_eq!(block_at(&region, (20, 16, 15))?, "minecraft:air");
+
+        Ok(())
+    }
+
+    #[test]
+    fn fill_twice() -> Result<()> {
+        let mut region = Region::default();
+        region.set_block((40, 5, 5), "dirt")?;
+        region.fill(((0, 0, 0), (20, 15, 15)), "stone")?;
+        region.fill(((0, 0, 0), (20, 15, 15)), "glass")?;
+        region.write_blocks()?;
+
+        // both the full and the partial section end up with the last fill
+        assert_eq!(block_at(&region, (5, 5, 5))?, "minecraft:glass");
+        assert_eq!(block_at(&region, (20, 15, 15))?, "minecraft:glass");
+        assert_eq!(block_at(&region, (40, 5, 5))?, "minecraft:dirt");
+
+        region.set_block((3, 3, 3), "dirt")?;
+        region.fill(((0, 0, 0), (15, 15, 15)), "stone")?;
+        region.write_blocks()?;
+        assert_eq!(block_at(&region, (3, 3, 3))?, "minecraft:stone");
+
+        Ok(())
+    }
+
+    #[test]
+    fn replace() -> Result<()> {
+        let mut region = Region::default();
+        region.fill(((0, 0, 0), (31, 15, 15)), "dirt")?;
+        region.set_block(
+            (20, 3, 3),
+            Block::new_with_props("oak_stairs", [("half", "top")]),
+        )?;
+        region.set_block(
+            (21, 3, 3),
+            Block::new_with_props("oak_stairs", [("half", "bottom")]),
+        )?;
+        region.write_blocks()?;
+
+        region.replace(((0, 0, 0), (31, 15, 15)), "dirt", "stone")?;
+        region.replace(
+            ((0, 0, 0), (31, 15, 15)),
+            Block::new_with_props("oak_stairs", [("half", "top")]),
+            Block::new("glass"),
+        )?;
+        region.write_blocks()?;
+
+        assert_eq!(block_at(&region, (0, 0, 0))?, "minecraft:stone");
+        assert_eq!(block_at(&region, (31, 15, 15))?, "minecraft:stone");
+        assert_eq!(block_at(&region, (20, 3, 3))?, "minecraft:glass");
+        assert_eq!(
+            block_at(&region, (21, 3, 3))?,
+            "minecraft:oak_stairs[half=bottom]"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn walls_outline_and_hollow() -> Result<()> {
+        let mut region = Region::default();
+        region.walls(((0, 0, 0), (4, 2, 4)), "oak_planks")?;
+        region.outline(((10, 0, 0), (14, 4, 4)), "glass")?;
+        region.fill(((20, 0, 0), (24, 4, 4)), "dirt")?;
+        region.write_blocks()?;
+        region.hollow(((20, 0, 0), (24, 4, 4)), "stone")?;
+        region.write_blocks()?;
+
+        assert_eq!(block_at(&region, (0, 1, 2))?, "minecraft:oak_planks");
+        assert_eq!(block_at(&region, (2, 1, 4))?, "minecraft:oak_planks");
+        assert_eq!(block_at(&region, (2, 0, 2))?, "minecraft:air");
+
+        assert_eq!(block_at(&region, (12, 0, 2))?, "minecraft:glass");
+        assert_eq!(block_at(&region, (12, 4, 2))?, "minecraft:glass");
+        assert_eq!(block_at(&region, (12, 2, 2))?, "minecraft:air");
+
+        assert_eq!(block_at(&region, (20, 2, 2))?, "minecraft:stone");
+        assert_eq!(block_at(&region, (22, 2, 2))?, "minecraft:air");
+
+        Ok(())
+    }
+
+    #[test]
+    fn out_of_bounds() {
+        let mut region = Region::default();
+        assert!(matches!(
+            region.fill(((500, 0, 0), (520, 0, 0)), "stone"),
+            Err(Error::CoordinatesOutOfRegionBounds(520, 0))
+        ));
+    }
+}
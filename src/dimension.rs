@@ -0,0 +1,270 @@
+//! `dimension` contains the [`Dimension`] enum used to open a specific dimension of a save
+//! and figure out where it's regions are stored and how tall it is.
+
+use crate::{Config, Error, Result};
+use serde_json::Value;
+use std::{
+    fs::File,
+    io::BufReader,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+/// A dimension within a Minecraft save.
+///
+/// Each dimension has it's own folder of region files and it's own world height.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Dimension {
+    /// The overworld, stored directly in the save folder.
+    Overworld,
+    /// The nether, stored in `DIM-1`.
+    Nether,
+    /// The end, stored in `DIM1`.
+    End,
+    /// A datapack dimension, stored in `dimensions/<namespace>/<name>`.
+    Custom {
+        /// The namespace of the dimension id.
+        namespace: String,
+        /// The path of the dimension id.
+        name: String,
+    },
+}
+
+impl Dimension {
+    /// The world height of the nether & the end.
+    pub const NETHER_END_WORLD_HEIGHT: Range<isize> = 0..256;
+
+    /// Creates a new [`Dimension::Custom`] from a dimension id, `<namespace>:<name>`.
+    ///
+    /// Defaults to the `minecraft` namespace if none is given.
+    ///
+    /// ## Example
+    /// ```
+    /// # use silverfish::Dimension;
+    /// let dimension = Dimension::custom("my_pack:mining_world");
+    /// ```
+    pub fn custom(id: &str) -> Self {
+        let (namespace, name) = split_id(id);
+        Dimension::Custom {
+            namespace: namespace.to_owned(),
+            name: name.to_owned(),
+        }
+    }
+
+    /// Returns the dimension id, like `minecraft:the_nether`.
+    pub fn id(&self) -> String {
+        match self {
+            Dimension::Overworld => String::from("minecraft:overworld"),
+            Dimension::Nether => String::from("minecraft:the_nether"),
+            Dimension::End => String::from("minecraft:the_end"),
+            Dimension::Custom { namespace, name } => format!("{namespace}:{name}"),
+        }
+    }
+
+    /// Returns the folder within the save that holds this dimension's data.
+    pub fn folder<P: AsRef<Path>>(&self, save: P) -> PathBuf {
+        let save = save.as_ref();
+        match self {
+            Dimension::Overworld => save.to_path_buf(),
+            Dimension::Nether => save.join("DIM-1"),
+            Dimension::End => save.join("DIM1"),
+            Dimension::Custom { namespace, name } => {
+                save.join("dimensions").join(namespace).join(name)
+            }
+        }
+    }
+
+    /// Returns the folder that holds this dimension's `r.x.z.mca` files.
+    pub fn region_folder<P: AsRef<Path>>(&self, save: P) -> PathBuf {
+        self.folder(save).join("region")
+    }
+
+    /// Returns the world height of this dimension.
+    ///
+    /// Vanilla dimensions use their hardcoded heights.
+    /// Custom dimensions are looked up through the datapacks in `<save>/datapacks`,
+    /// reading `min_y` & `height` from the dimension type the dimension uses.
+    ///
+    /// ## Example
+    /// ```
+    /// # use silverfish::Dimension;
+    /// let height = Dimension::Nether.world_height("saves/New World")?;
+    /// assert_eq!(height, 0..256);
+    /// # Ok::<(), silverfish::Error>(())
+    /// ```
+    pub fn world_height<P: AsRef<Path>>(&self, save: P) -> Result<Range<isize>> {
+        match self {
+            Dimension::Overworld => Ok(Config::DEFAULT_WORLD_HEIGHT),
+            Dimension::Nether | Dimension::End => Ok(Dimension::NETHER_END_WORLD_HEIGHT),
+            Dimension::Custom { namespace, name } => {
+                let datapacks = save.as_ref().join("datapacks");
+                let dimension = read_datapack_json(&datapacks, namespace, "dimension", name)?
+                    .ok_or_else(|| Error::UnknownDimension(self.id()))?;
+
+                match dimension.get("type") {
+                    Some(Value::String(type_id)) => dimension_type_height(&datapacks, type_id),
+                    // the dimension type can also be defined inline
+                    Some(dimension_type @ Value::Object(_)) => {
+                        height_from_dimension_type(dimension_type, &self.id())
+                    }
+                    _ => Err(Error::UnknownDimensionType(self.id())),
+                }
+            }
+        }
+    }
+}
+
+/// Splits an id into it's namespace & path, defaulting to `minecraft`.
+fn split_id(id: &str) -> (&str, &str) {
+    match id.split_once(':') {
+        Some((namespace, name)) => (namespace, name),
+        None => ("minecraft", id),
+    }
+}
+
+/// Returns the world height of a dimension type id.
+fn dimension_type_height(datapacks: &Path, type_id: &str) -> Result<Range<isize>> {
+    let (namespace, name) = split_id(type_id);
+
+    // datapacks can override vanilla dimension types so those are checked first
+    if let Some(dimension_type) = read_datapack_json(datapacks, namespace, "dimension_type", name)?
+    {
+        return height_from_dimension_type(&dimension_type, type_id);
+    }
+
+    match (namespace, name) {
+        ("minecraft", "overworld" | "overworld_caves") => Ok(Config::DEFAULT_WORLD_HEIGHT),
+        ("minecraft", "the_nether" | "the_end") => Ok(Dimension::NETHER_END_WORLD_HEIGHT),
+        _ => Err(Error::UnknownDimensionType(type_id.to_owned())),
+    }
+}
+
+/// Reads `min_y` & `height` from a dimension type json.
+fn height_from_dimension_type(dimension_type: &Value, type_id: &str) -> Result<Range<isize>> {
+    let min_y = dimension_type.get("min_y").and_then(|v| v.as_i64());
+    let height = dimension_type.get("height").and_then(|v| v.as_i64());
+
+    match (min_y, height) {
+        (Some(min_y), Some(height)) => Ok(min_y as isize..(min_y + height) as isize),
+        _ => Err(Error::UnknownDimensionType(type_id.to_owned())),
+    }
+}
+
+/// Searches every folder datapack for `data/<namespace>/<kind>/<name>.json`.
+fn read_datapack_json(
+    datapacks: &Path,
+    namespace: &str,
+    kind: &str,
+    name: &str,
+) -> Result<Option<Value>> {
+    let packs = match std::fs::read_dir(datapacks) {
+        Ok(packs) => packs,
+        Err(_) => return Ok(None),
+    };
+
+    for pack in packs {
+        let path = pack?
+            .path()
+            .join("data")
+            .join(namespace)
+            .join(kind)
+            .join(format!("{name}.json"));
+
+        if path.is_file() {
+            let value = serde_json::from_reader(BufReader::new(File::open(path)?))?;
+            return Ok(Some(value));
+        }
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Creates a save with a datapack that adds a custom dimension.
+    fn temp_save_with_datapack(name: &str, dimension: &str, dimension_type: &str) -> PathBuf {
+        let save = std::env::temp_dir().join(format!("silverfish_dimension_{name}"));
+        let _ = std::fs::remove_dir_all(&save);
+
+        let data = save.join("datapacks").join("pack").join("data").join("test");
+        std::fs::create_dir_all(data.join("dimension")).unwrap();
+        std::fs::create_dir_all(data.join("dimension_type")).unwrap();
+        std::fs::write(data.join("dimension").join("mining.json"), dimension).unwrap();
+        std::fs::write(
+            data.join("dimension_type").join("deep.json"),
+            dimension_type,
+        )
+        .unwrap();
+
+        save
+    }
+
+    #[test]
+    fn folders() {
+        let save = Path::new("save");
+        assert_eq!(Dimension::Overworld.region_folder(save), save.join("region"));
+        assert_eq!(
+            Dimension::Nether.region_folder(save),
+            save.join("DIM-1").join("region")
+        );
+        assert_eq!(
+            Dimension::End.region_folder(save),
+            save.join("DIM1").join("region")
+        );
+        assert_eq!(
+            Dimension::custom("test:mining").region_folder(save),
+            save.join("dimensions/test/mining/region")
+        );
+    }
+
+    #[test]
+    fn custom_id() {
+        assert_eq!(Dimension::custom("test:mining").id(), "test:mining");
+        assert_eq!(Dimension::custom("mining").id(), "minecraft:mining");
+    }
+
+    #[test]
+    fn vanilla_heights() -> Result<()> {
+        let save = Path::new("save");
+        assert_eq!(Dimension::Overworld.world_height(save)?, -64..320);
+        assert_eq!(Dimension::Nether.world_height(save)?, 0..256);
+        assert_eq!(Dimension::End.world_height(save)?, 0..256);
+        Ok(())
+    }
+
+    #[test]
+    fn custom_height() -> Result<()> {
+        let save = temp_save_with_datapack(
+            "custom_height",
+            r#"{ "type": "test:deep", "generator": {} }"#,
+            r#"{ "min_y": -128, "height": 512 }"#,
+        );
+        assert_eq!(
+            Dimension::custom("test:mining").world_height(&save)?,
+            -128..384
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn custom_vanilla_type_height() -> Result<()> {
+        let save = temp_save_with_datapack(
+            "custom_vanilla_type",
+            r#"{ "type": "minecraft:the_nether", "generator": {} }"#,
+            r#"{}"#,
+        );
+        assert_eq!(
+            Dimension::custom("test:mining").world_height(&save)?,
+            0..256
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_custom_dimension() {
+        let save = Path::new("save_that_does_not_exist");
+        assert!(Dimension::custom("test:nothing").world_height(save).is_err());
+    }
+}
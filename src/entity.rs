@@ -0,0 +1,582 @@
+//! `entity` contains the [`EntityRegion`] struct used to read & write the entities within a region.
+//!
+//! Since `1.17` entities are stored in their own region files in the `entities` folder,
+//! next to the `region` folder. Each chunk holds a list of [`Entity`] compounds.
+
+use crate::{
+    Coords, Error, NbtString, Region, Result,
+    nbt::set_tag,
+    region::{read_chunk_nbt, to_region_coords, to_region_local},
+    region_file::{self, Compression, ExternalChunks, RawChunk},
+};
+use ahash::AHashMap;
+use simdnbt::owned::{BaseNbt, Nbt, NbtCompound, NbtList, NbtTag};
+use std::{
+    fmt::Debug,
+    io::{Read, Write},
+    path::Path,
+};
+
+/// A single entity, like a mob, item or armor stand.
+///
+/// The entire NBT is kept around, [`Entity`] just provides typed accessors for the common fields.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entity {
+    /// The entity's actual NBT data
+    pub nbt: NbtCompound,
+}
+
+impl Entity {
+    /// Creates a new [`Entity`] with an id, a *global* position and a UUID.
+    ///
+    /// ## Example
+    /// ```
+    /// # use silverfish::Entity;
+    /// let pig = Entity::new("minecraft:pig", (12.5, 64.0, -3.5), 0x1234);
+    /// assert_eq!(pig.uuid(), Some(0x1234));
+    /// ```
+    pub fn new<S: Into<NbtString>>(id: S, position: (f64, f64, f64), uuid: u128) -> Self {
+        let id: NbtString = id.into();
+        let mut entity = Self {
+            nbt: NbtCompound::from_values(vec![(
+                "id".into(),
+                NbtTag::String(id.to_mutf8string()),
+            )]),
+        };
+        entity.set_position(position);
+        entity.set_uuid(uuid);
+
+        entity
+    }
+
+    /// Creates an [`Entity`] from it's NBT compound.
+    pub fn from_nbt(nbt: NbtCompound) -> Self {
+        Self { nbt }
+    }
+
+    /// The entity type, like `minecraft:zombie`.
+    pub fn id(&self) -> Option<NbtString> {
+        NbtString::from_mutf8str(self.nbt.string("id"))
+    }
+
+    /// The *global* position of the entity.
+    pub fn position(&self) -> Option<(f64, f64, f64)> {
+        match self.nbt.list("Pos")?.doubles()? {
+            [x, y, z] => Some((*x, *y, *z)),
+            _ => None,
+        }
+    }
+
+    /// Sets the *global* position of the entity.
+    pub fn set_position(&mut self, position: (f64, f64, f64)) {
+        set_tag(
+            &mut self.nbt,
+            "Pos",
+            NbtTag::List(NbtList::Double(vec![position.0, position.1, position.2])),
+        );
+    }
+
+    /// The *global* coordinates of the block the entity is within.
+    pub fn block_position(&self) -> Option<(i32, i32, i32)> {
+        let (x, y, z) = self.position()?;
+        Some((x.floor() as i32, y.floor() as i32, z.floor() as i32))
+    }
+
+    /// The UUID of the entity.
+    pub fn uuid(&self) -> Option<u128> {
+        match self.nbt.int_array("UUID")? {
+            [a, b, c, d] => Some(
+                ((*a as u32 as u128) << 96)
+                    | ((*b as u32 as u128) << 64)
+                    | ((*c as u32 as u128) << 32)
+                    | (*d as u32 as u128),
+            ),
+            _ => None,
+        }
+    }
+
+    /// Sets the UUID of the entity.
+    pub fn set_uuid(&mut self, uuid: u128) {
+        // stored as 4 ints, most significant first
+        set_tag(
+            &mut self.nbt,
+            "UUID",
+            NbtTag::IntArray(vec![
+                (uuid >> 96) as u32 as i32,
+                (uuid >> 64) as u32 as i32,
+                (uuid >> 32) as u32 as i32,
+                uuid as u32 as i32,
+            ]),
+        );
+    }
+}
+
+/// A chunk within an [`EntityRegion`] and the entities within it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntityChunk {
+    /// The `DataVersion` of the chunk
+    pub data_version: i32,
+    /// The entities within the chunk
+    pub(crate) entities: Vec<Entity>,
+    /// Any other tags in the chunk's root compound
+    pub(crate) nbt: NbtCompound,
+    /// The chunk exactly as it was read from the region file, dropped once modified
+    pub(crate) raw: Option<RawChunk>,
+    /// The compression the chunk was read with
+    pub(crate) compression: Option<Compression>,
+}
+
+impl EntityChunk {
+    /// Creates a new [`EntityChunk`] without any entities.
+    pub fn new(data_version: i32) -> Self {
+        Self {
+            data_version,
+            entities: Vec::new(),
+            nbt: NbtCompound::from_values(vec![]),
+            raw: None,
+            compression: None,
+        }
+    }
+
+    /// Creates an [`EntityChunk`] from the root compound of an entity chunk.
+    pub fn from_nbt(mut nbt: NbtCompound) -> Result<Self> {
+        let data_version = nbt
+            .int("DataVersion")
+            .ok_or(Error::MissingNbtTag("DataVersion"))?;
+        let entities = match nbt.remove("Entities") {
+            Some(NbtTag::List(NbtList::Compound(entities))) => entities,
+            Some(NbtTag::List(NbtList::Empty)) | None => vec![],
+            Some(_) => return Err(Error::InvalidNbtList("Entities")),
+        };
+
+        Ok(Self {
+            data_version,
+            entities: entities.into_iter().map(Entity::from_nbt).collect(),
+            nbt,
+            raw: None,
+            compression: None,
+        })
+    }
+
+    /// Returns the chunk's root compound.
+    pub fn to_nbt(&self, chunk_coords: (i32, i32)) -> NbtCompound {
+        let mut nbt = self.nbt.clone();
+        set_tag(&mut nbt, "DataVersion", NbtTag::Int(self.data_version));
+        set_tag(
+            &mut nbt,
+            "Position",
+            NbtTag::IntArray(vec![chunk_coords.0, chunk_coords.1]),
+        );
+
+        let entities = if self.entities.is_empty() {
+            NbtList::Empty
+        } else {
+            NbtList::Compound(self.entities.iter().map(|e| e.nbt.clone()).collect())
+        };
+        set_tag(&mut nbt, "Entities", NbtTag::List(entities));
+
+        nbt
+    }
+
+    /// Returns the entities within the chunk.
+    pub fn entities(&self) -> &[Entity] {
+        &self.entities
+    }
+
+    /// Returns the entities within the chunk, mutably.
+    pub fn entities_mut(&mut self) -> &mut Vec<Entity> {
+        self.mark_modified();
+        &mut self.entities
+    }
+
+    /// Marks the chunk as modified, so it gets re-encoded when written.
+    pub fn mark_modified(&mut self) {
+        self.raw = None;
+    }
+
+    /// Returns `true` if the chunk has been modified since it was read from the region file.
+    pub fn is_modified(&self) -> bool {
+        self.raw.is_none()
+    }
+}
+
+/// An in-memory entity region, read from `entities/r.<x>.<z>.mca`.
+///
+/// All coordinates given to an [`EntityRegion`] are local to within the region,
+/// just like with [`Region`].
+///
+/// ## Example
+/// ```no_run
+/// # use silverfish::{Entity, EntityRegion};
+/// let mut entities = EntityRegion::open("saves/New World/entities/r.0.0.mca")?;
+/// for entity in entities.entities() {
+///     println!("{:?} at {:?}", entity.id(), entity.position());
+/// }
+///
+/// entities.add_entity(Entity::new("minecraft:cow", (40.5, 70.0, 12.5), 0xC0))?;
+/// entities.save("saves/New World/entities/r.0.0.mca")?;
+/// # Ok::<(), silverfish::Error>(())
+/// ```
+#[derive(Clone)]
+pub struct EntityRegion {
+    /// The chunks within the region, mapped to their coordinates
+    pub(crate) chunks: AHashMap<(u8, u8), EntityChunk>,
+    /// Coordinates for this specific region
+    pub region_coords: (i32, i32),
+    /// Where oversized chunks are stored, if read from a region file
+    pub(crate) external: Option<ExternalChunks>,
+}
+
+impl EntityRegion {
+    /// Creates an empty [`EntityRegion`] with no chunks.
+    pub fn empty(region_coords: (i32, i32)) -> Self {
+        Self {
+            chunks: AHashMap::new(),
+            region_coords,
+            external: None,
+        }
+    }
+
+    /// Creates an [`EntityRegion`] from an already existing entity region file.
+    pub fn from_region<R: Read>(reader: &mut R, region_coords: (i32, i32)) -> Result<Self> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+
+        Self::from_region_bytes(&bytes, region_coords, None)
+    }
+
+    /// Opens an entity region file, working out the region coordinates from it's `r.<x>.<z>.mca` file name.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let (region_coords, bytes, external) = region_file::open_region(path.as_ref())?;
+        Self::from_region_bytes(&bytes, region_coords, Some(external))
+    }
+
+    /// Reads every chunk from the bytes of an entity region file.
+    fn from_region_bytes(
+        bytes: &[u8],
+        region_coords: (i32, i32),
+        external: Option<ExternalChunks>,
+    ) -> Result<Self> {
+        let mut region = Self::empty(region_coords);
+        for (coords, raw) in region_file::read_chunks(bytes, external.as_ref())? {
+            let mut chunk = EntityChunk::from_nbt(read_chunk_nbt(&raw.decompress()?)?)?;
+            chunk.compression = Some(raw.compression);
+            chunk.raw = Some(raw);
+            region.chunks.insert(coords, chunk);
+        }
+
+        region.external = external;
+
+        Ok(region)
+    }
+
+    /// Writes the entity region to the specified writer.
+    ///
+    /// Chunks that haven't been modified are copied over as is.
+    pub fn write<W: Write>(self, writer: &mut W) -> Result<()> {
+        region_file::write_region(writer, self.into_raw_chunks()?)
+    }
+
+    /// Saves the entity region to an entity region file.
+    ///
+    /// The file name must be `r.<x>.<z>.mca` and match [`EntityRegion::region_coords`].
+    pub fn save<P: AsRef<Path>>(self, path: P) -> Result<()> {
+        let (region_coords, external) = (self.region_coords, self.external.clone());
+        region_file::save_region(
+            path.as_ref(),
+            region_coords,
+            self.into_raw_chunks()?,
+            external.as_ref(),
+        )
+    }
+
+    /// Turns every chunk into it's compressed form, ready to be written to a region file.
+    fn into_raw_chunks(mut self) -> Result<Vec<((u8, u8), RawChunk)>> {
+        self.move_entities_to_their_chunks();
+
+        let now = region_file::now_timestamp();
+        let mut chunks = Vec::with_capacity(self.chunks.len());
+        for ((x, z), chunk) in self.chunks.iter() {
+            let raw = match &chunk.raw {
+                Some(raw) => raw.clone(),
+                None => {
+                    let nbt = chunk.to_nbt(self.global_chunk_coords((*x, *z)));
+                    let mut raw_nbt = vec![];
+                    Nbt::Some(BaseNbt::new("", nbt)).write(&mut raw_nbt);
+                    RawChunk::compress(&raw_nbt, chunk.compression.unwrap_or_default(), now)?
+                }
+            };
+            chunks.push(((*x, *z), raw));
+        }
+
+        Ok(chunks)
+    }
+
+    /// Moves any entity that has been moved to another chunk within this region to that chunk.
+    fn move_entities_to_their_chunks(&mut self) {
+        let region_coords = self.region_coords;
+        let mut moved = vec![];
+        for (coords, chunk) in self.chunks.iter_mut() {
+            if chunk.is_modified() {
+                let (stay, leave): (Vec<Entity>, Vec<Entity>) =
+                    chunk.entities.drain(..).partition(|e| {
+                        match chunk_within_region(region_coords, e) {
+                            Some(c) => c == *coords,
+                            // entities outside of this region can't be moved, so they stay put
+                            None => true,
+                        }
+                    });
+                chunk.entities = stay;
+                moved.extend(leave);
+            }
+        }
+
+        let data_version = self.data_version();
+        for entity in moved {
+            // always some, since entities outside the region stayed in their chunk
+            if let Some(coords) = chunk_within_region(region_coords, &entity) {
+                let chunk = self
+                    .chunks
+                    .entry(coords)
+                    .or_insert_with(|| EntityChunk::new(data_version));
+                chunk.mark_modified();
+                chunk.entities.push(entity);
+            }
+        }
+    }
+
+    /// Returns the global chunk coordinates of a chunk within this region.
+    fn global_chunk_coords(&self, chunk: (u8, u8)) -> (i32, i32) {
+        (
+            self.region_coords.0 * mca::REGION_SIZE as i32 + chunk.0 as i32,
+            self.region_coords.1 * mca::REGION_SIZE as i32 + chunk.1 as i32,
+        )
+    }
+
+    /// The `DataVersion` new chunks are created with, taken from any existing chunk.
+    fn data_version(&self) -> i32 {
+        self.chunks
+            .values()
+            .map(|c| c.data_version)
+            .max()
+            .unwrap_or(Region::MIN_DATA_VERSION)
+    }
+
+    /// Returns the entity chunk at the given chunk coordinates.
+    pub fn get_chunk(&self, x: u8, z: u8) -> Option<&EntityChunk> {
+        self.chunks.get(&(x, z))
+    }
+
+    /// Returns the entity chunk at the given chunk coordinates mutably, marking it as modified.
+    pub fn get_chunk_mut(&mut self, x: u8, z: u8) -> Option<&mut EntityChunk> {
+        let chunk = self.chunks.get_mut(&(x, z))?;
+        chunk.mark_modified();
+        Some(chunk)
+    }
+
+    /// Returns every entity within the region.
+    pub fn entities(&self) -> impl Iterator<Item = &Entity> {
+        self.chunks.values().flat_map(|c| c.entities.iter())
+    }
+
+    /// Returns the entities within the block at the specified coordinates *(local to within the region)*.
+    ///
+    /// ## Example
+    /// ```
+    /// # use silverfish::{Entity, EntityRegion};
+    /// let mut region = EntityRegion::empty((0, 0));
+    /// region.add_entity(Entity::new("minecraft:pig", (12.5, 64.0, 3.5), 1))?;
+    /// assert_eq!(region.get_entities((12, 64, 3)).len(), 1);
+    /// # Ok::<(), silverfish::Error>(())
+    /// ```
+    pub fn get_entities<C: Into<Coords>>(&self, coords: C) -> Vec<&Entity> {
+        let coords: Coords = coords.into();
+        let chunk = match self.chunks.get(&((coords.x >> 4) as u8, (coords.z >> 4) as u8)) {
+            Some(chunk) => chunk,
+            None => return vec![],
+        };
+
+        chunk
+            .entities
+            .iter()
+            .filter(|e| match e.block_position() {
+                Some(position) => to_region_local(position) == coords,
+                None => false,
+            })
+            .collect()
+    }
+
+    /// Returns the entity with the given UUID.
+    pub fn get_entity(&self, uuid: u128) -> Option<&Entity> {
+        self.entities().find(|e| e.uuid() == Some(uuid))
+    }
+
+    /// Returns the entity with the given UUID mutably, marking it's chunk as modified.
+    ///
+    /// If the entity is moved to another chunk, it's moved to that chunk when the region is written.
+    pub fn get_entity_mut(&mut self, uuid: u128) -> Option<&mut Entity> {
+        let chunk = self
+            .chunks
+            .values_mut()
+            .find(|c| c.entities.iter().any(|e| e.uuid() == Some(uuid)))?;
+        chunk.mark_modified();
+
+        chunk.entities.iter_mut().find(|e| e.uuid() == Some(uuid))
+    }
+
+    /// Adds an entity to the chunk it's position is within.
+    ///
+    /// Returns an error if the entity doesn't have a position or if it's outside of this region.
+    pub fn add_entity(&mut self, entity: Entity) -> Result<()> {
+        let position = entity.position().ok_or(Error::MissingNbtTag("Pos"))?;
+        let coords = chunk_within_region(self.region_coords, &entity).ok_or(
+            Error::EntityOutsideRegion {
+                region: self.region_coords,
+                position,
+            },
+        )?;
+
+        let data_version = self.data_version();
+        let chunk = self
+            .chunks
+            .entry(coords)
+            .or_insert_with(|| EntityChunk::new(data_version));
+        chunk.mark_modified();
+        chunk.entities.push(entity);
+
+        Ok(())
+    }
+
+    /// Removes the entity with the given UUID, returning it if it existed.
+    pub fn remove_entity(&mut self, uuid: u128) -> Option<Entity> {
+        for chunk in self.chunks.values_mut() {
+            if let Some(index) = chunk.entities.iter().position(|e| e.uuid() == Some(uuid)) {
+                chunk.mark_modified();
+                return Some(chunk.entities.remove(index));
+            }
+        }
+
+        None
+    }
+}
+
+/// Returns the region local chunk coordinates an entity is within, if it's within the given region.
+fn chunk_within_region(region_coords: (i32, i32), entity: &Entity) -> Option<(u8, u8)> {
+    let position = entity.block_position()?;
+    if to_region_coords(position) != region_coords {
+        return None;
+    }
+
+    let local = to_region_local(position);
+    Some(((local.x >> 4) as u8, (local.z >> 4) as u8))
+}
+
+impl Debug for EntityRegion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "EntityRegion({}, {})\n  > chunks: {}\n  > entities: {}",
+            self.region_coords.0,
+            self.region_coords.1,
+            self.chunks.len(),
+            self.entities().count()
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn entity_fields() {
+        let uuid = 0x0123_4567_89ab_cdef_fedc_ba98_7654_3210;
+        let mut entity = Entity::new("minecraft:zombie", (-0.5, 64.0, 1023.9), uuid);
+
+        assert_eq!(entity.id().unwrap(), "minecraft:zombie");
+        assert_eq!(entity.uuid(), Some(uuid));
+        assert_eq!(entity.position(), Some((-0.5, 64.0, 1023.9)));
+        assert_eq!(entity.block_position(), Some((-1, 64, 1023)));
+
+        entity.set_position((5.0, 6.0, 7.0));
+        assert_eq!(entity.block_position(), Some((5, 6, 7)));
+    }
+
+    #[test]
+    fn add_and_remove() -> Result<()> {
+        let mut region = EntityRegion::empty((-1, 0));
+        region.add_entity(Entity::new("minecraft:cow", (-500.5, 70.0, 20.5), 1))?;
+        region.add_entity(Entity::new("minecraft:pig", (-1.5, 70.0, 511.5), 2))?;
+        assert!(
+            region
+                .add_entity(Entity::new("minecraft:pig", (0.5, 70.0, 0.5), 3))
+                .is_err()
+        );
+
+        assert_eq!(region.entities().count(), 2);
+        assert_eq!(region.get_chunk(0, 1).unwrap().entities().len(), 1);
+        assert_eq!(region.get_entities((11, 70, 20)).len(), 1);
+        assert_eq!(region.get_entities((510, 70, 511)).len(), 1);
+        assert!(region.get_entities((0, 70, 0)).is_empty());
+
+        let pig = region.remove_entity(2).unwrap();
+        assert_eq!(pig.id().unwrap(), "minecraft:pig");
+        assert!(region.get_entity(2).is_none());
+        assert_eq!(region.entities().count(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn write_and_read() -> Result<()> {
+        let mut region = EntityRegion::empty((0, 0));
+        region.add_entity(Entity::new("minecraft:armor_stand", (8.0, 64.0, 8.0), 7))?;
+        region.add_entity(Entity::new("minecraft:cat", (100.0, 64.0, 300.0), 8))?;
+
+        let mut buf = vec![];
+        region.write(&mut buf)?;
+
+        let mut region = EntityRegion::from_region(&mut buf.as_slice(), (0, 0))?;
+        assert_eq!(region.entities().count(), 2);
+        assert!(!region.get_chunk(0, 0).unwrap().is_modified());
+
+        let chunk_nbt = region.get_chunk(6, 18).unwrap().to_nbt((6, 18));
+        assert_eq!(chunk_nbt.int_array("Position"), Some(&[6, 18][..]));
+
+        // moving an entity to another chunk moves it on write
+        region
+            .get_entity_mut(8)
+            .unwrap()
+            .set_position((40.0, 64.0, 40.0));
+        let mut buf = vec![];
+        region.write(&mut buf)?;
+
+        let region = EntityRegion::from_region(&mut buf.as_slice(), (0, 0))?;
+        assert!(region.get_chunk(6, 18).unwrap().entities().is_empty());
+        assert_eq!(region.get_entities((40, 64, 40)).len(), 1);
+        assert_eq!(
+            region.get_entity(7).unwrap().id().unwrap(),
+            "minecraft:armor_stand"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn open_and_save() -> Result<()> {
+        let folder = std::env::temp_dir().join("silverfish_entity_open_and_save");
+        let _ = std::fs::remove_dir_all(&folder);
+        std::fs::create_dir_all(&folder)?;
+        let path = folder.join("r.2.-3.mca");
+
+        let mut region = EntityRegion::empty((2, -3));
+        region.add_entity(Entity::new("minecraft:bee", (1030.0, 90.0, -1530.0), 99))?;
+        region.save(&path)?;
+
+        let region = EntityRegion::open(&path)?;
+        assert_eq!(region.region_coords, (2, -3));
+        assert!(region.get_entity(99).is_some());
+
+        Ok(())
+    }
+}
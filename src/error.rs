@@ -3,6 +3,7 @@
 //! `error` contains the [`Error`] type for this crate and a shorthand [`Result`] type.  
 
 use crate::{BLOCKS_PER_REGION, NbtString, nbt::Block, region::Region};
+use std::path::PathBuf;
 
 /// A shorthand type for `Result<T, silverfish::Error>`
 pub type Result<T> = std::result::Result<T, Error>;
@@ -16,6 +17,8 @@ pub enum Error {
     Io(#[from] std::io::Error),
     #[error(transparent)]
     Simdnbt(#[from] simdnbt::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
 
     #[error(
         "Coordinates are outside of regions bounds ({0} || {1} >= {width})",
@@ -52,4 +55,75 @@ pub enum Error {
     TriedToAccessArc(&'static str),
     #[error("The PalettedBlock > blocks index is unset (u32::MAX)")]
     UnsetPaletteBlock(u32),
+    #[error("No region file found for region {0} {1}")]
+    NoRegion(i32, i32),
+    #[error("Couldn't find a region folder at {0:?}")]
+    MissingRegionFolder(PathBuf),
+    #[error("Region file name '{0}' doesn't follow the 'r.<x>.<z>.mca' format")]
+    InvalidRegionFileName(String),
+    #[error("Tried to save region {region:?} to the region file for {file:?}")]
+    RegionFileMismatch { region: (i32, i32), file: (i32, i32) },
+    #[error("Couldn't find a dimension named '{0}' in any datapack")]
+    UnknownDimension(String),
+    #[error("Couldn't read the world height of the dimension type '{0}'")]
+    UnknownDimensionType(String),
+    #[error("Invalid region file: {0}")]
+    InvalidRegionFile(&'static str),
+    #[error("Unknown chunk compression type {0}")]
+    UnknownCompression(u8),
+    #[error("Chunk {0} {1} is too large to fit in the region file")]
+    ChunkTooLarge(u8, u8),
+    #[error("Chunk {0} {1} is stored in an external .mcc file, which needs the region to be opened from a path")]
+    ExternalChunk(u8, u8),
+    #[error("Entity at {position:?} is outside of region {region:?}")]
+    EntityOutsideRegion {
+        region: (i32, i32),
+        position: (f64, f64, f64),
+    },
+    #[error("No POI region attached to region {0} {1}, which is needed with Config::update_poi")]
+    MissingPoiRegion(i32, i32),
+    #[error("Tried to attach the POI region {poi:?} to region {region:?}")]
+    PoiRegionMismatch { region: (i32, i32), poi: (i32, i32) },
+    #[error("Point of interest at {position:?} is outside of region {region:?}")]
+    PoiOutsideRegion {
+        region: (i32, i32),
+        position: (i32, i32, i32),
+    },
+    #[error("Couldn't work out the block entity id for '{0}', set an 'id' tag in the block entity")]
+    UnknownBlockEntity(String),
+    #[error("Invalid block registry: {0}")]
+    InvalidBlockRegistry(String),
+    #[error("Unknown block '{0}'")]
+    UnknownBlock(String),
+    #[error("Block '{block}' has no property named '{property}', expected one of {allowed:?}")]
+    UnknownBlockProperty {
+        block: String,
+        property: String,
+        allowed: Vec<String>,
+    },
+    #[error("Invalid value '{value}' for property '{property}' of block '{block}', expected one of {allowed:?}")]
+    InvalidBlockPropertyValue {
+        block: String,
+        property: String,
+        value: String,
+        allowed: Vec<String>,
+    },
+    #[error("Invalid SNBT at {position} near '{near}': {reason}")]
+    InvalidSnbt {
+        position: usize,
+        near: String,
+        reason: String,
+    },
+    #[error("Invalid block state '{input}' at {position}: {reason}")]
+    InvalidBlockState {
+        input: String,
+        position: usize,
+        reason: String,
+    },
+    #[error("Invalid schematic: {0}")]
+    InvalidSchematic(String),
+    #[error("Unsupported schematic version {0}")]
+    UnsupportedSchematicVersion(i32),
+    #[error("Unknown legacy block {0}:{1}")]
+    UnknownLegacyBlock(u16, u8),
 }
@@ -141,8 +141,7 @@ impl Region {
 
         for chunk_group in groups.iter_mut() {
             let chunk = self
-                .chunks
-                .get(&chunk_group.coordinate)
+                .get_chunk(chunk_group.coordinate.0, chunk_group.coordinate.1)?
                 .ok_or(Error::NoChunk(
                     chunk_group.coordinate.0,
                     chunk_group.coordinate.1,
@@ -189,8 +188,7 @@ impl Region {
 
         for chunk_group in groups.iter_mut() {
             let chunk = self
-                .chunks
-                .get(&chunk_group.coordinate)
+                .get_chunk(chunk_group.coordinate.0, chunk_group.coordinate.1)?
                 .ok_or(Error::NoChunk(
                     chunk_group.coordinate.0,
                     chunk_group.coordinate.1,
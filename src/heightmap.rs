@@ -0,0 +1,423 @@
+//! `heightmap` recomputes the `Heightmaps` of a chunk from it's sections.
+//!
+//! Minecraft keeps track of the highest block in each column for a few different purposes,
+//! which block counts for each heightmap is decided by a small block classification table.
+
+use crate::{
+    ChunkData, Error, Result, data::decode_data, nbt::set_tag, region::get_block_bit_count,
+};
+use simdnbt::owned::{NbtCompound, NbtList, NbtTag};
+use std::ops::Range;
+
+/// The heightmaps stored in a full chunk, in the order of their classification bits.
+pub(crate) const HEIGHTMAPS: [&str; 4] = [
+    "WORLD_SURFACE",
+    "MOTION_BLOCKING",
+    "MOTION_BLOCKING_NO_LEAVES",
+    "OCEAN_FLOOR",
+];
+
+/// Any block that isn't air.
+const WORLD_SURFACE: u8 = 1 << 0;
+/// Blocks that blocks motion or contains a fluid.
+const MOTION_BLOCKING: u8 = 1 << 1;
+/// Like [`MOTION_BLOCKING`] but without leaves.
+const MOTION_BLOCKING_NO_LEAVES: u8 = 1 << 2;
+/// Blocks that blocks motion, fluids doesn't count.
+const OCEAN_FLOOR: u8 = 1 << 3;
+
+/// How many columns a chunk has.
+const COLUMNS: usize = ChunkData::WIDTH * ChunkData::WIDTH;
+
+/// Recomputes all the heightmaps of a chunk from it's sections.
+///
+/// The heights are stored relative to the bottom of the world.
+pub(crate) fn update_heightmaps(chunk: &mut NbtCompound, world_height: Range<isize>) -> Result<()> {
+    let min_y = world_height.start;
+    let mut sections: Vec<&NbtCompound> = match chunk
+        .list("sections")
+        .ok_or(Error::MissingNbtTag("sections"))?
+    {
+        NbtList::Compound(c) => c.iter().collect(),
+        NbtList::Empty => vec![],
+        _ => return Err(Error::InvalidNbtList("sections")),
+    };
+    sections.sort_by_key(|s| std::cmp::Reverse(s.byte("Y").unwrap_or(i8::MIN)));
+
+    // 0 means no block was found in the column
+    let mut heights = [[0i64; COLUMNS]; HEIGHTMAPS.len()];
+    let mut found = [0u8; COLUMNS];
+    let all = WORLD_SURFACE | MOTION_BLOCKING | MOTION_BLOCKING_NO_LEAVES | OCEAN_FLOOR;
+    let mut indexes = [0i64; ChunkData::WIDTH * ChunkData::WIDTH * ChunkData::WIDTH];
+
+    for section in sections {
+        let section_y = section.byte("Y").ok_or(Error::MissingNbtTag("Y"))? as isize;
+        let state = match section.compound("block_states") {
+            Some(state) => state,
+            None => continue,
+        };
+        let palette = state
+            .list("palette")
+            .ok_or(Error::MissingNbtTag("palette"))?
+            .compounds()
+            .ok_or(Error::InvalidNbtType("palette"))?;
+        let flags: Vec<u8> = palette.iter().map(classify).collect();
+
+        // an all air section can't change anything
+        if flags.iter().all(|f| *f == 0) {
+            continue;
+        }
+
+        decode_data(
+            &mut indexes,
+            get_block_bit_count(palette.len()),
+            state.long_array("data"),
+        );
+
+        for y in (0..ChunkData::WIDTH).rev() {
+            let height = (section_y * ChunkData::WIDTH as isize + y as isize + 1 - min_y) as i64;
+            for column in 0..COLUMNS {
+                let missing = !found[column] & all;
+                if missing == 0 {
+                    continue;
+                }
+
+                let palette_index = indexes[column + y * COLUMNS] as usize;
+                let block = *flags
+                    .get(palette_index)
+                    .ok_or(Error::InvalidPaletteIndex(palette_index as i64))?
+                    & missing;
+
+                for (i, heightmap) in heights.iter_mut().enumerate() {
+                    if block & (1 << i) != 0 {
+                        heightmap[column] = height;
+                    }
+                }
+                found[column] |= block;
+            }
+        }
+
+        if found.iter().all(|f| *f == all) {
+            break;
+        }
+    }
+
+    let bit_count = heightmap_bit_count(world_height.count());
+    let mut heightmaps = NbtCompound::new();
+    for (name, heights) in HEIGHTMAPS.iter().zip(heights.iter()) {
+        heightmaps.insert(*name, NbtTag::LongArray(pack_heightmap(heights, bit_count)));
+    }
+    set_tag(chunk, "Heightmaps", NbtTag::Compound(heightmaps));
+
+    Ok(())
+}
+
+/// How many bits each height takes up, enough to store `0..=world_height`.
+pub(crate) fn heightmap_bit_count(world_height: usize) -> u32 {
+    usize::BITS - world_height.leading_zeros()
+}
+
+/// Packs the heights into longs, without any value spanning across two longs.
+pub(crate) fn pack_heightmap(heights: &[i64; COLUMNS], bit_count: u32) -> Vec<i64> {
+    let per_long = (64 / bit_count) as usize;
+    let mut data = Vec::with_capacity(COLUMNS.div_ceil(per_long));
+    for chunk in heights.chunks(per_long) {
+        let mut long = 0i64;
+        for (i, height) in chunk.iter().enumerate() {
+            long |= height << (i as u32 * bit_count);
+        }
+        data.push(long);
+    }
+
+    data
+}
+
+/// Returns which heightmaps a block palette entry counts for.
+fn classify(block: &NbtCompound) -> u8 {
+    let name = match block.string("Name") {
+        Some(name) => name.to_str(),
+        None => return 0,
+    };
+    let id = name.strip_prefix("minecraft:").unwrap_or(&name);
+    if is_air(id) {
+        return 0;
+    }
+
+    let waterlogged = block
+        .compound("Properties")
+        .and_then(|p| p.string("waterlogged"))
+        .is_some_and(|w| w.to_str() == "true");
+    let fluid = waterlogged || is_fluid(id);
+    let solid = blocks_motion(id);
+
+    let mut flags = WORLD_SURFACE;
+    if solid || fluid {
+        flags |= MOTION_BLOCKING;
+        if !id.ends_with("_leaves") {
+            flags |= MOTION_BLOCKING_NO_LEAVES;
+        }
+    }
+    if solid {
+        flags |= OCEAN_FLOOR;
+    }
+
+    flags
+}
+
+/// Air doesn't count for any heightmap.
+pub(crate) fn is_air(id: &str) -> bool {
+    matches!(id, "air" | "cave_air" | "void_air")
+}
+
+/// Blocks that always contain a fluid, other blocks only do when waterlogged.
+pub(crate) fn is_fluid(id: &str) -> bool {
+    matches!(
+        id,
+        "water" | "lava" | "bubble_column" | "kelp" | "kelp_plant" | "seagrass" | "tall_seagrass"
+    )
+}
+
+/// If a block blocks motion, which is any block with a big enough collision box.
+///
+/// Blocks from other namespaces are assumed to be solid.
+pub(crate) fn blocks_motion(id: &str) -> bool {
+    if id.contains(':') {
+        return true;
+    }
+
+    let passable = matches!(
+        id,
+        "water"
+            | "lava"
+            | "bubble_column"
+            | "kelp"
+            | "kelp_plant"
+            | "seagrass"
+            | "tall_seagrass"
+            | "grass"
+            | "short_grass"
+            | "tall_grass"
+            | "fern"
+            | "large_fern"
+            | "dead_bush"
+            | "vine"
+            | "weeping_vines"
+            | "weeping_vines_plant"
+            | "twisting_vines"
+            | "twisting_vines_plant"
+            | "cave_vines"
+            | "cave_vines_plant"
+            | "glow_lichen"
+            | "sculk_vein"
+            | "hanging_roots"
+            | "spore_blossom"
+            | "sugar_cane"
+            | "bamboo_sapling"
+            | "cobweb"
+            | "fire"
+            | "soul_fire"
+            | "redstone_wire"
+            | "tripwire"
+            | "tripwire_hook"
+            | "lever"
+            | "repeater"
+            | "comparator"
+            | "snow"
+            | "nether_portal"
+            | "end_portal"
+            | "light"
+            | "structure_void"
+            | "wheat"
+            | "carrots"
+            | "potatoes"
+            | "beetroots"
+            | "melon_stem"
+            | "pumpkin_stem"
+            | "attached_melon_stem"
+            | "attached_pumpkin_stem"
+            | "nether_wart"
+            | "sweet_berry_bush"
+            | "torchflower_crop"
+            | "pitcher_crop"
+            | "pitcher_plant"
+            | "cocoa"
+            | "dandelion"
+            | "poppy"
+            | "blue_orchid"
+            | "allium"
+            | "azure_bluet"
+            | "oxeye_daisy"
+            | "cornflower"
+            | "lily_of_the_valley"
+            | "wither_rose"
+            | "torchflower"
+            | "sunflower"
+            | "lilac"
+            | "rose_bush"
+            | "peony"
+            | "crimson_roots"
+            | "warped_roots"
+            | "nether_sprouts"
+            | "crimson_fungus"
+            | "warped_fungus"
+            | "brown_mushroom"
+            | "red_mushroom"
+            | "small_dripleaf"
+            | "big_dripleaf_stem"
+            | "pink_petals"
+            | "frogspawn"
+            | "lily_pad"
+            | "sea_pickle"
+            | "turtle_egg"
+            | "flower_pot"
+            | "lantern"
+            | "soul_lantern"
+            | "torch"
+            | "rail"
+            | "candle"
+    );
+
+    !(passable
+        || id.starts_with("potted_")
+        || id.ends_with("_sapling")
+        || id.ends_with("_tulip")
+        || id.ends_with("_torch")
+        || id.ends_with("_sign")
+        || id.ends_with("_banner")
+        || id.ends_with("_rail")
+        || id.ends_with("_button")
+        || id.ends_with("_pressure_plate")
+        || id.ends_with("_carpet")
+        || id.ends_with("_coral")
+        || id.ends_with("_coral_fan")
+        || id.ends_with("_coral_wall_fan")
+        || id.ends_with("_candle")
+        || id.ends_with("_head")
+        || id.ends_with("_skull"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Block, Config, Region};
+
+    fn flags(block: Block) -> u8 {
+        classify(&block.to_compound().unwrap())
+    }
+
+    fn unpack(data: &[i64], bit_count: u32) -> Vec<i64> {
+        let per_long = 64 / bit_count;
+        (0..COLUMNS)
+            .map(|i| {
+                let long = data[i / per_long as usize];
+                (long >> ((i as u32 % per_long) * bit_count)) & ((1 << bit_count) - 1)
+            })
+            .collect()
+    }
+
+    fn heightmap(region: &Region, chunk: (u8, u8), name: &str) -> Vec<i64> {
+        let chunk = region.get_chunk(chunk.0, chunk.1).unwrap().unwrap();
+        let data = chunk
+            .nbt
+            .compound("Heightmaps")
+            .unwrap()
+            .long_array(name)
+            .unwrap()
+            .to_vec();
+        assert_eq!(data.len(), 37);
+        unpack(&data, 9)
+    }
+
+    #[test]
+    fn classification() {
+        let all = WORLD_SURFACE | MOTION_BLOCKING | MOTION_BLOCKING_NO_LEAVES | OCEAN_FLOOR;
+        assert_eq!(flags(Block::new("minecraft:stone")), all);
+        assert_eq!(flags(Block::new("minecraft:air")), 0);
+        assert_eq!(
+            flags(Block::new("minecraft:oak_leaves")),
+            WORLD_SURFACE | MOTION_BLOCKING | OCEAN_FLOOR
+        );
+        assert_eq!(
+            flags(Block::new("minecraft:water")),
+            WORLD_SURFACE | MOTION_BLOCKING | MOTION_BLOCKING_NO_LEAVES
+        );
+        assert_eq!(flags(Block::new("minecraft:poppy")), WORLD_SURFACE);
+        assert_eq!(
+            flags(Block::new_with_props("oak_sign", [("waterlogged", "true")])),
+            WORLD_SURFACE | MOTION_BLOCKING | MOTION_BLOCKING_NO_LEAVES
+        );
+    }
+
+    #[test]
+    fn bit_count() {
+        assert_eq!(heightmap_bit_count(384), 9);
+        assert_eq!(heightmap_bit_count(256), 9);
+        assert_eq!(heightmap_bit_count(255), 8);
+    }
+
+    #[test]
+    fn write_blocks_updates_heightmaps() -> Result<()> {
+        let mut region = Region::default();
+        region.set_config(Config {
+            update_heightmaps: true,
+            ..Default::default()
+        })?;
+
+        // stone with leaves on top
+        region.set_block((3, 70, 5), "minecraft:stone")?;
+        region.set_block((3, 80, 5), "minecraft:oak_leaves")?;
+        // stone with water on top
+        region.set_block((4, 70, 5), "minecraft:stone")?;
+        region.set_block((4, 71, 5), "minecraft:water")?;
+        // just a flower
+        region.set_block((5, -64, 5), "minecraft:poppy")?;
+        region.write_blocks()?;
+
+        let (leaves, water, flower, empty) = (3 + 5 * 16, 4 + 5 * 16, 5 + 5 * 16, 0);
+        let world_surface = heightmap(&region, (0, 0), "WORLD_SURFACE");
+        let motion_blocking = heightmap(&region, (0, 0), "MOTION_BLOCKING");
+        let no_leaves = heightmap(&region, (0, 0), "MOTION_BLOCKING_NO_LEAVES");
+        let ocean_floor = heightmap(&region, (0, 0), "OCEAN_FLOOR");
+
+        assert_eq!(
+            [
+                world_surface[leaves],
+                motion_blocking[leaves],
+                no_leaves[leaves],
+                ocean_floor[leaves]
+            ],
+            [145, 145, 135, 145]
+        );
+        assert_eq!(
+            [
+                world_surface[water],
+                motion_blocking[water],
+                no_leaves[water],
+                ocean_floor[water]
+            ],
+            [136, 136, 136, 135]
+        );
+        assert_eq!([world_surface[flower], motion_blocking[flower]], [1, 0]);
+        assert_eq!(world_surface[empty], 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn set_section_updates_heightmaps() -> Result<()> {
+        let mut region = Region::default();
+        region.set_config(Config {
+            update_heightmaps: true,
+            ..Default::default()
+        })?;
+
+        region.set_section((2, 7), 2, "minecraft:stone")?;
+        assert!(
+            heightmap(&region, (2, 7), "OCEAN_FLOOR")
+                .iter()
+                .all(|h| *h == 112)
+        );
+
+        Ok(())
+    }
+}
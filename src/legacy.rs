@@ -0,0 +1,1030 @@
+//! `legacy` converts numeric block ids & data values from before the flattening in Minecraft 1.13 into [`Block`]s.
+//!
+//! The blocks are converted to their names as of [`Region::MIN_DATA_VERSION`](crate::Region::MIN_DATA_VERSION),
+//! blocks that got renamed since then are renamed when they're pasted into newer chunks.
+
+use crate::{Block, Error, Name, Result, nbt::set_tag};
+use simdnbt::owned::{NbtCompound, NbtList, NbtTag};
+
+/// The 16 colors in the order of their legacy data value.
+const COLORS: [&str; 16] = [
+    "white",
+    "orange",
+    "magenta",
+    "light_blue",
+    "yellow",
+    "lime",
+    "pink",
+    "gray",
+    "light_gray",
+    "cyan",
+    "purple",
+    "blue",
+    "brown",
+    "green",
+    "red",
+    "black",
+];
+/// The wood types in the order of their legacy data value.
+const WOODS: [&str; 6] = ["oak", "spruce", "birch", "jungle", "acacia", "dark_oak"];
+/// The direction of a 3D facing, by data value.
+const FACING: [&str; 6] = ["down", "up", "north", "south", "west", "east"];
+/// The direction of a horizontal facing, by data value.
+const HORIZONTAL: [&str; 4] = ["south", "west", "north", "east"];
+/// The flowers of `red_flower`, by data value.
+const FLOWERS: [&str; 9] = [
+    "poppy",
+    "blue_orchid",
+    "allium",
+    "azure_bluet",
+    "red_tulip",
+    "orange_tulip",
+    "white_tulip",
+    "pink_tulip",
+    "oxeye_daisy",
+];
+/// Blocks renamed since [`Region::MIN_DATA_VERSION`](crate::Region::MIN_DATA_VERSION),
+/// as `(DataVersion, old name, new name)` in the order they were renamed.
+const RENAMES: [(i32, &str, &str); 1] = [
+    // 23w46a, 1.20.3
+    (3692, "minecraft:grass", "minecraft:short_grass"),
+];
+/// The skull types by the `SkullType` of their block entity, as `(floor, wall)`.
+const SKULLS: [(&str, &str); 6] = [
+    ("skeleton_skull", "skeleton_wall_skull"),
+    ("wither_skeleton_skull", "wither_skeleton_wall_skull"),
+    ("zombie_head", "zombie_wall_head"),
+    ("player_head", "player_wall_head"),
+    ("creeper_head", "creeper_wall_head"),
+    ("dragon_head", "dragon_wall_head"),
+];
+
+/// If the block saved in `data_version` got renamed in a later version.
+pub(crate) fn renamed_since(block: &Block, data_version: i32) -> bool {
+    let name = block.name.into_cow_namespaced();
+    RENAMES
+        .iter()
+        .any(|(version, old, _)| *version > data_version && *name == *old)
+}
+
+/// Renames a block saved in the `from` DataVersion to it's name in the `to` DataVersion.
+pub(crate) fn rename_block(mut block: Block, from: i32, to: i32) -> Block {
+    for (version, old, new) in RENAMES {
+        if from < version && version <= to && *block.name.into_cow_namespaced() == old {
+            block.name = Name::new_namespace(new);
+        }
+    }
+
+    block
+}
+
+impl Block {
+    /// Converts a numeric block id & data value from before Minecraft 1.13 into a [`Block`].
+    ///
+    /// Data values the block doesn't use fall back to it's first data value, just like Minecraft does.
+    /// Beds, banners, skulls & flower pots get their color or type from their block entity
+    /// and doors & tall plants from their other half, which is only known when reading a whole schematic.
+    ///
+    /// ## Example
+    /// ```
+    /// # use silverfish::Block;
+    /// let stairs = Block::from_legacy(53, 6)?;
+    /// assert_eq!(stairs.to_string(), "minecraft:oak_stairs[facing=south,half=top]");
+    /// # Ok::<(), silverfish::Error>(())
+    /// ```
+    pub fn from_legacy(id: u16, data: u8) -> Result<Self> {
+        legacy_state(id, data & 15)
+            .or_else(|| legacy_state(id, 0))
+            .ok_or(Error::UnknownLegacyBlock(id, data))?
+            .parse()
+    }
+}
+
+/// Converts a legacy block to a block state string, using the blocks below & above it
+/// and it's block entity for what the data value alone doesn't tell.
+pub(crate) fn legacy_state_in_context(
+    (id, data): (u16, u8),
+    below: (u16, u8),
+    above: (u16, u8),
+    block_entity: Option<&NbtCompound>,
+) -> Result<String> {
+    let state = match (id, block_entity) {
+        // the lower half of a door has it's facing, the upper half it's hinge
+        (64 | 71 | 193..=197, _) => door_name(id).map(|name| match data & 8 {
+            0 => door(name, data, if above.0 == id { above.1 } else { 0 }, "lower"),
+            _ => door(name, if below.0 == id { below.1 } else { 0 }, data, "upper"),
+        }),
+        // the upper half of a tall plant doesn't know which plant it is
+        (175, _) if data & 8 != 0 && below.0 == id => double_plant(below.1, "upper"),
+        (26 | 140 | 144 | 176 | 177, Some(block_entity)) => {
+            block_entity_state(id, data, block_entity)
+        }
+        _ => None,
+    };
+
+    state
+        .or_else(|| legacy_state(id, data))
+        .or_else(|| legacy_state(id, 0))
+        .ok_or(Error::UnknownLegacyBlock(id, data))
+}
+
+/// Converts a legacy block entity, returning `None` for block entities that don't exist anymore.
+///
+/// Only the id & what moved into the block state changes, everything else is kept as is.
+pub(crate) fn legacy_block_entity(block_entity: &NbtCompound) -> Option<NbtCompound> {
+    let id = block_entity.string("id")?.to_str();
+    let id = id.strip_prefix("minecraft:").unwrap_or(&id);
+    // before 1.11 block entities had their own names
+    let id = match id {
+        "Airportal" => "end_portal",
+        "Banner" => "banner",
+        "Beacon" => "beacon",
+        "Cauldron" => "brewing_stand",
+        "Chest" => "chest",
+        "Comparator" => "comparator",
+        "Control" => "command_block",
+        "DLDetector" => "daylight_detector",
+        "Dropper" => "dropper",
+        "EnchantTable" => "enchanting_table",
+        "EndGateway" => "end_gateway",
+        "EnderChest" => "ender_chest",
+        "Furnace" => "furnace",
+        "Hopper" => "hopper",
+        "MobSpawner" => "mob_spawner",
+        "Piston" => "piston",
+        "RecordPlayer" => "jukebox",
+        "Sign" => "sign",
+        "Skull" => "skull",
+        "Structure" => "structure_block",
+        "Trap" => "dispenser",
+        // note blocks & flower pots only have block states now
+        "Music" | "noteblock" | "FlowerPot" | "flower_pot" => return None,
+        id => id,
+    };
+
+    let mut block_entity = block_entity.clone();
+    set_tag(
+        &mut block_entity,
+        "id",
+        NbtTag::String(format!("minecraft:{id}").as_str().into()),
+    );
+    for tag in ["color", "SkullType", "Rot", "Base"] {
+        block_entity.remove(tag);
+    }
+
+    // banner patterns used the dye order, which is the reverse of the color order
+    if let Some(NbtList::Compound(patterns)) = block_entity.list_mut("Patterns") {
+        for pattern in patterns {
+            if let Some(color) = pattern.int("Color") {
+                set_tag(pattern, "Color", NbtTag::Int(15 - (color & 15)));
+            }
+        }
+    }
+
+    Some(block_entity)
+}
+
+/// Converts the block of a block entity, which stored it's color or type there instead of in the data value.
+fn block_entity_state(id: u16, data: u8, block_entity: &NbtCompound) -> Option<String> {
+    match id {
+        26 => {
+            let color = COLORS.get(block_entity.int("color")? as usize)?;
+            Some(bed(color, data))
+        }
+        140 => potted_plant(block_entity).map(|plant| state(&format!("potted_{plant}"), &[])),
+        144 => {
+            let (floor, wall) = SKULLS.get(block_entity.byte("SkullType")? as usize)?;
+            Some(match data & 7 {
+                0 | 1 => {
+                    let rotation = block_entity.byte("Rot").unwrap_or_default() & 15;
+                    state(floor, &[("rotation", &rotation.to_string())])
+                }
+                _ => state(wall, &[("facing", wall_facing(data))]),
+            })
+        }
+        176 | 177 => {
+            // banners used the dye order, which is the reverse of the color order
+            let color = COLORS[15 - (block_entity.int("Base").unwrap_or_default() & 15) as usize];
+            Some(banner(color, id, data))
+        }
+        _ => None,
+    }
+}
+
+/// The plant in a flower pot, the item can either be a numeric or a named id.
+fn potted_plant(block_entity: &NbtCompound) -> Option<String> {
+    let data = block_entity.int("Data").unwrap_or_default() as usize;
+    let item = match block_entity.int("Item") {
+        Some(id) => match id {
+            6 => "sapling",
+            31 => "tallgrass",
+            32 => "deadbush",
+            37 => "yellow_flower",
+            38 => "red_flower",
+            39 => "brown_mushroom",
+            40 => "red_mushroom",
+            81 => "cactus",
+            _ => return None,
+        }
+        .to_owned(),
+        None => {
+            let item = block_entity.string("Item")?.to_str();
+            item.strip_prefix("minecraft:").unwrap_or(&item).to_owned()
+        }
+    };
+
+    Some(match item.as_str() {
+        "sapling" => format!("{}_sapling", WOODS.get(data)?),
+        "tallgrass" if data == 2 => String::from("fern"),
+        "deadbush" | "tallgrass" => String::from("dead_bush"),
+        "yellow_flower" => String::from("dandelion"),
+        "red_flower" => FLOWERS.get(data)?.to_string(),
+        "brown_mushroom" | "red_mushroom" | "cactus" => item,
+        _ => return None,
+    })
+}
+
+/// Converts a legacy block to a block state string by only it's id & data value.
+fn legacy_state(id: u16, data: u8) -> Option<String> {
+    let d = data as usize;
+    let bit = |mask: u8| if data & mask != 0 { "true" } else { "false" };
+    let num = |value: u8| value.to_string();
+    let facing = FACING.get(d & 7).copied();
+    let horizontal = HORIZONTAL[d & 3];
+    let slab_type = if data & 8 != 0 { "top" } else { "bottom" };
+    let axis = ["y", "x", "z"].get((d >> 2) & 3).copied();
+
+    Some(match id {
+        0 => state("air", &[]),
+        1 => state(
+            [
+                "stone",
+                "granite",
+                "polished_granite",
+                "diorite",
+                "polished_diorite",
+                "andesite",
+                "polished_andesite",
+            ]
+            .get(d)?,
+            &[],
+        ),
+        2 => state("grass_block", &[("snowy", "false")]),
+        3 => state(["dirt", "coarse_dirt", "podzol"].get(d)?, &[]),
+        4 => state("cobblestone", &[]),
+        5 => state(&format!("{}_planks", WOODS.get(d)?), &[]),
+        6 => state(
+            &format!("{}_sapling", WOODS.get(d & 7)?),
+            &[("stage", &num((data >> 3) & 1))],
+        ),
+        7 => state("bedrock", &[]),
+        8 | 9 => state("water", &[("level", &num(data))]),
+        10 | 11 => state("lava", &[("level", &num(data))]),
+        12 => state(["sand", "red_sand"].get(d)?, &[]),
+        13 => state("gravel", &[]),
+        14 => state("gold_ore", &[]),
+        15 => state("iron_ore", &[]),
+        16 => state("coal_ore", &[]),
+        17 => log(WOODS[d & 3], data),
+        18 => state(
+            &format!("{}_leaves", WOODS[d & 3]),
+            &[("persistent", bit(4))],
+        ),
+        19 => state(["sponge", "wet_sponge"].get(d)?, &[]),
+        20 => state("glass", &[]),
+        21 => state("lapis_ore", &[]),
+        22 => state("lapis_block", &[]),
+        23 => state("dispenser", &[("facing", facing?), ("triggered", bit(8))]),
+        24 => state(
+            ["sandstone", "chiseled_sandstone", "cut_sandstone"].get(d)?,
+            &[],
+        ),
+        25 => state("note_block", &[]),
+        26 => bed("red", data),
+        27 => rail("powered_rail", data & 7, Some(bit(8)))?,
+        28 => rail("detector_rail", data & 7, Some(bit(8)))?,
+        29 => state(
+            "sticky_piston",
+            &[("facing", facing?), ("extended", bit(8))],
+        ),
+        30 => state("cobweb", &[]),
+        31 => state(["dead_bush", "grass", "fern"].get(d)?, &[]),
+        32 => state("dead_bush", &[]),
+        33 => state("piston", &[("facing", facing?), ("extended", bit(8))]),
+        34 | 36 => state(
+            if id == 34 {
+                "piston_head"
+            } else {
+                "moving_piston"
+            },
+            &[
+                ("facing", facing?),
+                ("type", if data & 8 != 0 { "sticky" } else { "normal" }),
+            ],
+        ),
+        35 => state(&format!("{}_wool", COLORS[d]), &[]),
+        37 => state("dandelion", &[]),
+        38 => state(FLOWERS.get(d)?, &[]),
+        39 => state("brown_mushroom", &[]),
+        40 => state("red_mushroom", &[]),
+        41 => state("gold_block", &[]),
+        42 => state("iron_block", &[]),
+        43 => match d {
+            8 => state("smooth_stone", &[]),
+            9 => state("smooth_sandstone", &[]),
+            15 => state("smooth_quartz", &[]),
+            _ => state(STONE_SLABS.get(d)?, &[("type", "double")]),
+        },
+        44 => state(STONE_SLABS[d & 7], &[("type", slab_type)]),
+        45 => state("bricks", &[]),
+        46 => state("tnt", &[("unstable", bit(1))]),
+        47 => state("bookshelf", &[]),
+        48 => state("mossy_cobblestone", &[]),
+        49 => state("obsidian", &[]),
+        50 => torch("torch", "wall_torch", data, None),
+        51 => state("fire", &[("age", &num(data))]),
+        52 => state("spawner", &[]),
+        53 => stairs("oak_stairs", data),
+        54 => state("chest", &[("facing", wall_facing(data))]),
+        55 => state("redstone_wire", &[("power", &num(data))]),
+        56 => state("diamond_ore", &[]),
+        57 => state("diamond_block", &[]),
+        58 => state("crafting_table", &[]),
+        59 => state("wheat", &[("age", &num(data & 7))]),
+        60 => state("farmland", &[("moisture", &num(data & 7))]),
+        61 | 62 => state(
+            "furnace",
+            &[
+                ("facing", wall_facing(data)),
+                ("lit", if id == 62 { "true" } else { "false" }),
+            ],
+        ),
+        63 => state("oak_sign", &[("rotation", &num(data))]),
+        64 | 71 | 193..=197 => {
+            let name = door_name(id)?;
+            match data & 8 {
+                0 => door(name, data, 0, "lower"),
+                _ => door(name, 0, data, "upper"),
+            }
+        }
+        65 => state("ladder", &[("facing", wall_facing(data))]),
+        66 => rail("rail", data, None)?,
+        67 => stairs("cobblestone_stairs", data),
+        68 => state("oak_wall_sign", &[("facing", wall_facing(data))]),
+        69 => {
+            let (face, facing) = [
+                ("ceiling", "west"),
+                ("wall", "east"),
+                ("wall", "west"),
+                ("wall", "south"),
+                ("wall", "north"),
+                ("floor", "north"),
+                ("floor", "west"),
+                ("ceiling", "north"),
+            ][d & 7];
+            state(
+                "lever",
+                &[("face", face), ("facing", facing), ("powered", bit(8))],
+            )
+        }
+        70 => state("stone_pressure_plate", &[("powered", bit(1))]),
+        72 => state("oak_pressure_plate", &[("powered", bit(1))]),
+        73 | 74 => state(
+            "redstone_ore",
+            &[("lit", if id == 74 { "true" } else { "false" })],
+        ),
+        75 | 76 => torch(
+            "redstone_torch",
+            "redstone_wall_torch",
+            data,
+            Some(if id == 76 { "true" } else { "false" }),
+        ),
+        77 => button("stone_button", data)?,
+        78 => state("snow", &[("layers", &num((data & 7) + 1))]),
+        79 => state("ice", &[]),
+        80 => state("snow_block", &[]),
+        81 => state("cactus", &[("age", &num(data))]),
+        82 => state("clay", &[]),
+        83 => state("sugar_cane", &[("age", &num(data))]),
+        84 => state("jukebox", &[("has_record", bit(1))]),
+        85 => state("oak_fence", &[]),
+        86 => state("carved_pumpkin", &[("facing", horizontal)]),
+        87 => state("netherrack", &[]),
+        88 => state("soul_sand", &[]),
+        89 => state("glowstone", &[]),
+        90 => state(
+            "nether_portal",
+            &[("axis", if data == 2 { "z" } else { "x" })],
+        ),
+        91 => state("jack_o_lantern", &[("facing", horizontal)]),
+        92 => state("cake", &[("bites", &num(data.min(6)))]),
+        93 | 94 => state(
+            "repeater",
+            &[
+                ("facing", horizontal),
+                ("delay", &num((data >> 2) + 1)),
+                ("powered", if id == 94 { "true" } else { "false" }),
+            ],
+        ),
+        95 => state(&format!("{}_stained_glass", COLORS[d]), &[]),
+        96 => trapdoor("oak_trapdoor", data),
+        97 => state(
+            [
+                "infested_stone",
+                "infested_cobblestone",
+                "infested_stone_bricks",
+                "infested_mossy_stone_bricks",
+                "infested_cracked_stone_bricks",
+                "infested_chiseled_stone_bricks",
+            ]
+            .get(d)?,
+            &[],
+        ),
+        98 => state(
+            [
+                "stone_bricks",
+                "mossy_stone_bricks",
+                "cracked_stone_bricks",
+                "chiseled_stone_bricks",
+            ]
+            .get(d)?,
+            &[],
+        ),
+        99 => mushroom_block("brown_mushroom_block", data),
+        100 => mushroom_block("red_mushroom_block", data),
+        101 => state("iron_bars", &[]),
+        102 => state("glass_pane", &[]),
+        103 => state("melon", &[]),
+        104 => state("pumpkin_stem", &[("age", &num(data & 7))]),
+        105 => state("melon_stem", &[("age", &num(data & 7))]),
+        106 => state(
+            "vine",
+            &[
+                ("south", bit(1)),
+                ("west", bit(2)),
+                ("north", bit(4)),
+                ("east", bit(8)),
+                ("up", if data == 0 { "true" } else { "false" }),
+            ],
+        ),
+        107 => fence_gate("oak", data),
+        108 => stairs("brick_stairs", data),
+        109 => stairs("stone_brick_stairs", data),
+        110 => state("mycelium", &[("snowy", "false")]),
+        111 => state("lily_pad", &[]),
+        112 => state("nether_bricks", &[]),
+        113 => state("nether_brick_fence", &[]),
+        114 => stairs("nether_brick_stairs", data),
+        115 => state("nether_wart", &[("age", &num(data & 3))]),
+        116 => state("enchanting_table", &[]),
+        117 => state(
+            "brewing_stand",
+            &[
+                ("has_bottle_0", bit(1)),
+                ("has_bottle_1", bit(2)),
+                ("has_bottle_2", bit(4)),
+            ],
+        ),
+        // cauldrons with water became their own block in 1.17
+        118 => match data & 3 {
+            0 => state("cauldron", &[]),
+            level => state("water_cauldron", &[("level", &num(level))]),
+        },
+        119 => state("end_portal", &[]),
+        120 => state(
+            "end_portal_frame",
+            &[("facing", horizontal), ("eye", bit(4))],
+        ),
+        121 => state("end_stone", &[]),
+        122 => state("dragon_egg", &[]),
+        123 | 124 => state(
+            "redstone_lamp",
+            &[("lit", if id == 124 { "true" } else { "false" })],
+        ),
+        125 => state(
+            &format!("{}_slab", WOODS.get(d & 7)?),
+            &[("type", "double")],
+        ),
+        126 => state(
+            &format!("{}_slab", WOODS.get(d & 7)?),
+            &[("type", slab_type)],
+        ),
+        127 => state(
+            "cocoa",
+            &[("facing", horizontal), ("age", &num((data >> 2).min(2)))],
+        ),
+        128 => stairs("sandstone_stairs", data),
+        129 => state("emerald_ore", &[]),
+        130 => state("ender_chest", &[("facing", wall_facing(data))]),
+        131 => state(
+            "tripwire_hook",
+            &[
+                ("facing", horizontal),
+                ("attached", bit(4)),
+                ("powered", bit(8)),
+            ],
+        ),
+        132 => state(
+            "tripwire",
+            &[
+                ("powered", bit(1)),
+                ("attached", bit(4)),
+                ("disarmed", bit(8)),
+            ],
+        ),
+        133 => state("emerald_block", &[]),
+        134 => stairs("spruce_stairs", data),
+        135 => stairs("birch_stairs", data),
+        136 => stairs("jungle_stairs", data),
+        137 | 210 | 211 => state(
+            match id {
+                137 => "command_block",
+                210 => "repeating_command_block",
+                _ => "chain_command_block",
+            },
+            &[("facing", facing?), ("conditional", bit(8))],
+        ),
+        138 => state("beacon", &[]),
+        139 => state(["cobblestone_wall", "mossy_cobblestone_wall"].get(d)?, &[]),
+        140 => state("flower_pot", &[]),
+        141 => state("carrots", &[("age", &num(data & 7))]),
+        142 => state("potatoes", &[("age", &num(data & 7))]),
+        143 => button("oak_button", data)?,
+        144 => match data & 7 {
+            0 | 1 => state("skeleton_skull", &[("rotation", "0")]),
+            _ => state("skeleton_wall_skull", &[("facing", wall_facing(data))]),
+        },
+        145 => state(
+            ["anvil", "chipped_anvil", "damaged_anvil"].get(d >> 2)?,
+            &[("facing", horizontal)],
+        ),
+        146 => state("trapped_chest", &[("facing", wall_facing(data))]),
+        147 => state("light_weighted_pressure_plate", &[("power", &num(data))]),
+        148 => state("heavy_weighted_pressure_plate", &[("power", &num(data))]),
+        149 | 150 => state(
+            "comparator",
+            &[
+                ("facing", horizontal),
+                ("mode", if data & 4 != 0 { "subtract" } else { "compare" }),
+                ("powered", if id == 150 { "true" } else { bit(8) }),
+            ],
+        ),
+        151 | 178 => state(
+            "daylight_detector",
+            &[
+                ("power", &num(data)),
+                ("inverted", if id == 178 { "true" } else { "false" }),
+            ],
+        ),
+        152 => state("redstone_block", &[]),
+        153 => state("nether_quartz_ore", &[]),
+        154 => state(
+            "hopper",
+            &[
+                ("facing", facing.filter(|f| *f != "up")?),
+                ("enabled", if data & 8 == 0 { "true" } else { "false" }),
+            ],
+        ),
+        155 => match d {
+            0 => state("quartz_block", &[]),
+            1 => state("chiseled_quartz_block", &[]),
+            2..=4 => state("quartz_pillar", &[("axis", ["y", "x", "z"][d - 2])]),
+            _ => return None,
+        },
+        156 => stairs("quartz_stairs", data),
+        157 => rail("activator_rail", data & 7, Some(bit(8)))?,
+        158 => state("dropper", &[("facing", facing?), ("triggered", bit(8))]),
+        159 => state(&format!("{}_terracotta", COLORS[d]), &[]),
+        160 => state(&format!("{}_stained_glass_pane", COLORS[d]), &[]),
+        161 => state(
+            &format!("{}_leaves", WOODS[4 + (d & 1)]),
+            &[("persistent", bit(4))],
+        ),
+        162 => log(WOODS[4 + (d & 1)], data),
+        163 => stairs("acacia_stairs", data),
+        164 => stairs("dark_oak_stairs", data),
+        165 => state("slime_block", &[]),
+        166 => state("barrier", &[]),
+        167 => trapdoor("iron_trapdoor", data),
+        168 => state(
+            ["prismarine", "prismarine_bricks", "dark_prismarine"].get(d)?,
+            &[],
+        ),
+        169 => state("sea_lantern", &[]),
+        170 => state("hay_block", &[("axis", axis?)]),
+        171 => state(&format!("{}_carpet", COLORS[d]), &[]),
+        172 => state("terracotta", &[]),
+        173 => state("coal_block", &[]),
+        174 => state("packed_ice", &[]),
+        175 => match data & 8 {
+            0 => double_plant(data, "lower")?,
+            // the upper half doesn't know which plant it is
+            _ => double_plant(0, "upper")?,
+        },
+        176 | 177 => banner("white", id, data),
+        179 => state(
+            [
+                "red_sandstone",
+                "chiseled_red_sandstone",
+                "cut_red_sandstone",
+            ]
+            .get(d)?,
+            &[],
+        ),
+        180 => stairs("red_sandstone_stairs", data),
+        181 => match d {
+            8 => state("smooth_red_sandstone", &[]),
+            _ => state("red_sandstone_slab", &[("type", "double")]),
+        },
+        182 => state("red_sandstone_slab", &[("type", slab_type)]),
+        183..=187 => fence_gate(
+            ["spruce", "birch", "jungle", "dark_oak", "acacia"][id as usize - 183],
+            data,
+        ),
+        188..=192 => state(
+            &format!(
+                "{}_fence",
+                ["spruce", "birch", "jungle", "dark_oak", "acacia"][id as usize - 188]
+            ),
+            &[],
+        ),
+        198 => state("end_rod", &[("facing", facing?)]),
+        199 => state("chorus_plant", &[]),
+        200 => state("chorus_flower", &[("age", &num(data.min(5)))]),
+        201 => state("purpur_block", &[]),
+        202 => state("purpur_pillar", &[("axis", axis?)]),
+        203 => stairs("purpur_stairs", data),
+        204 => state("purpur_slab", &[("type", "double")]),
+        205 => state("purpur_slab", &[("type", slab_type)]),
+        206 => state("end_stone_bricks", &[]),
+        207 => state("beetroots", &[("age", &num(data & 3))]),
+        // renamed from grass path in 1.17
+        208 => state("dirt_path", &[]),
+        209 => state("end_gateway", &[]),
+        212 => state("frosted_ice", &[("age", &num(data & 3))]),
+        213 => state("magma_block", &[]),
+        214 => state("nether_wart_block", &[]),
+        215 => state("red_nether_bricks", &[]),
+        216 => state("bone_block", &[("axis", axis?)]),
+        217 => state("structure_void", &[]),
+        218 => state("observer", &[("facing", facing?), ("powered", bit(8))]),
+        219..=234 => state(
+            &format!("{}_shulker_box", COLORS[id as usize - 219]),
+            &[("facing", facing?)],
+        ),
+        235..=250 => state(
+            &format!("{}_glazed_terracotta", COLORS[id as usize - 235]),
+            &[("facing", horizontal)],
+        ),
+        251 => state(&format!("{}_concrete", COLORS[d]), &[]),
+        252 => state(&format!("{}_concrete_powder", COLORS[d]), &[]),
+        255 => state(
+            "structure_block",
+            &[("mode", ["save", "load", "corner", "data"].get(d)?)],
+        ),
+        _ => return None,
+    })
+}
+
+/// The slabs of `stone_slab` & `double_stone_slab`, by data value.
+const STONE_SLABS: [&str; 8] = [
+    "smooth_stone_slab",
+    "sandstone_slab",
+    "petrified_oak_slab",
+    "cobblestone_slab",
+    "brick_slab",
+    "stone_brick_slab",
+    "nether_brick_slab",
+    "quartz_slab",
+];
+
+fn state(name: &str, properties: &[(&str, &str)]) -> String {
+    if properties.is_empty() {
+        return name.to_owned();
+    }
+
+    let properties = properties
+        .iter()
+        .map(|(k, v)| format!("{k}={v}"))
+        .collect::<Vec<String>>()
+        .join(",");
+    format!("{name}[{properties}]")
+}
+
+/// The facing of blocks placed against a wall, anything but `2..=5` is north.
+fn wall_facing(data: u8) -> &'static str {
+    match data & 7 {
+        3 => "south",
+        4 => "west",
+        5 => "east",
+        _ => "north",
+    }
+}
+
+fn stairs(name: &str, data: u8) -> String {
+    state(
+        name,
+        &[
+            (
+                "facing",
+                ["east", "west", "south", "north"][data as usize & 3],
+            ),
+            ("half", if data & 4 != 0 { "top" } else { "bottom" }),
+        ],
+    )
+}
+
+fn log(wood: &str, data: u8) -> String {
+    match (data >> 2) & 3 {
+        // logs with bark on all sides
+        3 => state(&format!("{wood}_wood"), &[("axis", "y")]),
+        axis => state(
+            &format!("{wood}_log"),
+            &[("axis", ["y", "x", "z"][axis as usize])],
+        ),
+    }
+}
+
+fn rail(name: &str, shape: u8, powered: Option<&str>) -> Option<String> {
+    let shape = [
+        "north_south",
+        "east_west",
+        "ascending_east",
+        "ascending_west",
+        "ascending_north",
+        "ascending_south",
+        "south_east",
+        "south_west",
+        "north_west",
+        "north_east",
+    ]
+    .get(shape as usize)?;
+
+    Some(match powered {
+        Some(powered) => state(name, &[("shape", shape), ("powered", powered)]),
+        None => state(name, &[("shape", shape)]),
+    })
+}
+
+fn torch(name: &str, wall_name: &str, data: u8, lit: Option<&str>) -> String {
+    let mut properties = vec![];
+    // torches on the floor are 5, but anything else that isn't a wall works too
+    let name = match data {
+        1..=4 => {
+            properties.push((
+                "facing",
+                ["east", "west", "south", "north"][data as usize - 1],
+            ));
+            wall_name
+        }
+        _ => name,
+    };
+    properties.extend(lit.map(|lit| ("lit", lit)));
+
+    state(name, &properties)
+}
+
+fn button(name: &str, data: u8) -> Option<String> {
+    let (face, facing) = [
+        ("ceiling", "north"),
+        ("wall", "east"),
+        ("wall", "west"),
+        ("wall", "south"),
+        ("wall", "north"),
+        ("floor", "north"),
+    ]
+    .get(data as usize & 7)?;
+    let powered = if data & 8 != 0 { "true" } else { "false" };
+
+    Some(state(
+        name,
+        &[("face", face), ("facing", facing), ("powered", powered)],
+    ))
+}
+
+fn trapdoor(name: &str, data: u8) -> String {
+    state(
+        name,
+        &[
+            (
+                "facing",
+                ["north", "south", "west", "east"][data as usize & 3],
+            ),
+            ("open", if data & 4 != 0 { "true" } else { "false" }),
+            ("half", if data & 8 != 0 { "top" } else { "bottom" }),
+        ],
+    )
+}
+
+fn fence_gate(wood: &str, data: u8) -> String {
+    state(
+        &format!("{wood}_fence_gate"),
+        &[
+            ("facing", HORIZONTAL[data as usize & 3]),
+            ("open", if data & 4 != 0 { "true" } else { "false" }),
+            ("powered", if data & 8 != 0 { "true" } else { "false" }),
+        ],
+    )
+}
+
+fn door_name(id: u16) -> Option<&'static str> {
+    Some(match id {
+        64 => "oak_door",
+        71 => "iron_door",
+        193 => "spruce_door",
+        194 => "birch_door",
+        195 => "jungle_door",
+        196 => "acacia_door",
+        197 => "dark_oak_door",
+        _ => return None,
+    })
+}
+
+/// The lower half of a door has the facing & if it's open, the upper half the hinge & if it's powered.
+fn door(name: &str, lower: u8, upper: u8, half: &str) -> String {
+    state(
+        name,
+        &[
+            (
+                "facing",
+                ["east", "south", "west", "north"][lower as usize & 3],
+            ),
+            ("open", if lower & 4 != 0 { "true" } else { "false" }),
+            ("hinge", if upper & 1 != 0 { "right" } else { "left" }),
+            ("powered", if upper & 2 != 0 { "true" } else { "false" }),
+            ("half", half),
+        ],
+    )
+}
+
+/// The plant is only known from the data value of the lower half.
+fn double_plant(lower: u8, half: &str) -> Option<String> {
+    let name = [
+        "sunflower",
+        "lilac",
+        "tall_grass",
+        "large_fern",
+        "rose_bush",
+        "peony",
+    ]
+    .get(lower as usize & 7)?;
+
+    Some(state(name, &[("half", half)]))
+}
+
+fn mushroom_block(name: &str, data: u8) -> String {
+    // which sides show the cap, as north, east, south, west, up & down
+    let sides: [bool; 6] = match data {
+        1 => [true, false, false, true, true, false],
+        2 => [true, false, false, false, true, false],
+        3 => [true, true, false, false, true, false],
+        4 => [false, false, false, true, true, false],
+        5 => [false, false, false, false, true, false],
+        6 => [false, true, false, false, true, false],
+        7 => [false, false, true, true, true, false],
+        8 => [false, false, true, false, true, false],
+        9 => [false, true, true, false, true, false],
+        10 => [true, true, true, true, false, false],
+        14 => [true; 6],
+        15 => [true; 6],
+        _ => [false; 6],
+    };
+    let name = match data {
+        10 | 15 => "mushroom_stem",
+        _ => name,
+    };
+
+    let sides = ["north", "east", "south", "west", "up", "down"]
+        .into_iter()
+        .zip(sides)
+        .map(|(side, cap)| (side, if cap { "true" } else { "false" }))
+        .collect::<Vec<_>>();
+    state(name, &sides)
+}
+
+fn bed(color: &str, data: u8) -> String {
+    state(
+        &format!("{color}_bed"),
+        &[
+            ("facing", HORIZONTAL[data as usize & 3]),
+            ("occupied", if data & 4 != 0 { "true" } else { "false" }),
+            ("part", if data & 8 != 0 { "head" } else { "foot" }),
+        ],
+    )
+}
+
+fn banner(color: &str, id: u16, data: u8) -> String {
+    match id {
+        176 => state(
+            &format!("{color}_banner"),
+            &[("rotation", &(data & 15).to_string())],
+        ),
+        _ => state(
+            &format!("{color}_wall_banner"),
+            &[("facing", wall_facing(data))],
+        ),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn from_legacy() -> Result<()> {
+        let blocks = [
+            ((1, 5), "minecraft:andesite"),
+            ((17, 4), "minecraft:oak_log[axis=x]"),
+            ((35, 14), "minecraft:red_wool"),
+            ((44, 8), "minecraft:smooth_stone_slab[type=top]"),
+            ((50, 3), "minecraft:wall_torch[facing=south]"),
+            ((66, 9), "minecraft:rail[shape=north_east]"),
+            ((118, 2), "minecraft:water_cauldron[level=2]"),
+            ((162, 13), "minecraft:dark_oak_wood[axis=y]"),
+            ((251, 8), "minecraft:light_gray_concrete"),
+        ];
+        for ((id, data), expected) in blocks {
+            assert_eq!(Block::from_legacy(id, data)?.to_string(), expected);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn unused_data_values() -> Result<()> {
+        // stone only uses 0 to 6
+        assert_eq!(Block::from_legacy(1, 12)?.to_string(), "minecraft:stone");
+        assert!(matches!(
+            Block::from_legacy(253, 0),
+            Err(Error::UnknownLegacyBlock(253, 0))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn context() -> Result<()> {
+        let air = (0, 0);
+        // lower half facing north & open, upper half with a right hinge
+        let (lower, upper) = ((64, 7), (64, 9));
+        assert_eq!(
+            legacy_state_in_context(upper, lower, air, None)?,
+            "oak_door[facing=north,open=true,hinge=right,powered=false,half=upper]"
+        );
+        assert_eq!(
+            legacy_state_in_context((175, 8), (175, 4), air, None)?,
+            "rose_bush[half=upper]"
+        );
+
+        let skull = NbtCompound::from_values(vec![
+            ("SkullType".into(), NbtTag::Byte(4)),
+            ("Rot".into(), NbtTag::Byte(6)),
+        ]);
+        assert_eq!(
+            legacy_state_in_context((144, 1), air, air, Some(&skull))?,
+            "creeper_head[rotation=6]"
+        );
+
+        let pot = NbtCompound::from_values(vec![
+            ("Item".into(), NbtTag::String("minecraft:red_flower".into())),
+            ("Data".into(), NbtTag::Int(2)),
+        ]);
+        assert_eq!(
+            legacy_state_in_context((140, 0), air, air, Some(&pot))?,
+            "potted_allium"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn block_entities() {
+        let chest = NbtCompound::from_values(vec![("id".into(), NbtTag::String("Chest".into()))]);
+        let chest = legacy_block_entity(&chest).unwrap();
+        assert_eq!(chest.string("id").unwrap().to_str(), "minecraft:chest");
+
+        let pot = NbtCompound::from_values(vec![(
+            "id".into(),
+            NbtTag::String("minecraft:flower_pot".into()),
+        )]);
+        assert!(legacy_block_entity(&pot).is_none());
+
+        let banner = NbtCompound::from_values(vec![
+            ("id".into(), NbtTag::String("Banner".into())),
+            ("Base".into(), NbtTag::Int(0)),
+            (
+                "Patterns".into(),
+                NbtTag::List(NbtList::Compound(vec![NbtCompound::from_values(vec![
+                    ("Pattern".into(), NbtTag::String("bs".into())),
+                    ("Color".into(), NbtTag::Int(1)),
+                ])])),
+            ),
+        ]);
+        let banner = legacy_block_entity(&banner).unwrap();
+        assert!(banner.int("Base").is_none());
+        let Some(NbtList::Compound(patterns)) = banner.list("Patterns") else {
+            panic!("patterns should be kept");
+        };
+        assert_eq!(patterns[0].int("Color"), Some(14));
+    }
+}
@@ -0,0 +1,399 @@
+//! `level` contains the [`LevelData`] struct used to read & write a save's `level.dat`.
+//!
+//! Only the most common fields have typed accessors.
+//! The entire NBT is kept around so any unknown tags are written back untouched.
+
+use crate::{
+    NbtString,
+    error::{Error, Result},
+    nbt::{read_gzip_nbt, set_tag, write_gzip_nbt},
+    region_file::{temp_path_of, write_synced},
+};
+use simdnbt::owned::{NbtCompound, NbtTag};
+use std::{
+    fs::File,
+    io::{BufReader, Read, Write},
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The contents of a save's `level.dat`.
+///
+/// ## Example
+/// ```no_run
+/// # use silverfish::LevelData;
+/// let mut level = LevelData::open("saves/New World/level.dat")?;
+/// println!("{:?} ({:?})", level.level_name(), level.data_version());
+///
+/// level.set_spawn((0, 80, 0))?;
+/// level.set_game_rule("doDaylightCycle", "false")?;
+/// level.save("saves/New World/level.dat")?;
+/// # Ok::<(), silverfish::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct LevelData {
+    /// The root compound of `level.dat`, all fields live within it's `Data` compound.
+    pub nbt: NbtCompound,
+}
+
+/// The world border fields stored in `level.dat`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorldBorder {
+    /// The center of the border, `(x, z)`.
+    pub center: (f64, f64),
+    /// The width of the border.
+    pub size: f64,
+    /// How far outside the border a player can be before taking damage.
+    pub safe_zone: f64,
+    /// Damage per block a player takes outside of the safe zone.
+    pub damage_per_block: f64,
+    /// How close to the border the warning is shown.
+    pub warning_blocks: f64,
+    /// How many seconds before a shrinking border hits a player a warning is shown.
+    pub warning_time: f64,
+    /// The size the border is moving towards.
+    pub size_lerp_target: f64,
+    /// How many milliseconds are left until the border reaches [`WorldBorder::size_lerp_target`].
+    pub size_lerp_time: i64,
+}
+
+impl LevelData {
+    /// Creates a [`LevelData`] from the root compound of a `level.dat`.
+    pub fn from_nbt(nbt: NbtCompound) -> Self {
+        Self { nbt }
+    }
+
+    /// Reads a gzip-compressed `level.dat` from the given reader.
+    pub fn read<R: Read>(reader: &mut R) -> Result<Self> {
+        let nbt = read_gzip_nbt(reader)?;
+
+        Ok(Self { nbt })
+    }
+
+    /// Reads a `level.dat` from the given path.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::read(&mut BufReader::new(File::open(path)?))
+    }
+
+    /// Writes the [`LevelData`] gzip-compressed to the given writer.
+    pub fn write<W: Write>(&self, writer: &mut W) -> Result<()> {
+        write_gzip_nbt(writer, "", self.nbt.clone())
+    }
+
+    /// Writes the [`LevelData`] to the given path.
+    ///
+    /// Just like the game, it's first written to a temporary file that's then renamed into place,
+    /// and the previous file is kept next to it as `level.dat_old`.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let temp_path = temp_path_of(path);
+        write_synced(&temp_path, |writer| self.write(writer))?;
+
+        if path.exists() {
+            let mut old_path = path.as_os_str().to_owned();
+            old_path.push("_old");
+            std::fs::rename(path, old_path)?;
+        }
+        std::fs::rename(&temp_path, path)?;
+
+        Ok(())
+    }
+
+    /// Returns the `Data` compound that holds all the fields.
+    pub fn data(&self) -> Result<&NbtCompound> {
+        self.nbt.compound("Data").ok_or(Error::MissingNbtTag("Data"))
+    }
+
+    /// Returns the `Data` compound that holds all the fields, mutably.
+    pub fn data_mut(&mut self) -> Result<&mut NbtCompound> {
+        self.nbt
+            .compound_mut("Data")
+            .ok_or(Error::MissingNbtTag("Data"))
+    }
+
+    /// The `DataVersion` the world was last saved in.
+    pub fn data_version(&self) -> Option<i32> {
+        self.data().ok()?.int("DataVersion")
+    }
+
+    /// The name of the version the world was last saved in, like `1.21.4`.
+    pub fn version_name(&self) -> Option<NbtString> {
+        NbtString::from_mutf8str(self.data().ok()?.compound("Version")?.string("Name"))
+    }
+
+    /// The name of the world.
+    pub fn level_name(&self) -> Option<NbtString> {
+        NbtString::from_mutf8str(self.data().ok()?.string("LevelName"))
+    }
+
+    /// Sets the name of the world.
+    pub fn set_level_name<S: Into<NbtString>>(&mut self, name: S) -> Result<()> {
+        let name: NbtString = name.into();
+        set_tag(
+            self.data_mut()?,
+            "LevelName",
+            NbtTag::String(name.to_mutf8string()),
+        );
+        Ok(())
+    }
+
+    /// The world seed.
+    ///
+    /// Read from `WorldGenSettings` and falls back to `RandomSeed` for older worlds.
+    pub fn seed(&self) -> Option<i64> {
+        let data = self.data().ok()?;
+        data.compound("WorldGenSettings")
+            .and_then(|settings| settings.long("seed"))
+            .or_else(|| data.long("RandomSeed"))
+    }
+
+    /// The world spawn point, `(x, y, z)`.
+    pub fn spawn(&self) -> Option<(i32, i32, i32)> {
+        let data = self.data().ok()?;
+        Some((data.int("SpawnX")?, data.int("SpawnY")?, data.int("SpawnZ")?))
+    }
+
+    /// Sets the world spawn point, `(x, y, z)`.
+    pub fn set_spawn(&mut self, spawn: (i32, i32, i32)) -> Result<()> {
+        let data = self.data_mut()?;
+        set_tag(data, "SpawnX", NbtTag::Int(spawn.0));
+        set_tag(data, "SpawnY", NbtTag::Int(spawn.1));
+        set_tag(data, "SpawnZ", NbtTag::Int(spawn.2));
+        Ok(())
+    }
+
+    /// Returns the value of a game rule, all game rules are stored as strings.
+    pub fn game_rule(&self, rule: &str) -> Option<NbtString> {
+        NbtString::from_mutf8str(self.data().ok()?.compound("GameRules")?.string(rule))
+    }
+
+    /// Returns all game rules and their values.
+    pub fn game_rules(&self) -> Vec<(NbtString, NbtString)> {
+        let rules = match self.data().ok().and_then(|d| d.compound("GameRules")) {
+            Some(rules) => rules,
+            None => return vec![],
+        };
+
+        rules
+            .iter()
+            .filter_map(|(k, v)| {
+                Some((
+                    NbtString::from_mutf8str(Some(k))?,
+                    NbtString::from_mutf8str(v.string())?,
+                ))
+            })
+            .collect()
+    }
+
+    /// Sets a game rule, creating the `GameRules` compound if it's missing.
+    pub fn set_game_rule<V: Into<NbtString>>(&mut self, rule: &str, value: V) -> Result<()> {
+        let value: NbtString = value.into();
+        let data = self.data_mut()?;
+        if data.compound("GameRules").is_none() {
+            set_tag(data, "GameRules", NbtTag::Compound(NbtCompound::new()));
+        }
+
+        // unwrap is fine since we just made sure it exists
+        let rules = data.compound_mut("GameRules").unwrap();
+        set_tag(rules, rule, NbtTag::String(value.to_mutf8string()));
+        Ok(())
+    }
+
+    /// The world border, if all of it's fields exists.
+    pub fn world_border(&self) -> Option<WorldBorder> {
+        let data = self.data().ok()?;
+        Some(WorldBorder {
+            center: (data.double("BorderCenterX")?, data.double("BorderCenterZ")?),
+            size: data.double("BorderSize")?,
+            safe_zone: data.double("BorderSafeZone")?,
+            damage_per_block: data.double("BorderDamagePerBlock")?,
+            warning_blocks: data.double("BorderWarningBlocks")?,
+            warning_time: data.double("BorderWarningTime")?,
+            size_lerp_target: data.double("BorderSizeLerpTarget")?,
+            size_lerp_time: data.long("BorderSizeLerpTime")?,
+        })
+    }
+
+    /// Sets all the world border fields.
+    pub fn set_world_border(&mut self, border: &WorldBorder) -> Result<()> {
+        let data = self.data_mut()?;
+        set_tag(data, "BorderCenterX", NbtTag::Double(border.center.0));
+        set_tag(data, "BorderCenterZ", NbtTag::Double(border.center.1));
+        set_tag(data, "BorderSize", NbtTag::Double(border.size));
+        set_tag(data, "BorderSafeZone", NbtTag::Double(border.safe_zone));
+        set_tag(
+            data,
+            "BorderDamagePerBlock",
+            NbtTag::Double(border.damage_per_block),
+        );
+        set_tag(
+            data,
+            "BorderWarningBlocks",
+            NbtTag::Double(border.warning_blocks),
+        );
+        set_tag(data, "BorderWarningTime", NbtTag::Double(border.warning_time));
+        set_tag(
+            data,
+            "BorderSizeLerpTarget",
+            NbtTag::Double(border.size_lerp_target),
+        );
+        set_tag(
+            data,
+            "BorderSizeLerpTime",
+            NbtTag::Long(border.size_lerp_time),
+        );
+        Ok(())
+    }
+
+    /// When the world was last played, in milliseconds since the unix epoch.
+    pub fn last_played(&self) -> Option<i64> {
+        self.data().ok()?.long("LastPlayed")
+    }
+
+    /// Sets when the world was last played, in milliseconds since the unix epoch.
+    pub fn set_last_played(&mut self, last_played: i64) -> Result<()> {
+        set_tag(self.data_mut()?, "LastPlayed", NbtTag::Long(last_played));
+        Ok(())
+    }
+
+    /// Sets `LastPlayed` to the current time.
+    pub fn touch(&mut self) -> Result<()> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as i64)
+            .unwrap_or(0);
+        self.set_last_played(now)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn level() -> LevelData {
+        let data = NbtCompound::from_values(vec![
+            ("DataVersion".into(), NbtTag::Int(4189)),
+            ("LevelName".into(), NbtTag::String("New World".into())),
+            ("SpawnX".into(), NbtTag::Int(16)),
+            ("SpawnY".into(), NbtTag::Int(72)),
+            ("SpawnZ".into(), NbtTag::Int(-48)),
+            ("LastPlayed".into(), NbtTag::Long(1_723_000_000_000)),
+            (
+                "Version".into(),
+                NbtTag::Compound(NbtCompound::from_values(vec![(
+                    "Name".into(),
+                    NbtTag::String("1.21.4".into()),
+                )])),
+            ),
+            (
+                "WorldGenSettings".into(),
+                NbtTag::Compound(NbtCompound::from_values(vec![(
+                    "seed".into(),
+                    NbtTag::Long(-4172144997902289642),
+                )])),
+            ),
+            (
+                "GameRules".into(),
+                NbtTag::Compound(NbtCompound::from_values(vec![(
+                    "keepInventory".into(),
+                    NbtTag::String("false".into()),
+                )])),
+            ),
+            ("SomeModdedTag".into(), NbtTag::Byte(1)),
+        ]);
+
+        LevelData::from_nbt(NbtCompound::from_values(vec![(
+            "Data".into(),
+            NbtTag::Compound(data),
+        )]))
+    }
+
+    #[test]
+    fn read_fields() {
+        let level = level();
+        assert_eq!(level.data_version(), Some(4189));
+        assert_eq!(level.level_name().unwrap(), "New World");
+        assert_eq!(level.version_name().unwrap(), "1.21.4");
+        assert_eq!(level.spawn(), Some((16, 72, -48)));
+        assert_eq!(level.seed(), Some(-4172144997902289642));
+        assert_eq!(level.game_rule("keepInventory").unwrap(), "false");
+        assert_eq!(level.game_rules().len(), 1);
+        assert_eq!(level.last_played(), Some(1_723_000_000_000));
+        assert_eq!(level.world_border(), None);
+    }
+
+    #[test]
+    fn set_fields() -> Result<()> {
+        let mut level = level();
+        level.set_spawn((0, 80, 0))?;
+        level.set_level_name("Renamed")?;
+        level.set_game_rule("keepInventory", "true")?;
+        level.set_game_rule("doDaylightCycle", "false")?;
+
+        assert_eq!(level.spawn(), Some((0, 80, 0)));
+        assert_eq!(level.level_name().unwrap(), "Renamed");
+        assert_eq!(level.game_rule("keepInventory").unwrap(), "true");
+        assert_eq!(level.game_rules().len(), 2);
+
+        let border = WorldBorder {
+            center: (0.0, 0.0),
+            size: 5000.0,
+            safe_zone: 5.0,
+            damage_per_block: 0.2,
+            warning_blocks: 5.0,
+            warning_time: 15.0,
+            size_lerp_target: 5000.0,
+            size_lerp_time: 0,
+        };
+        level.set_world_border(&border)?;
+        assert_eq!(level.world_border(), Some(border));
+
+        Ok(())
+    }
+
+    #[test]
+    fn roundtrip_keeps_unknown_tags() -> Result<()> {
+        let level = level();
+        let mut buf = vec![];
+        level.write(&mut buf)?;
+
+        let read_level = LevelData::read(&mut buf.as_slice())?;
+        assert_eq!(read_level.data_version(), Some(4189));
+        assert_eq!(read_level.data()?.byte("SomeModdedTag"), Some(1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn save_keeps_old() -> Result<()> {
+        let folder = std::env::temp_dir().join("silverfish_level_save_keeps_old");
+        let _ = std::fs::remove_dir_all(&folder);
+        std::fs::create_dir_all(&folder)?;
+        let path = folder.join("level.dat");
+
+        let mut level = level();
+        level.save(&path)?;
+        assert!(!folder.join("level.dat_old").exists());
+
+        level.set_level_name("Renamed")?;
+        level.save(&path)?;
+        assert_eq!(LevelData::open(&path)?.level_name().unwrap(), "Renamed");
+        assert_eq!(
+            LevelData::open(folder.join("level.dat_old"))?
+                .level_name()
+                .unwrap(),
+            "New World"
+        );
+        assert!(!temp_path_of(&path).exists());
+
+        std::fs::remove_dir_all(&folder)?;
+        Ok(())
+    }
+
+    #[test]
+    fn missing_data() {
+        let mut level = LevelData::from_nbt(NbtCompound::new());
+        assert_eq!(level.data_version(), None);
+        assert!(level.set_spawn((0, 0, 0)).is_err());
+    }
+}
@@ -4,27 +4,63 @@
 #![warn(missing_docs)]
 
 mod biome;
+mod block_entity;
 mod chunk;
+mod clipboard;
 mod config;
 mod coords;
+mod cuboid;
 mod data;
+mod dimension;
+mod entity;
 mod error;
 mod get;
+mod heightmap;
+mod legacy;
+mod level;
+mod light;
+mod litematic;
+mod mcedit;
 mod nbt;
 mod nbt_impls;
 mod paletted_blocks;
+mod poi;
 mod region;
+mod region_file;
+mod registry;
 mod set;
+mod snbt;
+mod sponge;
+mod structure;
+mod transform;
+mod world;
 mod write;
 
 pub use biome::{BiomeCell, BiomeCellWithId, coordinates_to_biome_cell};
 pub use chunk::ChunkData;
+pub use clipboard::{Clipboard, PasteOptions};
 pub use config::Config;
 pub use coords::Coords;
+pub use cuboid::Cuboid;
+pub use dimension::Dimension;
+pub use entity::{Entity, EntityChunk, EntityRegion};
 pub use error::{Error, Result};
+pub use level::{LevelData, WorldBorder};
+pub use litematic::{Litematic, LitematicRegion};
 pub use nbt::{Block, Name, NbtString};
 pub use paletted_blocks::{PalettedBlocks, PalettedBlocksIntoIter};
-pub use region::{BlockWithCoordinate, Region, get_empty_chunk, to_region_local};
+pub use poi::{PoiChunk, PoiRecord, PoiRegion, PoiSection};
+pub use region::{
+    BlockWithCoordinate, Region, get_empty_chunk, region_coords_from_path, to_region_coords,
+    to_region_local,
+};
+pub use region_file::Compression;
+pub use registry::{BlockDefinition, BlockRegistry};
+pub use snbt::{parse_snbt, to_snbt, to_snbt_pretty};
+pub use sponge::SpongeVersion;
+pub use structure::{StructureBlock, StructureEntity, StructureTemplate};
+pub use transform::{Mirror, Rotation};
+pub use world::World;
 
 /// How many blocks wide a region is.  
 pub const BLOCKS_PER_REGION: u32 = (ChunkData::WIDTH * mca::REGION_SIZE) as u32;
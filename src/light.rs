@@ -0,0 +1,702 @@
+//! `light` is a small offline light engine that computes the `SkyLight` & `BlockLight` of chunks.
+//!
+//! Light is computed from scratch from the blocks of a chunk and it's neighbours,
+//! how much light a block lets through and emits is decided by a small block table.
+
+use crate::{
+    ChunkData, Error, Region, Result,
+    data::decode_data,
+    heightmap::{blocks_motion, is_air, is_fluid},
+    nbt::set_tag,
+    region::{get_block_bit_count, is_valid_chunk},
+};
+use ahash::{AHashMap, AHashSet};
+use simdnbt::owned::{NbtCompound, NbtList, NbtTag};
+use std::{borrow::Cow, collections::VecDeque, ops::Range};
+
+/// The highest light level.
+const MAX_LIGHT: u8 = 15;
+/// How many blocks a single y level of a chunk has.
+const LAYER: usize = ChunkData::WIDTH * ChunkData::WIDTH;
+/// How many chunks along each axis that are lit at once, keeps memory usage down for big regions.
+const TILE_SIZE: u8 = 8;
+
+impl Region {
+    /// Computes the sky & block light of all chunks flagged with `isLightOn = 0`.
+    ///
+    /// Light spreads up to 15 blocks, so the neighbours of those chunks are relit as well.
+    /// Chunks are flagged by [`Config::update_lighting`](crate::Config::update_lighting) when written to,
+    /// and are marked as lit again once done.
+    /// Light doesn't spread across region borders, chunks outside the region count as missing.
+    /// So chunks on the border of the region keep `isLightOn = 0` for the game to relight them.
+    ///
+    /// ## Example
+    /// ```
+    /// # let mut region = silverfish::Region::empty((0, 0));
+    /// region.set_block((5, 64, 9), "glowstone")?;
+    /// region.write_blocks()?;
+    /// region.compute_lighting()?;
+    /// # Ok::<(), silverfish::Error>(())
+    /// ```
+    pub fn compute_lighting(&mut self) -> Result<()> {
+        let changed: Vec<(u8, u8)> = self
+            .chunks
+            .iter()
+            .filter(|c| c.nbt.byte("isLightOn") == Some(0))
+            .map(|c| *c.key())
+            .collect();
+
+        let mut relight = AHashSet::new();
+        for coords in changed {
+            for neighbour in neighbourhood(coords) {
+                let lightable = match self.get_chunk(neighbour.0, neighbour.1)? {
+                    Some(chunk) => is_valid_chunk(&chunk.nbt, neighbour).is_ok(),
+                    None => false,
+                };
+                if lightable {
+                    relight.insert(neighbour);
+                }
+            }
+        }
+
+        let mut tiles: AHashMap<(u8, u8), Vec<(u8, u8)>> = AHashMap::new();
+        for coords in relight {
+            tiles
+                .entry((coords.0 / TILE_SIZE, coords.1 / TILE_SIZE))
+                .or_default()
+                .push(coords);
+        }
+
+        for tile in tiles.values() {
+            self.light_tile(tile)?;
+        }
+
+        Ok(())
+    }
+
+    /// Lights a group of chunks, their neighbours are only read from.
+    fn light_tile(&self, tile: &[(u8, u8)]) -> Result<()> {
+        let mut volume = LightVolume::new(self.config.world_height.clone());
+
+        let mut coords: AHashSet<(u8, u8)> = AHashSet::new();
+        for chunk in tile {
+            coords.extend(neighbourhood(*chunk));
+        }
+        for chunk_coords in coords {
+            if let Some(chunk) = self.get_chunk(chunk_coords.0, chunk_coords.1)? {
+                volume.add_chunk(chunk_coords, &chunk.nbt)?;
+            }
+        }
+
+        volume.link_neighbours();
+        volume.propagate_block_light();
+        volume.propagate_sky_light();
+
+        for chunk_coords in tile {
+            let mut chunk = self.get_chunk_mut(chunk_coords.0, chunk_coords.1)?;
+            volume.write_chunk(*chunk_coords, &mut chunk.nbt)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The chunk and all of it's neighbours that are within the region.
+fn neighbourhood(coords: (u8, u8)) -> impl Iterator<Item = (u8, u8)> {
+    let size = mca::REGION_SIZE as i32;
+    (-1..=1)
+        .flat_map(move |dx| (-1..=1).map(move |dz| (coords.0 as i32 + dx, coords.1 as i32 + dz)))
+        .filter(move |(x, z)| (0..size).contains(x) && (0..size).contains(z))
+        .map(|(x, z)| (x as u8, z as u8))
+}
+
+/// If the chunk has a neighbour in another region.
+fn on_region_border(coords: (u8, u8)) -> bool {
+    let last = mca::REGION_SIZE as u8 - 1;
+    coords.0 == 0 || coords.1 == 0 || coords.0 == last || coords.1 == last
+}
+
+/// The blocks & light of a group of chunks that are lit together.
+struct LightVolume {
+    chunks: Vec<LightChunk>,
+    indexes: AHashMap<(u8, u8), usize>,
+    world_height: Range<isize>,
+    /// How many blocks are in a chunk.
+    len: usize,
+}
+
+/// The blocks & light of a single chunk, indexed by `y * 256 + z * 16 + x`.
+struct LightChunk {
+    coords: (u8, u8),
+    /// The chunks at `-x`, `+x`, `-z` & `+z`.
+    neighbours: [Option<usize>; 4],
+    /// Opacity in the low nibble and emission in the high nibble.
+    blocks: Vec<u8>,
+    sky: Vec<u8>,
+    block: Vec<u8>,
+}
+
+impl LightChunk {
+    fn light(&mut self, sky: bool) -> &mut Vec<u8> {
+        match sky {
+            true => &mut self.sky,
+            false => &mut self.block,
+        }
+    }
+}
+
+impl LightVolume {
+    fn new(world_height: Range<isize>) -> Self {
+        let len = world_height.clone().count() * LAYER;
+        Self {
+            chunks: Vec::new(),
+            indexes: AHashMap::new(),
+            world_height,
+            len,
+        }
+    }
+
+    fn min_section(&self) -> isize {
+        self.world_height
+            .start
+            .div_euclid(ChunkData::WIDTH as isize)
+    }
+
+    fn section_count(&self) -> isize {
+        (self.len / Region::BLOCK_DATA_LEN) as isize
+    }
+
+    /// Reads the light properties of all blocks in a chunk.
+    fn add_chunk(&mut self, coords: (u8, u8), nbt: &NbtCompound) -> Result<()> {
+        let mut blocks = vec![0u8; self.len];
+        let mut indexes = [0i64; Region::BLOCK_DATA_LEN];
+
+        let sections = match nbt.list("sections") {
+            Some(NbtList::Compound(sections)) => sections.as_slice(),
+            _ => &[],
+        };
+        for section in sections {
+            let section_index =
+                section.byte("Y").ok_or(Error::MissingNbtTag("Y"))? as isize - self.min_section();
+            if !(0..self.section_count()).contains(&section_index) {
+                continue;
+            }
+            let state = match section.compound("block_states") {
+                Some(state) => state,
+                None => continue,
+            };
+            let palette = state
+                .list("palette")
+                .ok_or(Error::MissingNbtTag("palette"))?
+                .compounds()
+                .ok_or(Error::InvalidNbtType("palette"))?;
+            let properties: Vec<u8> = palette.iter().map(light_properties).collect();
+
+            // plain air, nothing to do
+            if properties.iter().all(|p| *p == 0) {
+                continue;
+            }
+
+            decode_data(
+                &mut indexes,
+                get_block_bit_count(palette.len()),
+                state.long_array("data"),
+            );
+
+            let offset = section_index as usize * Region::BLOCK_DATA_LEN;
+            for (i, palette_index) in indexes.iter().enumerate() {
+                blocks[offset + i] = *properties
+                    .get(*palette_index as usize)
+                    .ok_or(Error::InvalidPaletteIndex(*palette_index))?;
+            }
+        }
+
+        self.indexes.insert(coords, self.chunks.len());
+        self.chunks.push(LightChunk {
+            coords,
+            neighbours: [None; 4],
+            blocks,
+            sky: vec![0; self.len],
+            block: vec![0; self.len],
+        });
+
+        Ok(())
+    }
+
+    fn link_neighbours(&mut self) {
+        for chunk in self.chunks.iter_mut() {
+            let (x, z) = chunk.coords;
+            let offsets = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+            for (neighbour, (dx, dz)) in chunk.neighbours.iter_mut().zip(offsets) {
+                let coords = (x.checked_add_signed(dx), z.checked_add_signed(dz));
+                *neighbour = match coords {
+                    (Some(x), Some(z)) => self.indexes.get(&(x, z)).copied(),
+                    _ => None,
+                };
+            }
+        }
+    }
+
+    /// Returns the neighbouring block in one of the six directions, if it's within the volume.
+    fn neighbour(&self, chunk: usize, index: usize, direction: usize) -> Option<(usize, usize)> {
+        let width = ChunkData::WIDTH;
+        let (x, z) = (index % width, (index / width) % width);
+        let neighbours = &self.chunks[chunk].neighbours;
+
+        match direction {
+            0 if x == 0 => neighbours[0].map(|n| (n, index + width - 1)),
+            0 => Some((chunk, index - 1)),
+            1 if x == width - 1 => neighbours[1].map(|n| (n, index + 1 - width)),
+            1 => Some((chunk, index + 1)),
+            2 if z == 0 => neighbours[2].map(|n| (n, index + LAYER - width)),
+            2 => Some((chunk, index - width)),
+            3 if z == width - 1 => neighbours[3].map(|n| (n, index + width - LAYER)),
+            3 => Some((chunk, index + width)),
+            4 if index < LAYER => None,
+            4 => Some((chunk, index - LAYER)),
+            _ if index + LAYER >= self.len => None,
+            _ => Some((chunk, index + LAYER)),
+        }
+    }
+
+    /// Spreads light from every queued block, losing at least one level per block.
+    fn propagate(&mut self, mut queue: VecDeque<(usize, usize)>, sky: bool) {
+        while let Some((chunk, index)) = queue.pop_front() {
+            let level = self.chunks[chunk].light(sky)[index];
+            if level <= 1 {
+                continue;
+            }
+
+            for direction in 0..6 {
+                let (n_chunk, n_index) = match self.neighbour(chunk, index, direction) {
+                    Some(neighbour) => neighbour,
+                    None => continue,
+                };
+
+                let neighbour = &mut self.chunks[n_chunk];
+                let opacity = (neighbour.blocks[n_index] & MAX_LIGHT).max(1);
+                let new_level = level.saturating_sub(opacity);
+                let light = neighbour.light(sky);
+                if new_level > light[n_index] {
+                    light[n_index] = new_level;
+                    queue.push_back((n_chunk, n_index));
+                }
+            }
+        }
+    }
+
+    fn propagate_block_light(&mut self) {
+        let mut queue = VecDeque::new();
+        for (c, chunk) in self.chunks.iter_mut().enumerate() {
+            for (i, block) in chunk.blocks.iter().enumerate() {
+                let emission = block >> 4;
+                if emission > 0 {
+                    chunk.block[i] = emission;
+                    queue.push_back((c, i));
+                }
+            }
+        }
+
+        self.propagate(queue, false);
+    }
+
+    fn propagate_sky_light(&mut self) {
+        let mut queue = VecDeque::new();
+        for (c, chunk) in self.chunks.iter_mut().enumerate() {
+            for column in 0..LAYER {
+                // full sky light goes straight down until something blocks it
+                let mut level = MAX_LIGHT;
+                for index in (column..self.len).step_by(LAYER).rev() {
+                    level = level.saturating_sub(chunk.blocks[index] & MAX_LIGHT);
+                    if level == 0 {
+                        break;
+                    }
+                    chunk.sky[index] = level;
+                    queue.push_back((c, index));
+                }
+            }
+        }
+
+        self.propagate(queue, true);
+    }
+
+    /// Writes the computed light to the sections of a chunk and marks it as lit,
+    /// unless it's on the region border where the light from the neighbouring region is missing.
+    fn write_chunk(&self, coords: (u8, u8), nbt: &mut NbtCompound) -> Result<()> {
+        let chunk = &self.chunks[self.indexes[&coords]];
+        let (min_section, section_count) = (self.min_section(), self.section_count());
+
+        let sections = match nbt
+            .list_mut("sections")
+            .ok_or(Error::MissingNbtTag("sections"))?
+        {
+            NbtList::Compound(sections) => sections,
+            _ => return Err(Error::InvalidNbtList("sections")),
+        };
+        for section in sections.iter_mut() {
+            let section_index =
+                section.byte("Y").ok_or(Error::MissingNbtTag("Y"))? as isize - min_section;
+
+            if section_index < 0 {
+                section.remove("SkyLight");
+                section.remove("BlockLight");
+            } else if section_index >= section_count {
+                let full_sky = vec![u8::MAX; Region::BLOCK_DATA_LEN / 2];
+                set_tag(section, "SkyLight", NbtTag::ByteArray(full_sky));
+                section.remove("BlockLight");
+            } else {
+                let range = section_index as usize * Region::BLOCK_DATA_LEN
+                    ..(section_index as usize + 1) * Region::BLOCK_DATA_LEN;
+                let sky = pack_nibbles(&chunk.sky[range.clone()]);
+                let block = pack_nibbles(&chunk.block[range]);
+                set_tag(section, "SkyLight", NbtTag::ByteArray(sky));
+                set_tag(section, "BlockLight", NbtTag::ByteArray(block));
+            }
+        }
+
+        let lit = match on_region_border(coords) {
+            true => 0,
+            false => 1,
+        };
+        set_tag(nbt, "isLightOn", NbtTag::Byte(lit));
+
+        Ok(())
+    }
+}
+
+/// Packs light levels into nibbles, the first level in the lower half of each byte.
+fn pack_nibbles(levels: &[u8]) -> Vec<u8> {
+    levels
+        .chunks(2)
+        .map(|pair| pair[0] | (pair[1] << 4))
+        .collect()
+}
+
+/// Returns the opacity of a block palette entry in the low nibble and it's emission in the high nibble.
+fn light_properties(block: &NbtCompound) -> u8 {
+    let name = match block.string("Name") {
+        Some(name) => name.to_str(),
+        None => return 0,
+    };
+    let id = name.strip_prefix("minecraft:").unwrap_or(&name);
+    let properties = block.compound("Properties");
+
+    opacity(id, flag(properties, "waterlogged", false)) | (emission(id, properties) << 4)
+}
+
+fn property<'a>(properties: Option<&'a NbtCompound>, key: &str) -> Option<Cow<'a, str>> {
+    properties.and_then(|p| p.string(key)).map(|v| v.to_str())
+}
+
+fn flag(properties: Option<&NbtCompound>, key: &str, default: bool) -> bool {
+    property(properties, key).map_or(default, |v| v == "true")
+}
+
+fn number(properties: Option<&NbtCompound>, key: &str, default: u8) -> u8 {
+    property(properties, key)
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+/// How many light levels a block takes away from light passing through it.
+///
+/// Blocks from other namespaces are assumed to be opaque.
+fn opacity(id: &str, waterlogged: bool) -> u8 {
+    if waterlogged || is_fluid(id) || id.ends_with("_leaves") {
+        return 1;
+    }
+    if matches!(
+        id,
+        "ice" | "frosted_ice" | "cobweb" | "slime_block" | "honey_block" | "powder_snow"
+    ) {
+        return 1;
+    }
+    if id == "tinted_glass" {
+        return MAX_LIGHT;
+    }
+
+    if is_air(id) || !blocks_motion(id) || lets_light_through(id) {
+        0
+    } else {
+        MAX_LIGHT
+    }
+}
+
+/// Solid blocks that light passes through, mostly non-full blocks.
+fn lets_light_through(id: &str) -> bool {
+    let transparent = matches!(
+        id,
+        "glass"
+            | "iron_bars"
+            | "chain"
+            | "ladder"
+            | "scaffolding"
+            | "beacon"
+            | "barrier"
+            | "end_rod"
+            | "lightning_rod"
+            | "chest"
+            | "trapped_chest"
+            | "ender_chest"
+            | "enchanting_table"
+            | "campfire"
+            | "soul_campfire"
+            | "hopper"
+            | "brewing_stand"
+            | "cauldron"
+            | "water_cauldron"
+            | "lava_cauldron"
+            | "powder_snow_cauldron"
+            | "composter"
+            | "bell"
+            | "lectern"
+            | "grindstone"
+            | "stonecutter"
+            | "daylight_detector"
+            | "conduit"
+            | "dragon_egg"
+            | "end_portal_frame"
+            | "farmland"
+            | "dirt_path"
+            | "pointed_dripstone"
+            | "amethyst_cluster"
+            | "big_dripleaf"
+            | "azalea"
+            | "flowering_azalea"
+            | "mangrove_roots"
+            | "bamboo"
+            | "cactus"
+            | "chorus_plant"
+            | "chorus_flower"
+            | "cake"
+            | "anvil"
+            | "chipped_anvil"
+            | "damaged_anvil"
+            | "decorated_pot"
+            | "sniffer_egg"
+            | "spawner"
+            | "trial_spawner"
+            | "vault"
+            | "heavy_core"
+            | "piston_head"
+            | "moving_piston"
+            | "shulker_box"
+    );
+
+    transparent
+        || id.ends_with("_glass")
+        || id.ends_with("_slab")
+        || id.ends_with("_stairs")
+        || id.ends_with("_fence")
+        || id.ends_with("_fence_gate")
+        || id.ends_with("_wall")
+        || id.ends_with("_pane")
+        || id.ends_with("_door")
+        || id.ends_with("_trapdoor")
+        || id.ends_with("_bed")
+        || id.ends_with("_amethyst_bud")
+        || id.ends_with("_candle_cake")
+        || id.ends_with("_shulker_box")
+        || id.ends_with("_grate")
+}
+
+/// The light level a block emits.
+fn emission(id: &str, properties: Option<&NbtCompound>) -> u8 {
+    let lit = |default| flag(properties, "lit", default);
+    let candles = || 3 * number(properties, "candles", 1).clamp(1, 4);
+
+    match id {
+        "glowstone"
+        | "sea_lantern"
+        | "jack_o_lantern"
+        | "beacon"
+        | "shroomlight"
+        | "lava"
+        | "lava_cauldron"
+        | "fire"
+        | "end_portal"
+        | "end_gateway"
+        | "conduit"
+        | "lantern"
+        | "ochre_froglight"
+        | "verdant_froglight"
+        | "pearlescent_froglight" => 15,
+        "torch" | "wall_torch" | "end_rod" => 14,
+        "nether_portal" => 11,
+        "soul_torch" | "soul_wall_torch" | "soul_lantern" | "soul_fire" | "crying_obsidian" => 10,
+        "enchanting_table" | "ender_chest" | "glow_lichen" => 7,
+        "sculk_catalyst" => 6,
+        "amethyst_cluster" => 5,
+        "large_amethyst_bud" => 4,
+        "magma_block" => 3,
+        "medium_amethyst_bud" => 2,
+        "small_amethyst_bud"
+        | "brewing_stand"
+        | "brown_mushroom"
+        | "dragon_egg"
+        | "end_portal_frame"
+        | "sculk_sensor"
+        | "calibrated_sculk_sensor" => 1,
+        "redstone_torch" | "redstone_wall_torch" if lit(true) => 7,
+        "redstone_lamp" if lit(false) => 15,
+        "redstone_ore" | "deepslate_redstone_ore" if lit(false) => 9,
+        "furnace" | "blast_furnace" | "smoker" if lit(false) => 13,
+        "campfire" if lit(true) => 15,
+        "soul_campfire" if lit(true) => 10,
+        "respawn_anchor" => [0, 3, 7, 11, 15][number(properties, "charges", 0).min(4) as usize],
+        "light" => number(properties, "level", 15).min(MAX_LIGHT),
+        "cave_vines" | "cave_vines_plant" if flag(properties, "berries", false) => 14,
+        "sea_pickle" if flag(properties, "waterlogged", true) => {
+            3 + 3 * number(properties, "pickles", 1).clamp(1, 4)
+        }
+        "candle" if lit(false) => candles(),
+        "candle_cake" if lit(false) => 3,
+        _ if id.ends_with("_candle") && lit(false) => candles(),
+        _ if id.ends_with("_candle_cake") && lit(false) => 3,
+        _ if id.ends_with("copper_bulb") && lit(false) => {
+            if id.contains("oxidized") {
+                4
+            } else if id.contains("weathered") {
+                8
+            } else if id.contains("exposed") {
+                12
+            } else {
+                15
+            }
+        }
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Block, Config};
+
+    fn properties(block: Block) -> (u8, u8) {
+        let properties = light_properties(&block.to_compound().unwrap());
+        (properties & MAX_LIGHT, properties >> 4)
+    }
+
+    fn light(region: &Region, coords: (u32, i32, u32), name: &str) -> u8 {
+        let chunk = region
+            .get_chunk((coords.0 / 16) as u8, (coords.2 / 16) as u8)
+            .unwrap()
+            .unwrap();
+        let section = chunk
+            .nbt
+            .list("sections")
+            .unwrap()
+            .compounds()
+            .unwrap()
+            .iter()
+            .find(|s| s.byte("Y") == Some(coords.1.div_euclid(16) as i8))
+            .unwrap();
+        let index = (coords.1.rem_euclid(16) as usize) * LAYER
+            + (coords.2 % 16) as usize * 16
+            + (coords.0 % 16) as usize;
+        let data = section.byte_array(name).unwrap();
+        assert_eq!(data.len(), 2048);
+        (data[index / 2] >> ((index % 2) * 4)) & MAX_LIGHT
+    }
+
+    #[test]
+    fn block_properties() {
+        assert_eq!(properties(Block::new("minecraft:stone")), (15, 0));
+        assert_eq!(properties(Block::new("minecraft:air")), (0, 0));
+        assert_eq!(properties(Block::new("minecraft:glowstone")), (15, 15));
+        assert_eq!(properties(Block::new("minecraft:torch")), (0, 14));
+        assert_eq!(properties(Block::new("minecraft:water")), (1, 0));
+        assert_eq!(properties(Block::new("minecraft:oak_leaves")), (1, 0));
+        assert_eq!(properties(Block::new("minecraft:glass")), (0, 0));
+        assert_eq!(properties(Block::new("minecraft:oak_slab")), (0, 0));
+        assert_eq!(
+            properties(Block::new_with_props("furnace", [("lit", "true")])),
+            (15, 13)
+        );
+        assert_eq!(properties(Block::new("minecraft:furnace")), (15, 0));
+        assert_eq!(
+            properties(Block::new_with_props(
+                "candle",
+                [("lit", "true"), ("candles", "3")]
+            )),
+            (0, 9)
+        );
+        assert_eq!(
+            properties(Block::new_with_props("light", [("level", "4")])),
+            (0, 4)
+        );
+    }
+
+    #[test]
+    fn block_light_spreads_across_chunks() -> Result<()> {
+        let mut region = Region::empty((0, 0));
+        region.set_block((8, 64, 8), "minecraft:glowstone")?;
+        region.set_block((8, 64, 24), "minecraft:air")?;
+        region.write_blocks()?;
+        region.compute_lighting()?;
+
+        assert_eq!(light(&region, (8, 64, 8), "BlockLight"), 15);
+        assert_eq!(light(&region, (8, 64, 10), "BlockLight"), 13);
+        assert_eq!(light(&region, (9, 65, 9), "BlockLight"), 12);
+        assert_eq!(light(&region, (8, 64, 20), "BlockLight"), 3);
+        assert_eq!(light(&region, (8, 64, 24), "BlockLight"), 0);
+        // the neighbouring region could light it too, so the game relights it
+        assert_eq!(
+            region.get_chunk(0, 1)?.unwrap().nbt.byte("isLightOn"),
+            Some(0)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn sky_light_under_roof() -> Result<()> {
+        let mut region = Region::empty((0, 0));
+        for x in 0..3 {
+            for z in 0..3 {
+                region.set_block((x * 16, 0, z * 16), "minecraft:air")?;
+            }
+        }
+        region.write_blocks()?;
+        region.set_section((1, 1), 10, "minecraft:stone")?;
+        region.compute_lighting()?;
+
+        assert_eq!(light(&region, (24, 170, 24), "SkyLight"), 15);
+        assert_eq!(light(&region, (24, 165, 24), "SkyLight"), 0);
+        // the closest open sky is 8 blocks away
+        assert_eq!(light(&region, (24, 150, 24), "SkyLight"), 7);
+        assert_eq!(light(&region, (16, 150, 24), "SkyLight"), 14);
+        assert_eq!(light(&region, (4, 150, 4), "SkyLight"), 15);
+        assert_eq!(
+            region.get_chunk(1, 1)?.unwrap().nbt.byte("isLightOn"),
+            Some(1)
+        );
+        assert_eq!(
+            region.get_chunk(0, 0)?.unwrap().nbt.byte("isLightOn"),
+            Some(0)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn compute_lighting_config() -> Result<()> {
+        let mut region = Region::empty((0, 0));
+        region.set_config(Config {
+            create_chunk_if_missing: true,
+            compute_lighting: true,
+            ..Default::default()
+        })?;
+        region.set_block((19, 10, 19), "minecraft:sea_lantern")?;
+        region.write_blocks()?;
+
+        let chunk = region.get_chunk(1, 1)?.unwrap();
+        assert_eq!(chunk.nbt.byte("isLightOn"), Some(1));
+        drop(chunk);
+        assert_eq!(light(&region, (19, 11, 19), "BlockLight"), 14);
+        assert_eq!(light(&region, (19, 11, 19), "SkyLight"), 15);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,811 @@
+//! `litematic` reads & writes Litematica schematics (`.litematic`), which can hold multiple named regions.
+
+use crate::{
+    BLOCKS_PER_REGION, Block, Clipboard, Coords, Error, PasteOptions, Region, Result, World,
+    block_entity::block_entity_position,
+    clipboard::{PasteTarget, rotated_size, schematic_volume, transform_xz},
+    heightmap::is_air,
+    nbt::{compounds, read_gzip_nbt, set_tag, write_file, write_gzip_nbt},
+};
+use ahash::AHashMap;
+use simdnbt::owned::{NbtCompound, NbtList, NbtTag};
+use std::{
+    fs::File,
+    io::{BufReader, Read, Write},
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// A Litematica schematic.
+#[derive(Debug, Clone)]
+pub struct Litematic {
+    /// The name shown in Litematica
+    pub name: String,
+    /// Who made the schematic
+    pub author: String,
+    /// A description of the schematic
+    pub description: String,
+    /// The `DataVersion` the schematic was saved in
+    pub data_version: i32,
+    /// The regions within the schematic, in the order they're stored
+    pub regions: Vec<LitematicRegion>,
+}
+
+/// A named region within a [`Litematic`].
+#[derive(Debug, Clone)]
+pub struct LitematicRegion {
+    /// The name of the region
+    pub name: String,
+    /// The position of the region's smallest corner, relative to the smallest corner of all regions
+    pub position: (u32, u32, u32),
+    /// The blocks & block entities within the region, without any biomes
+    pub clipboard: Clipboard,
+    /// The entities within the region, kept as is and not pasted
+    pub entities: Vec<NbtCompound>,
+}
+
+/// The format version written by [`Litematic::to_nbt`].
+const LITEMATIC_VERSION: i32 = 6;
+/// The newest format version that can be read.
+const MAX_LITEMATIC_VERSION: i32 = 7;
+
+impl Litematic {
+    /// Reads a gzip-compressed Litematica schematic from the given reader.
+    pub fn read<R: Read>(reader: &mut R) -> Result<Self> {
+        let nbt = read_gzip_nbt(reader)?;
+
+        Self::from_nbt(&nbt)
+    }
+
+    /// Reads a Litematica schematic from the given path.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// # use silverfish::{Litematic, PasteOptions, Region};
+    /// # let mut region = Region::default();
+    /// let farm = Litematic::open("schematics/iron_farm.litematic")?;
+    /// farm.paste(&mut region, (100, 64, 100), PasteOptions::default())?;
+    /// region.write_blocks()?;
+    /// # Ok::<(), silverfish::Error>(())
+    /// ```
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::read(&mut BufReader::new(File::open(path)?))
+    }
+
+    /// Creates a [`Litematic`] from it's root compound.
+    pub fn from_nbt(nbt: &NbtCompound) -> Result<Self> {
+        let version = nbt.int("Version").ok_or(Error::MissingNbtTag("Version"))?;
+        if !(1..=MAX_LITEMATIC_VERSION).contains(&version) {
+            return Err(Error::UnsupportedSchematicVersion(version));
+        }
+
+        let data_version = nbt
+            .int("MinecraftDataVersion")
+            .ok_or(Error::MissingNbtTag("MinecraftDataVersion"))?;
+        let metadata = nbt
+            .compound("Metadata")
+            .ok_or(Error::MissingNbtTag("Metadata"))?;
+        let string = |name: &str| {
+            metadata
+                .string(name)
+                .map(|s| s.to_str().into_owned())
+                .unwrap_or_default()
+        };
+
+        let nbt_regions = nbt
+            .compound("Regions")
+            .ok_or(Error::MissingNbtTag("Regions"))?;
+        if nbt_regions.is_empty() {
+            return Err(Error::InvalidSchematic(String::from("it has no regions")));
+        }
+
+        let mut regions = vec![];
+        let mut min_corners = vec![];
+        for (name, region) in nbt_regions.iter() {
+            let region = region.compound().ok_or(Error::InvalidNbtType("Regions"))?;
+            let (min_corner, clipboard) = read_region(region, data_version)?;
+            min_corners.push(min_corner);
+            regions.push(LitematicRegion {
+                name: name.to_str().into_owned(),
+                position: (0, 0, 0),
+                clipboard,
+                entities: compounds(region.list("Entities"), "Entities")?.to_vec(),
+            });
+        }
+
+        // positions are stored relative to an origin of the player's choosing,
+        // so they're moved to start from the smallest corner of all regions
+        let origin = min_corners
+            .iter()
+            .fold((i32::MAX, i32::MAX, i32::MAX), |o, c| {
+                (o.0.min(c.0), o.1.min(c.1), o.2.min(c.2))
+            });
+        for (region, corner) in regions.iter_mut().zip(min_corners) {
+            region.position = (
+                corner.0.abs_diff(origin.0),
+                corner.1.abs_diff(origin.1),
+                corner.2.abs_diff(origin.2),
+            );
+        }
+
+        let litematic = Self {
+            name: string("Name"),
+            author: string("Author"),
+            description: string("Description"),
+            data_version,
+            regions,
+        };
+        // the regions can be further apart than the enclosing size fits
+        litematic.size()?;
+
+        Ok(litematic)
+    }
+
+    /// Creates a [`Litematic`] with a single region from a cuboid within a region.
+    ///
+    /// The corners are inclusive and can be given in any order.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// # use silverfish::{Litematic, Region};
+    /// # let region = Region::default();
+    /// let farm = Litematic::from_region(&region, (100, 60, 100), (130, 90, 130), "Iron Farm")?;
+    /// farm.save("schematics/iron_farm.litematic")?;
+    /// # Ok::<(), silverfish::Error>(())
+    /// ```
+    pub fn from_region<C: Into<Coords>>(
+        region: &Region,
+        from: C,
+        to: C,
+        name: &str,
+    ) -> Result<Self> {
+        Ok(Self::from_clipboard(
+            Clipboard::copy(region, from, to)?,
+            name,
+        ))
+    }
+
+    /// Creates a [`Litematic`] with a single region, named `name`, from a [`Clipboard`].
+    ///
+    /// Litematica doesn't store biomes, so they're left out.
+    pub fn from_clipboard(mut clipboard: Clipboard, name: &str) -> Self {
+        clipboard.biomes.clear();
+
+        Self {
+            name: name.to_owned(),
+            author: String::new(),
+            description: String::new(),
+            data_version: clipboard.data_version,
+            regions: vec![LitematicRegion {
+                name: name.to_owned(),
+                position: (0, 0, 0),
+                clipboard,
+                entities: vec![],
+            }],
+        }
+    }
+
+    /// Returns the size of the box enclosing all regions as `(x, y, z)`.
+    ///
+    /// Errors if a region is too far away for the size to fit in a [`u32`].
+    pub fn size(&self) -> Result<(u32, u32, u32)> {
+        self.regions.iter().try_fold((0, 0, 0), |size, region| {
+            let (x, y, z) = region.position;
+            let (width, height, length) = region.clipboard.size;
+            let end = |position: u32, len: u32| {
+                position.checked_add(len).ok_or_else(|| {
+                    Error::InvalidSchematic(format!("the region {} is too far away", region.name))
+                })
+            };
+
+            Ok((
+                size.0.max(end(x, width)?),
+                size.1.max(end(y, height)?),
+                size.2.max(end(z, length)?),
+            ))
+        })
+    }
+
+    /// Converts the schematic into it's root compound.
+    pub fn to_nbt(&self) -> Result<NbtCompound> {
+        let size = self.size()?;
+        // can't overflow since both sides are at most `u32::MAX`
+        let volume = (size.0 as u64 * size.1 as u64)
+            .checked_mul(size.2 as u64)
+            .and_then(|v| i32::try_from(v).ok())
+            .ok_or_else(|| Error::InvalidSchematic(format!("the size {size:?} is too large")))?;
+        let total_blocks: usize = self
+            .regions
+            .iter()
+            .map(|r| non_air_blocks(&r.clipboard))
+            .sum();
+        let time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as i64)
+            .unwrap_or_default();
+
+        let mut metadata = NbtCompound::new();
+        set_tag(
+            &mut metadata,
+            "Name",
+            NbtTag::String(self.name.as_str().into()),
+        );
+        set_tag(
+            &mut metadata,
+            "Author",
+            NbtTag::String(self.author.as_str().into()),
+        );
+        set_tag(
+            &mut metadata,
+            "Description",
+            NbtTag::String(self.description.as_str().into()),
+        );
+        set_tag(
+            &mut metadata,
+            "RegionCount",
+            NbtTag::Int(self.regions.len() as i32),
+        );
+        set_tag(&mut metadata, "TotalVolume", NbtTag::Int(volume));
+        set_tag(
+            &mut metadata,
+            "TotalBlocks",
+            NbtTag::Int(total_blocks as i32),
+        );
+        set_tag(
+            &mut metadata,
+            "EnclosingSize",
+            NbtTag::Compound(vector(int(size.0)?, int(size.1)?, int(size.2)?)),
+        );
+        set_tag(&mut metadata, "TimeCreated", NbtTag::Long(time));
+        set_tag(&mut metadata, "TimeModified", NbtTag::Long(time));
+
+        let regions = self
+            .regions
+            .iter()
+            .map(|r| Ok((r.name.as_str().into(), NbtTag::Compound(write_region(r)?))))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut nbt = NbtCompound::new();
+        set_tag(
+            &mut nbt,
+            "MinecraftDataVersion",
+            NbtTag::Int(self.data_version),
+        );
+        set_tag(&mut nbt, "Version", NbtTag::Int(LITEMATIC_VERSION));
+        set_tag(&mut nbt, "SubVersion", NbtTag::Int(1));
+        set_tag(&mut nbt, "Metadata", NbtTag::Compound(metadata));
+        set_tag(
+            &mut nbt,
+            "Regions",
+            NbtTag::Compound(NbtCompound::from_values(regions)),
+        );
+
+        Ok(nbt)
+    }
+
+    /// Writes the schematic gzip-compressed to the given writer.
+    pub fn write<W: Write>(&self, writer: &mut W) -> Result<()> {
+        write_gzip_nbt(writer, "", self.to_nbt()?)
+    }
+
+    /// Writes the schematic to the given path.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        write_file(path.as_ref(), |writer| self.write(writer))
+    }
+
+    /// Pastes every region with the smallest corner of the schematic at `to` *(local to within the region)*.
+    ///
+    /// The regions keep their placement relative to each other, also when rotated or mirrored.
+    /// Call [`Region::write_blocks`] afterwards.
+    pub fn paste<C: Into<Coords>>(
+        &self,
+        region: &mut Region,
+        to: C,
+        options: PasteOptions,
+    ) -> Result<()> {
+        let to: Coords = to.into();
+        let size = rotated_size(self.size()?, options.rotation);
+        if size.0 == 0 || size.1 == 0 || size.2 == 0 {
+            return Ok(());
+        }
+
+        let max = (to.x + size.0 - 1, to.z + size.2 - 1);
+        if max.0 >= BLOCKS_PER_REGION || max.1 >= BLOCKS_PER_REGION {
+            return Err(Error::CoordinatesOutOfRegionBounds(max.0, max.1));
+        }
+
+        self.paste_into(region, (to.x as i32, to.y, to.z as i32), options)
+    }
+
+    /// Pastes every region with the smallest corner of the schematic at `to` *(global coordinates)*.
+    ///
+    /// Call [`World::write_blocks`] or [`World::save`] afterwards.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// # use silverfish::{Litematic, PasteOptions, Rotation, World};
+    /// let mut world = World::open("saves/New World")?;
+    /// let farm = Litematic::open("schematics/iron_farm.litematic")?;
+    /// let options = PasteOptions {
+    ///     rotation: Rotation::Clockwise90,
+    ///     ..Default::default()
+    /// };
+    /// farm.paste_world(&mut world, (-200, 64, 340), options)?;
+    /// world.save()?;
+    /// # Ok::<(), silverfish::Error>(())
+    /// ```
+    pub fn paste_world(
+        &self,
+        world: &mut World,
+        to: (i32, i32, i32),
+        options: PasteOptions,
+    ) -> Result<()> {
+        self.paste_into(world, to, options)
+    }
+
+    fn paste_into<T: PasteTarget>(
+        &self,
+        target: &mut T,
+        to: (i32, i32, i32),
+        options: PasteOptions,
+    ) -> Result<()> {
+        let size = self.size()?;
+        for region in self.regions.iter().filter(|r| !r.clipboard.is_empty()) {
+            // the transform keeps the shape of the region,
+            // so it's new smallest corner is the smallest of it's transformed corners
+            let (x, y, z) = region.position;
+            let (width, _, length) = region.clipboard.size;
+            let first = transform_xz((x, z), size, options)?;
+            let last = transform_xz((x + width - 1, z + length - 1), size, options)?;
+
+            region.clipboard.paste_into(
+                target,
+                (
+                    to.0 + first.0.min(last.0) as i32,
+                    to.1 + y as i32,
+                    to.2 + first.1.min(last.1) as i32,
+                ),
+                options,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads a region into a [`Clipboard`], returning it together with the region's smallest corner.
+fn read_region(region: &NbtCompound, data_version: i32) -> Result<((i32, i32, i32), Clipboard)> {
+    let position = read_vector(region, "Position")?;
+    let signed_size = read_vector(region, "Size")?;
+
+    // a negative size means the region extends from `Position` towards negative coordinates
+    let corner = |position: i32, size: i32| {
+        if size < 0 {
+            position.checked_add(size + 1).ok_or_else(|| {
+                Error::InvalidSchematic(format!("the region at {position} extends too far"))
+            })
+        } else {
+            Ok(position)
+        }
+    };
+    let min_corner = (
+        corner(position.0, signed_size.0)?,
+        corner(position.1, signed_size.1)?,
+        corner(position.2, signed_size.2)?,
+    );
+    let size = (
+        signed_size.0.unsigned_abs(),
+        signed_size.1.unsigned_abs(),
+        signed_size.2.unsigned_abs(),
+    );
+    let volume = schematic_volume(size)?;
+
+    let palette = compounds(region.list("BlockStatePalette"), "BlockStatePalette")?
+        .iter()
+        .map(Block::from_compound)
+        .collect::<Result<Vec<_>>>()?;
+    if palette.is_empty() {
+        return Err(Error::MissingNbtTag("BlockStatePalette"));
+    }
+
+    let longs = region
+        .long_array("BlockStates")
+        .ok_or(Error::MissingNbtTag("BlockStates"))?;
+    let blocks = unpack(longs, bits_per_entry(palette.len()), volume)?;
+    if let Some(index) = blocks.iter().find(|i| **i as usize >= palette.len()) {
+        return Err(Error::InvalidPaletteIndex(*index as i64));
+    }
+
+    let mut block_entities = AHashMap::new();
+    for block_entity in compounds(region.list("TileEntities"), "TileEntities")? {
+        let (x, y, z) = block_entity_position(block_entity)?;
+        block_entities.insert((x as u32, y as u32, z as u32), block_entity.clone());
+    }
+
+    Ok((
+        min_corner,
+        Clipboard {
+            size,
+            data_version,
+            palette,
+            blocks,
+            biomes: vec![],
+            block_entities,
+        },
+    ))
+}
+
+fn write_region(region: &LitematicRegion) -> Result<NbtCompound> {
+    let clipboard = &region.clipboard;
+    let (x, y, z) = region.position;
+    let (width, height, length) = clipboard.size;
+
+    let palette = clipboard
+        .palette
+        .iter()
+        .map(|b| b.clone().to_compound())
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut block_entities: Vec<_> = clipboard.block_entities.iter().collect();
+    // keeps the output the same between runs
+    block_entities.sort_by_key(|((x, y, z), _)| (*y, *z, *x));
+    let block_entities = block_entities
+        .into_iter()
+        .map(|((x, y, z), block_entity)| {
+            // a copied block entity still has it's position within the world
+            let mut block_entity = block_entity.clone();
+            set_tag(&mut block_entity, "x", NbtTag::Int(*x as i32));
+            set_tag(&mut block_entity, "y", NbtTag::Int(*y as i32));
+            set_tag(&mut block_entity, "z", NbtTag::Int(*z as i32));
+            block_entity
+        })
+        .collect();
+
+    let mut nbt = NbtCompound::new();
+    set_tag(
+        &mut nbt,
+        "Position",
+        NbtTag::Compound(vector(int(x)?, int(y)?, int(z)?)),
+    );
+    set_tag(
+        &mut nbt,
+        "Size",
+        NbtTag::Compound(vector(int(width)?, int(height)?, int(length)?)),
+    );
+    set_tag(
+        &mut nbt,
+        "BlockStates",
+        NbtTag::LongArray(pack(&clipboard.blocks, bits_per_entry(palette.len()))),
+    );
+    set_tag(
+        &mut nbt,
+        "BlockStatePalette",
+        NbtTag::List(NbtList::Compound(palette)),
+    );
+    set_tag(
+        &mut nbt,
+        "TileEntities",
+        NbtTag::List(NbtList::Compound(block_entities)),
+    );
+    set_tag(
+        &mut nbt,
+        "Entities",
+        NbtTag::List(NbtList::Compound(region.entities.clone())),
+    );
+    set_tag(&mut nbt, "PendingBlockTicks", NbtTag::List(NbtList::Empty));
+    set_tag(&mut nbt, "PendingFluidTicks", NbtTag::List(NbtList::Empty));
+
+    Ok(nbt)
+}
+
+fn read_vector(nbt: &NbtCompound, name: &'static str) -> Result<(i32, i32, i32)> {
+    let vector = nbt.compound(name).ok_or(Error::MissingNbtTag(name))?;
+    Ok((
+        vector.int("x").ok_or(Error::MissingNbtTag("x"))?,
+        vector.int("y").ok_or(Error::MissingNbtTag("y"))?,
+        vector.int("z").ok_or(Error::MissingNbtTag("z"))?,
+    ))
+}
+
+/// Converts a position or size to the signed int it's stored as.
+fn int(value: u32) -> Result<i32> {
+    i32::try_from(value).map_err(|_| Error::InvalidSchematic(format!("{value} is too large")))
+}
+
+fn vector(x: i32, y: i32, z: i32) -> NbtCompound {
+    NbtCompound::from_values(vec![
+        ("x".into(), NbtTag::Int(x)),
+        ("y".into(), NbtTag::Int(y)),
+        ("z".into(), NbtTag::Int(z)),
+    ])
+}
+
+fn non_air_blocks(clipboard: &Clipboard) -> usize {
+    let air: Vec<bool> = clipboard
+        .palette
+        .iter()
+        .map(|b| {
+            let name = b.name.to_str();
+            is_air(name.strip_prefix("minecraft:").unwrap_or(&name))
+        })
+        .collect();
+
+    clipboard
+        .blocks
+        .iter()
+        .filter(|b| !air[**b as usize])
+        .count()
+}
+
+/// Litematica always uses at least 2 bits per entry.
+fn bits_per_entry(palette_len: usize) -> u32 {
+    (usize::BITS - palette_len.saturating_sub(1).leading_zeros()).max(2)
+}
+
+/// Packs the values tightly with `bits` each, unlike chunk sections an entry can span over two longs.
+fn pack(values: &[u32], bits: u32) -> Vec<i64> {
+    let bits = bits as usize;
+    let mut longs = vec![0u64; (values.len() * bits).div_ceil(64)];
+    for (i, value) in values.iter().enumerate() {
+        let (index, offset) = (i * bits / 64, i * bits % 64);
+        longs[index] |= (*value as u64) << offset;
+        if offset + bits > 64 {
+            longs[index + 1] |= (*value as u64) >> (64 - offset);
+        }
+    }
+
+    longs.into_iter().map(|l| l as i64).collect()
+}
+
+/// Unpacks `len` values of `bits` each, packed like with [`pack`].
+fn unpack(longs: &[i64], bits: u32, len: usize) -> Result<Vec<u32>> {
+    let bits = bits as usize;
+    let expected = (len * bits).div_ceil(64);
+    if longs.len() < expected {
+        return Err(Error::InvalidSchematic(format!(
+            "expected {expected} longs of block states but found {}",
+            longs.len()
+        )));
+    }
+
+    let mask = (1u64 << bits) - 1;
+    let mut values = Vec::with_capacity(len);
+    for i in 0..len {
+        let (index, offset) = (i * bits / 64, i * bits % 64);
+        let mut value = longs[index] as u64 >> offset;
+        if offset + bits > 64 {
+            value |= (longs[index + 1] as u64) << (64 - offset);
+        }
+        values.push((value & mask) as u32);
+    }
+
+    Ok(values)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::clipboard::test_region;
+
+    fn litematic() -> Result<Litematic> {
+        let mut region = test_region()?;
+        region.set_block((20, 0, 20), Block::new("stone"))?;
+        region.write_blocks()?;
+
+        let mut litematic = Litematic::from_region(&region, (0, 0, 0), (3, 1, 2), "Main")?;
+        litematic.regions.push(LitematicRegion {
+            name: String::from("Tower"),
+            position: (4, 0, 0),
+            clipboard: Clipboard::copy(&region, (20, 0, 20), (20, 3, 20))?,
+            entities: vec![],
+        });
+
+        Ok(litematic)
+    }
+
+    #[test]
+    fn bit_packing() -> Result<()> {
+        // 5 bits doesn't divide 64, so entry 12 spans over the first two longs
+        let values: Vec<u32> = (0..40).map(|i| i % 32).collect();
+        let longs = pack(&values, 5);
+        assert_eq!(longs.len(), 4);
+        assert_eq!(unpack(&longs, 5, values.len())?, values);
+        assert!(unpack(&longs[..3], 5, values.len()).is_err());
+
+        assert_eq!(bits_per_entry(1), 2);
+        assert_eq!(bits_per_entry(4), 2);
+        assert_eq!(bits_per_entry(5), 3);
+        assert_eq!(bits_per_entry(257), 9);
+
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip() -> Result<()> {
+        let litematic = litematic()?;
+        let mut bytes = vec![];
+        litematic.write(&mut bytes)?;
+        let read = Litematic::read(&mut bytes.as_slice())?;
+
+        assert_eq!(read.name, "Main");
+        assert_eq!(read.size()?, (5, 4, 3));
+        assert_eq!(read.regions.len(), 2);
+
+        let main = &read.regions[0].clipboard;
+        assert_eq!(main.size(), (4, 2, 3));
+        assert_eq!(
+            main.get_block((0, 0, 0)).unwrap().to_string(),
+            "minecraft:oak_stairs[facing=east,half=top]"
+        );
+        let chest = main.get_block_entity((1, 1, 1)).unwrap();
+        assert_eq!(chest.string("CustomName").unwrap().to_str(), "\"Loot\"");
+        assert_eq!(chest.int("x"), Some(1));
+
+        let tower = &read.regions[1];
+        assert_eq!(tower.name, "Tower");
+        assert_eq!(tower.position, (4, 0, 0));
+        assert_eq!(
+            tower.clipboard.get_block((0, 0, 0)).unwrap().to_string(),
+            "minecraft:stone"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn entries_spanning_longs() -> Result<()> {
+        let mut region = test_region()?;
+        region.set_block((3, 0, 0), "minecraft:stone")?;
+        region.set_block((2, 0, 2), "minecraft:dirt")?;
+        // with 6 block states each entry is 3 bits, so entry 21 starts at bit 63
+        region.set_block((1, 1, 2), "minecraft:gold_block")?;
+        region.write_blocks()?;
+        let litematic = Litematic::from_region(&region, (0, 0, 0), (3, 1, 2), "Main")?;
+
+        let nbt = litematic.to_nbt()?;
+        let main = nbt.compound("Regions").unwrap().compound("Main").unwrap();
+        let palette = compounds(main.list("BlockStatePalette"), "BlockStatePalette")?;
+        assert_eq!(palette.len(), 6);
+        assert_eq!(main.long_array("BlockStates").unwrap().len(), 2);
+
+        let read = Litematic::from_nbt(&nbt)?;
+        let clipboard = &read.regions[0].clipboard;
+        assert_eq!(
+            clipboard.get_block((1, 1, 2)).unwrap().to_string(),
+            "minecraft:gold_block"
+        );
+        assert_eq!(
+            clipboard.get_block((2, 0, 2)).unwrap().to_string(),
+            "minecraft:dirt"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn negative_size() -> Result<()> {
+        let litematic = litematic()?;
+        let mut nbt = litematic.to_nbt()?;
+        let regions = nbt.compound_mut("Regions").unwrap();
+        let tower = regions.compound_mut("Tower").unwrap();
+        // same cuboid, but measured from it's largest corner
+        set_tag(tower, "Position", NbtTag::Compound(vector(4, 3, 0)));
+        set_tag(tower, "Size", NbtTag::Compound(vector(-1, -4, 1)));
+
+        let read = Litematic::from_nbt(&nbt)?;
+        assert_eq!(read.regions[1].position, (4, 0, 0));
+        assert_eq!(read.regions[1].clipboard.size(), (1, 4, 1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn paste_rotated() -> Result<()> {
+        let litematic = litematic()?;
+        let mut region = Region::default();
+        let options = PasteOptions {
+            rotation: crate::Rotation::Clockwise90,
+            ..Default::default()
+        };
+        litematic.paste(&mut region, (100, 0, 100), options)?;
+        region.write_blocks()?;
+
+        // enclosing size is (5, 4, 3), so x becomes `2 - z` and z becomes x
+        assert_eq!(
+            region.get_block((102, 0, 100))?.to_string(),
+            "minecraft:oak_stairs[facing=south,half=top]"
+        );
+        assert_eq!(
+            region.get_block((101, 1, 101))?.to_string(),
+            "minecraft:chest"
+        );
+        assert_eq!(
+            region.get_block((102, 0, 104))?.to_string(),
+            "minecraft:stone"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_regions() -> Result<()> {
+        let mut nbt = litematic()?.to_nbt()?;
+        let regions = nbt.compound_mut("Regions").unwrap();
+        let tower = regions.compound_mut("Tower").unwrap();
+        // more blocks than fit in a `u32`
+        set_tag(tower, "Size", NbtTag::Compound(vector(65536, 65536, 2)));
+        assert!(matches!(
+            Litematic::from_nbt(&nbt),
+            Err(Error::InvalidSchematic(_))
+        ));
+
+        // the corner of a region measured from it's largest corner is below `i32::MIN`
+        let regions = nbt.compound_mut("Regions").unwrap();
+        let tower = regions.compound_mut("Tower").unwrap();
+        set_tag(tower, "Position", NbtTag::Compound(vector(i32::MIN, 0, 0)));
+        set_tag(tower, "Size", NbtTag::Compound(vector(-2, 4, 1)));
+        assert!(matches!(
+            Litematic::from_nbt(&nbt),
+            Err(Error::InvalidSchematic(_))
+        ));
+
+        // the regions are further apart than the enclosing size fits
+        let regions = nbt.compound_mut("Regions").unwrap();
+        let tower = regions.compound_mut("Tower").unwrap();
+        set_tag(tower, "Position", NbtTag::Compound(vector(i32::MAX, 0, 0)));
+        set_tag(tower, "Size", NbtTag::Compound(vector(1, 4, 1)));
+        let main = regions.compound_mut("Main").unwrap();
+        set_tag(main, "Position", NbtTag::Compound(vector(i32::MIN, 0, 0)));
+        assert!(matches!(
+            Litematic::from_nbt(&nbt),
+            Err(Error::InvalidSchematic(_))
+        ));
+
+        set_tag(&mut nbt, "Regions", NbtTag::Compound(NbtCompound::new()));
+        assert!(matches!(
+            Litematic::from_nbt(&nbt),
+            Err(Error::InvalidSchematic(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn too_large_to_write() -> Result<()> {
+        let mut litematic = litematic()?;
+        litematic.regions[1].position = (u32::MAX, 0, 0);
+        assert!(matches!(litematic.size(), Err(Error::InvalidSchematic(_))));
+        assert!(litematic.to_nbt().is_err());
+
+        // the enclosing size fits, but not it's volume
+        litematic.regions[1].position = (70_000, 70_000, 0);
+        assert!(matches!(
+            litematic.to_nbt(),
+            Err(Error::InvalidSchematic(_))
+        ));
+
+        // without any height there's no volume, but the positions are still stored as signed ints
+        litematic.regions[1].position = (i32::MAX as u32 + 1, 0, 0);
+        litematic.regions[0].clipboard.size.1 = 0;
+        litematic.regions[1].clipboard.size.1 = 0;
+        assert!(matches!(
+            litematic.to_nbt(),
+            Err(Error::InvalidSchematic(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn paste_empty() -> Result<()> {
+        let mut litematic = litematic()?;
+        let mut region = Region::default();
+
+        litematic.regions[1].clipboard.size = (0, 0, 0);
+        litematic.paste(&mut region, (100, 0, 100), PasteOptions::default())?;
+        litematic.regions.clear();
+        litematic.paste(&mut region, (100, 0, 100), PasteOptions::default())?;
+
+        Ok(())
+    }
+}
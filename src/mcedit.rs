@@ -0,0 +1,320 @@
+//! `mcedit` reads legacy MCEdit schematics (`.schematic`) from before Minecraft 1.13 as a [`Clipboard`].
+//!
+//! The numeric block ids & data values are converted with the flattening table behind [`Block::from_legacy`].
+
+use crate::{
+    Block, Clipboard, Error, Region, Result,
+    block_entity::block_entity_position,
+    clipboard::schematic_volume,
+    legacy::{legacy_block_entity, legacy_state_in_context},
+    nbt::{compounds, read_gzip_nbt},
+};
+use ahash::AHashMap;
+use simdnbt::owned::NbtCompound;
+use std::{
+    fs::File,
+    io::{BufReader, Read},
+    path::Path,
+};
+
+impl Clipboard {
+    /// Reads a gzip-compressed MCEdit schematic from the given reader.
+    pub fn read_mcedit<R: Read>(reader: &mut R) -> Result<Self> {
+        let nbt = read_gzip_nbt(reader)?;
+
+        Self::from_mcedit_nbt(&nbt)
+    }
+
+    /// Reads an MCEdit schematic from the given path.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// # use silverfish::{Clipboard, PasteOptions, Region};
+    /// # let mut region = Region::default();
+    /// let castle = Clipboard::open_mcedit("archive/castle.schematic")?;
+    /// castle.paste(&mut region, (100, 64, 100), PasteOptions::default())?;
+    /// region.write_blocks()?;
+    /// # Ok::<(), silverfish::Error>(())
+    /// ```
+    pub fn open_mcedit<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::read_mcedit(&mut BufReader::new(File::open(path)?))
+    }
+
+    /// Creates a [`Clipboard`] from the root compound of an MCEdit schematic.
+    ///
+    /// Block entities get their modern id, but their data is kept as is.
+    /// Entities & biomes are left out.
+    pub fn from_mcedit_nbt(nbt: &NbtCompound) -> Result<Self> {
+        // `Pocket` schematics from Bedrock Edition use different ids
+        if let Some(materials) = nbt
+            .string("Materials")
+            .map(|m| m.to_str())
+            .filter(|m| m != "Alpha")
+        {
+            return Err(Error::InvalidSchematic(format!(
+                "unsupported materials {materials}"
+            )));
+        }
+
+        let size = (
+            nbt.short("Width").ok_or(Error::MissingNbtTag("Width"))? as u16 as u32,
+            nbt.short("Height").ok_or(Error::MissingNbtTag("Height"))? as u16 as u32,
+            nbt.short("Length").ok_or(Error::MissingNbtTag("Length"))? as u16 as u32,
+        );
+        let volume = schematic_volume(size)?;
+
+        let ids = read_ids(nbt, volume)?;
+        let data = nbt.byte_array("Data").ok_or(Error::MissingNbtTag("Data"))?;
+        if data.len() != volume {
+            return Err(Error::InvalidSchematic(format!(
+                "expected {volume} data values but found {}",
+                data.len()
+            )));
+        }
+
+        let mut block_entities = AHashMap::new();
+        for block_entity in compounds(nbt.list("TileEntities"), "TileEntities")? {
+            let (x, y, z) = block_entity_position(block_entity)?;
+            block_entities.insert((x as u32, y as u32, z as u32), block_entity);
+        }
+
+        // anything above the schematic counts as air
+        let block = |x: u32, y: u32, z: u32| {
+            if y >= size.1 {
+                return (0, 0);
+            }
+            let index = (x + z * size.0 + y * size.0 * size.2) as usize;
+            (ids[index], data[index] & 15)
+        };
+        let mut lookup: AHashMap<String, u32> = AHashMap::new();
+        let mut palette = vec![];
+        let mut blocks = Vec::with_capacity(volume);
+        for y in 0..size.1 {
+            for z in 0..size.2 {
+                for x in 0..size.0 {
+                    let below = match y {
+                        0 => (0, 0),
+                        _ => block(x, y - 1, z),
+                    };
+                    let state = legacy_state_in_context(
+                        block(x, y, z),
+                        below,
+                        block(x, y + 1, z),
+                        block_entities.get(&(x, y, z)).copied(),
+                    )?;
+
+                    let index = match lookup.get(&state) {
+                        Some(index) => *index,
+                        None => {
+                            palette.push(state.parse::<Block>()?);
+                            lookup.insert(state, palette.len() as u32 - 1);
+                            palette.len() as u32 - 1
+                        }
+                    };
+                    blocks.push(index);
+                }
+            }
+        }
+
+        let mut clipboard = Clipboard {
+            size,
+            // the blocks are converted to their names in the oldest supported version
+            data_version: Region::MIN_DATA_VERSION,
+            palette,
+            blocks,
+            biomes: vec![],
+            block_entities: AHashMap::new(),
+        };
+        for (coords, block_entity) in block_entities {
+            if let Some(block_entity) = legacy_block_entity(block_entity) {
+                clipboard.block_entities.insert(coords, block_entity);
+            }
+        }
+
+        Ok(clipboard)
+    }
+}
+
+/// Reads the block ids, which are extended past 255 by `AddBlocks` (4 bits each) or `Add` (8 bits each).
+fn read_ids(nbt: &NbtCompound, volume: usize) -> Result<Vec<u16>> {
+    let blocks = nbt
+        .byte_array("Blocks")
+        .ok_or(Error::MissingNbtTag("Blocks"))?;
+    if blocks.len() != volume {
+        return Err(Error::InvalidSchematic(format!(
+            "expected {volume} blocks but found {}",
+            blocks.len()
+        )));
+    }
+
+    let add_blocks = nbt.byte_array("AddBlocks");
+    let add = nbt.byte_array("Add");
+    Ok(blocks
+        .iter()
+        .enumerate()
+        .map(|(i, id)| {
+            let high = match (add_blocks, add) {
+                // two ids share a byte, the first one in the lower 4 bits
+                (Some(add_blocks), _) => match add_blocks.get(i >> 1) {
+                    Some(nibbles) if i & 1 == 0 => nibbles & 0x0F,
+                    Some(nibbles) => nibbles >> 4,
+                    None => 0,
+                },
+                (None, Some(add)) => add.get(i).copied().unwrap_or_default(),
+                (None, None) => 0,
+            };
+            ((high as u16) << 8) | *id as u16
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{PasteOptions, nbt::set_tag};
+    use simdnbt::owned::{NbtList, NbtTag};
+
+    /// A 2x2x1 schematic with a door, a chest and a blue bed foot.
+    fn schematic() -> NbtCompound {
+        let chest = NbtCompound::from_values(vec![
+            ("id".into(), NbtTag::String("Chest".into())),
+            ("x".into(), NbtTag::Int(1)),
+            ("y".into(), NbtTag::Int(0)),
+            ("z".into(), NbtTag::Int(0)),
+        ]);
+        let bed = NbtCompound::from_values(vec![
+            ("id".into(), NbtTag::String("minecraft:bed".into())),
+            ("x".into(), NbtTag::Int(1)),
+            ("y".into(), NbtTag::Int(1)),
+            ("z".into(), NbtTag::Int(0)),
+            ("color".into(), NbtTag::Int(11)),
+        ]);
+
+        NbtCompound::from_values(vec![
+            ("Width".into(), NbtTag::Short(2)),
+            ("Height".into(), NbtTag::Short(2)),
+            ("Length".into(), NbtTag::Short(1)),
+            ("Materials".into(), NbtTag::String("Alpha".into())),
+            // door lower & chest, then door upper & bed
+            ("Blocks".into(), NbtTag::ByteArray(vec![64, 54, 64, 26])),
+            ("Data".into(), NbtTag::ByteArray(vec![3, 3, 9, 2])),
+            (
+                "TileEntities".into(),
+                NbtTag::List(NbtList::Compound(vec![chest, bed])),
+            ),
+        ])
+    }
+
+    #[test]
+    fn read() -> Result<()> {
+        let clipboard = Clipboard::from_mcedit_nbt(&schematic())?;
+        assert_eq!(clipboard.size(), (2, 2, 1));
+        assert_eq!(
+            clipboard.get_block((0, 0, 0)).unwrap().to_string(),
+            "minecraft:oak_door[facing=north,half=lower,hinge=right,open=false,powered=false]"
+        );
+        assert_eq!(
+            clipboard.get_block((0, 1, 0)).unwrap().to_string(),
+            "minecraft:oak_door[facing=north,half=upper,hinge=right,open=false,powered=false]"
+        );
+        assert_eq!(
+            clipboard.get_block((1, 0, 0)).unwrap().to_string(),
+            "minecraft:chest[facing=south]"
+        );
+        assert_eq!(
+            clipboard.get_block((1, 1, 0)).unwrap().to_string(),
+            "minecraft:blue_bed[facing=north,occupied=false,part=foot]"
+        );
+
+        let chest = clipboard.get_block_entity((1, 0, 0)).unwrap();
+        assert_eq!(chest.string("id").unwrap().to_str(), "minecraft:chest");
+        let bed = clipboard.get_block_entity((1, 1, 0)).unwrap();
+        assert!(bed.int("color").is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn add_blocks() -> Result<()> {
+        let mut nbt = schematic();
+        nbt.insert("AddBlocks", NbtTag::ByteArray(vec![0x10, 0x00]));
+        assert_eq!(read_ids(&nbt, 4)?, vec![64, 54 | (1 << 8), 64, 26]);
+
+        // unknown ids past 255 can't be converted
+        assert!(matches!(
+            Clipboard::from_mcedit_nbt(&nbt),
+            Err(Error::UnknownLegacyBlock(310, 3))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_size() {
+        let mut nbt = schematic();
+        // the sizes are read as unsigned, so this is 65535 long
+        for (tag, value) in [("Width", -1), ("Height", -1), ("Length", 2)] {
+            set_tag(&mut nbt, tag, NbtTag::Short(value));
+        }
+        assert!(matches!(
+            Clipboard::from_mcedit_nbt(&nbt),
+            Err(Error::InvalidSchematic(_))
+        ));
+
+        set_tag(&mut nbt, "Width", NbtTag::Short(0));
+        assert!(matches!(
+            Clipboard::from_mcedit_nbt(&nbt),
+            Err(Error::InvalidSchematic(_))
+        ));
+    }
+
+    #[test]
+    fn paste() -> Result<()> {
+        let clipboard = Clipboard::from_mcedit_nbt(&schematic())?;
+        let mut region = Region::default();
+        clipboard.paste(&mut region, (10, 0, 10), PasteOptions::default())?;
+        region.write_blocks()?;
+
+        assert_eq!(
+            region.get_block((11, 0, 10))?.to_string(),
+            "minecraft:chest[facing=south]"
+        );
+        let chest = region.get_block_entity((11, 0, 10))?.unwrap();
+        assert_eq!(chest.int("x"), Some(11));
+
+        Ok(())
+    }
+
+    #[test]
+    fn paste_renamed() -> Result<()> {
+        let mut nbt = schematic();
+        set_tag(&mut nbt, "Blocks", NbtTag::ByteArray(vec![31, 31, 0, 0]));
+        set_tag(&mut nbt, "Data", NbtTag::ByteArray(vec![1, 2, 0, 0]));
+        set_tag(&mut nbt, "TileEntities", NbtTag::List(NbtList::Empty));
+        let clipboard = Clipboard::from_mcedit_nbt(&nbt)?;
+
+        let mut region = Region::default();
+        clipboard.paste(&mut region, (10, 0, 10), PasteOptions::default())?;
+        region.write_blocks()?;
+        assert_eq!(
+            region.get_block((10, 0, 10))?.to_string(),
+            "minecraft:grass"
+        );
+
+        let mut region = Region::default();
+        set_tag(
+            &mut region.get_chunk_mut(0, 0)?.nbt,
+            "DataVersion",
+            NbtTag::Int(3700),
+        );
+        clipboard.paste(&mut region, (10, 0, 10), PasteOptions::default())?;
+        region.write_blocks()?;
+        assert_eq!(
+            region.get_block((10, 0, 10))?.to_string(),
+            "minecraft:short_grass"
+        );
+        assert_eq!(region.get_block((11, 0, 10))?.to_string(), "minecraft:fern");
+
+        Ok(())
+    }
+}
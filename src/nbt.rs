@@ -1,11 +1,23 @@
 //! `nbt` contains the [`Block`] struct used to set/get blocks and its associated functions and data.  
 
-use crate::error::{Error, Result};
+use crate::{
+    BlockRegistry,
+    error::{Error, Result},
+    snbt::{parse_block_state, to_snbt},
+};
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
 use simdnbt::{
     Mutf8Str, Mutf8String,
-    owned::{NbtCompound, NbtTag},
+    owned::{BaseNbt, Nbt, NbtCompound, NbtList, NbtTag},
+};
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    fs::File,
+    hash::Hash,
+    io::{BufWriter, Cursor, Read, Write},
+    path::Path,
 };
-use std::{borrow::Cow, collections::BTreeMap, hash::Hash};
 
 /// A Minecraft [Block](https://minecraft.wiki/w/Block), used when setting blocks or when retrieving blocks
 #[derive(Clone, PartialEq, Eq)]
@@ -127,6 +139,81 @@ impl Block {
         })
     }
 
+    /// Tries to create a new block from it's id and properties, checking them against a [`BlockRegistry`].
+    ///
+    /// Fails if the block doesn't exist or a property or value isn't valid for it.
+    ///
+    /// ## Example
+    /// ```
+    /// # use silverfish::{Block, BlockRegistry};
+    /// # let registry = BlockRegistry::from_json_str(r#"{ "minecraft:conduit": { "properties": { "waterlogged": ["true", "false"] } } }"#)?;
+    /// let conduit = Block::try_new_checked("conduit", &[("waterlogged", "true")], &registry)?;
+    /// assert!(Block::try_new_checked("conduit", &[("pickles", "4")], &registry).is_err());
+    /// # Ok::<(), silverfish::Error>(())
+    /// ```
+    pub fn try_new_checked<B: Into<Name>>(
+        block: B,
+        properties: &[(&str, &str)],
+        registry: &BlockRegistry,
+    ) -> Result<Self> {
+        let block = Self::try_new_with_props(block, properties)?;
+        registry.validate(&block)?;
+
+        Ok(block)
+    }
+
+    /// Fills in every missing property with the value from the block's default state in the [`BlockRegistry`].
+    ///
+    /// ## Example
+    /// ```
+    /// # use silverfish::{Block, BlockRegistry};
+    /// # let registry = BlockRegistry::from_json_str(r#"{ "minecraft:furnace": { "states": [
+    /// #     { "default": true, "id": 1, "properties": { "facing": "north", "lit": "false" } }
+    /// # ] } }"#)?;
+    /// let furnace = Block::new("furnace").with_default_properties(&registry);
+    /// # Ok::<(), silverfish::Error>(())
+    /// ```
+    pub fn with_default_properties(self, registry: &BlockRegistry) -> Self {
+        registry.fill_defaults(self)
+    }
+
+    /// Compares two blocks where any missing property counts as it's default value in the [`BlockRegistry`].
+    ///
+    /// See [`BlockRegistry::same_state`].
+    pub fn eq_with_defaults(&self, other: &Block, registry: &BlockRegistry) -> bool {
+        registry.same_state(self, other)
+    }
+
+    /// Parses a block state string that may end with the block entity data in SNBT.
+    ///
+    /// Same syntax as `/setblock`, `namespace:id[key=value,...]{...}`.
+    ///
+    /// ## Example
+    /// ```
+    /// # use silverfish::Block;
+    /// let (chest, nbt) = Block::parse_with_entity("chest[facing=east]{Lock: \"key\"}")?;
+    /// assert_eq!(chest, Block::new_with_props("chest", [("facing", "east")]));
+    /// assert!(nbt.is_some());
+    /// # Ok::<(), silverfish::Error>(())
+    /// ```
+    pub fn parse_with_entity(input: &str) -> Result<(Self, Option<NbtCompound>)> {
+        parse_block_state(input)
+    }
+
+    /// Formats the block together with it's block entity data, the opposite of [`Block::parse_with_entity`].
+    ///
+    /// ## Example
+    /// ```
+    /// # use silverfish::{Block, parse_snbt};
+    /// let chest = Block::new_with_props("chest", [("facing", "east")]);
+    /// let nbt = parse_snbt("{Lock: 'key'}")?;
+    /// assert_eq!(chest.to_string_with_entity(&nbt), r#"minecraft:chest[facing=east]{Lock: "key"}"#);
+    /// # Ok::<(), silverfish::Error>(())
+    /// ```
+    pub fn to_string_with_entity(&self, nbt: &NbtCompound) -> String {
+        format!("{self}{}", to_snbt(nbt))
+    }
+
     /// Creates a new block from just an id
     ///
     /// Auto populates into minecraft namespace if no namespace was given
@@ -294,6 +381,59 @@ impl Name {
     }
 }
 
+/// Sets a tag within a compound, replacing any already existing tag with the same name.  
+pub(crate) fn set_tag(compound: &mut NbtCompound, name: &str, tag: NbtTag) {
+    compound.remove(name);
+    compound.insert(name, tag);
+}
+
+/// Returns the compounds within a list, a missing or empty list has none.  
+pub(crate) fn compounds<'a>(
+    list: Option<&'a NbtList>,
+    name: &'static str,
+) -> Result<&'a [NbtCompound]> {
+    match list {
+        Some(NbtList::Compound(c)) => Ok(c),
+        Some(NbtList::Empty) | None => Ok(&[]),
+        Some(_) => Err(Error::InvalidNbtList(name)),
+    }
+}
+
+/// Reads the root compound of a gzip-compressed NBT file, like `level.dat` or a schematic.  
+pub(crate) fn read_gzip_nbt<R: Read>(reader: &mut R) -> Result<NbtCompound> {
+    let mut bytes = Vec::new();
+    GzDecoder::new(reader).read_to_end(&mut bytes)?;
+
+    match simdnbt::owned::read(&mut Cursor::new(&bytes))? {
+        Nbt::Some(nbt) => Ok(nbt.as_compound()),
+        Nbt::None => Err(Error::InvalidNbtType("base_nbt")),
+    }
+}
+
+/// Writes a root compound named `name` gzip-compressed, the opposite of [`read_gzip_nbt`].  
+pub(crate) fn write_gzip_nbt<W: Write>(writer: &mut W, name: &str, nbt: NbtCompound) -> Result<()> {
+    let mut raw_nbt = vec![];
+    Nbt::Some(BaseNbt::new(name, nbt)).write(&mut raw_nbt);
+
+    let mut encoder = GzEncoder::new(writer, Compression::default());
+    encoder.write_all(&raw_nbt)?;
+    encoder.finish()?;
+
+    Ok(())
+}
+
+/// Creates the file at `path` and writes to it through a buffer.  
+pub(crate) fn write_file(
+    path: &Path,
+    write: impl FnOnce(&mut BufWriter<File>) -> Result<()>,
+) -> Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write(&mut writer)?;
+    writer.flush()?;
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -404,6 +544,17 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn set_existing_tag() {
+        let mut nbt = NbtCompound::from_values(vec![("x".into(), NbtTag::Int(5))]);
+        set_tag(&mut nbt, "x", NbtTag::Int(12));
+        set_tag(&mut nbt, "y", NbtTag::Int(-3));
+
+        assert_eq!(nbt.len(), 2);
+        assert_eq!(nbt.int("x"), Some(12));
+        assert_eq!(nbt.int("y"), Some(-3));
+    }
+
     #[test]
     fn populate_namespace() {
         let id = Block::populate_namespace("lime_concrete");
@@ -449,4 +600,28 @@ mod test {
         let name_2 = Name::new_id("white_stained_glass");
         assert_eq!(name_1, name_2.into_namespaced())
     }
+
+    #[test]
+    fn block_state_string() -> Result<()> {
+        let block: Block = "oak_stairs[facing=east,half=top]".parse()?;
+        assert_eq!(
+            block,
+            Block::new_with_props("oak_stairs", [("facing", "east"), ("half", "top")])
+        );
+        assert_eq!(
+            block.to_string(),
+            "minecraft:oak_stairs[facing=east,half=top]"
+        );
+        assert_eq!(Block::new("mymod:machine").to_string(), "mymod:machine");
+
+        let round_trip: Block = block.to_string().parse()?;
+        assert_eq!(round_trip.to_string(), block.to_string());
+
+        assert!("chest{Lock: \"key\"}".parse::<Block>().is_err());
+        let (chest, nbt) = Block::parse_with_entity("chest{Lock: \"key\"}")?;
+        assert_eq!(chest, Block::new("chest"));
+        assert!(nbt.is_some());
+
+        Ok(())
+    }
 }
@@ -1,6 +1,12 @@
-use crate::{Block, Name, NbtString};
+use crate::{Block, Error, Name, NbtString, Result, snbt::parse_block_state};
 use simdnbt::{Mutf8Str, Mutf8String, owned::NbtCompound};
-use std::{borrow::Cow, collections::BTreeMap, fmt::Debug, hash::Hash};
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    fmt::{Debug, Display},
+    hash::Hash,
+    str::FromStr,
+};
 
 impl Debug for Block {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -31,6 +37,41 @@ impl Debug for Block {
     }
 }
 
+impl Display for Block {
+    /// Formats the block like Minecraft does, `minecraft:oak_stairs[facing=east,half=top]`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name.into_cow_namespaced().to_str())?;
+        if let Some(props) = self.properties.as_ref().filter(|p| !p.is_empty()) {
+            let props = props
+                .iter()
+                .map(|(k, v)| format!("{}={}", k.to_str(), v.to_str()))
+                .collect::<Vec<String>>()
+                .join(",");
+            write!(f, "[{props}]")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Block {
+    type Err = Error;
+
+    /// Parses a block state string like `oak_stairs[facing=east,half=top]`.
+    ///
+    /// Block entity data isn't part of a [`Block`], use [`Block::parse_with_entity`] for that.
+    fn from_str(s: &str) -> Result<Self> {
+        match parse_block_state(s)? {
+            (block, None) => Ok(block),
+            (_, Some(_)) => Err(Error::InvalidBlockState {
+                input: s.to_owned(),
+                position: s.find('{').unwrap_or_default(),
+                reason: String::from("block entity data needs Block::parse_with_entity"),
+            }),
+        }
+    }
+}
+
 impl Into<Block> for &str {
     fn into(self) -> Block {
         Block::new(self)
@@ -2,7 +2,7 @@
 //! gathered from [`get_blocks`](crate::Region::get_blocks) & [`get_block`](crate::Region::get_block).  
 //! Theses blocks are lazily collected and just holds references until used.  
 
-use crate::{Block, BlockWithCoordinate, Coords, Error, Result};
+use crate::{Block, BlockRegistry, BlockWithCoordinate, Coords, Error, Result};
 use fixedbitset::{FixedBitSet, Ones};
 use simdnbt::owned::NbtCompound;
 use std::{ops::Range, u32};
@@ -145,6 +145,17 @@ impl<'a> PalettedBlocks<'a> {
         false
     }
 
+    /// Like [`PalettedBlocks::contains`] but any missing property counts as it's default value in the [`BlockRegistry`].  
+    pub fn contains_with_defaults(&self, block: &Block, registry: &BlockRegistry) -> bool {
+        for (_, pal_block) in self {
+            if registry.same_state(&pal_block, block) {
+                return true;
+            }
+        }
+
+        false
+    }
+
     /// Converts all the blocks into a list of [`BlockWithCoordinate`]
     pub fn get_all(&self) -> Vec<BlockWithCoordinate> {
         self.into_iter()
@@ -0,0 +1,761 @@
+//! `poi` contains the [`PoiRegion`] struct used to read & write the points of interest within a region.
+//!
+//! Points of interest are stored in their own region files in the `poi` folder, next to the `region` folder.
+//! Villagers find their beds, workstations & bells through them, and bees & portals use them as well.
+//! With [`Config::update_poi`](crate::Config::update_poi) set, block changes are written to the attached [`PoiRegion`].
+
+use crate::{
+    BLOCKS_PER_REGION, Block, Coords, Error, NbtString, Region, Result,
+    region::{read_chunk_nbt, to_region_coords, to_region_local},
+    region_file::{self, Compression, ExternalChunks, RawChunk},
+};
+use ahash::AHashMap;
+use simdnbt::owned::{BaseNbt, Nbt, NbtCompound, NbtList, NbtTag};
+use std::{
+    collections::BTreeMap,
+    fmt::Debug,
+    io::{Read, Write},
+    path::Path,
+};
+
+/// A single point of interest, like a bed or a workstation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoiRecord {
+    /// The point of interest type, like `minecraft:home`
+    pub kind: NbtString,
+    /// The *global* coordinates of the block
+    pub position: (i32, i32, i32),
+    /// How many more villagers (or bees etc.) can claim this point of interest
+    pub free_tickets: i32,
+}
+
+impl PoiRecord {
+    /// Creates a new [`PoiRecord`] at the *global* coordinates.
+    pub fn new<S: Into<NbtString>>(kind: S, position: (i32, i32, i32), free_tickets: i32) -> Self {
+        Self {
+            kind: kind.into(),
+            position,
+            free_tickets,
+        }
+    }
+
+    /// Creates the [`PoiRecord`] Minecraft would create for a block, if the block is a point of interest.
+    ///
+    /// ## Example
+    /// ```
+    /// # use silverfish::{Block, PoiRecord};
+    /// let record = PoiRecord::from_block(&Block::new("minecraft:bell"), (5, 70, -3)).unwrap();
+    /// assert_eq!(record.kind, "minecraft:meeting");
+    /// assert!(PoiRecord::from_block(&Block::new("minecraft:stone"), (5, 70, -3)).is_none());
+    /// ```
+    pub fn from_block(block: &Block, position: (i32, i32, i32)) -> Option<Self> {
+        let poi = poi_of_block(block)?;
+        Some(Self::new(poi.kind, position, poi.max_tickets))
+    }
+
+    /// Creates a [`PoiRecord`] from it's NBT compound.
+    pub fn from_nbt(nbt: &NbtCompound) -> Result<Self> {
+        let kind =
+            NbtString::from_mutf8str(nbt.string("type")).ok_or(Error::MissingNbtTag("type"))?;
+        let position = match nbt.int_array("pos").ok_or(Error::MissingNbtTag("pos"))? {
+            [x, y, z] => (*x, *y, *z),
+            _ => return Err(Error::InvalidNbtType("pos")),
+        };
+
+        Ok(Self {
+            kind,
+            position,
+            free_tickets: nbt.int("free_tickets").unwrap_or(0),
+        })
+    }
+
+    /// Returns the record as an NBT compound.
+    pub fn to_nbt(&self) -> NbtCompound {
+        NbtCompound::from_values(vec![
+            (
+                "type".into(),
+                NbtTag::String(self.kind.clone().to_mutf8string()),
+            ),
+            (
+                "pos".into(),
+                NbtTag::IntArray(vec![self.position.0, self.position.1, self.position.2]),
+            ),
+            ("free_tickets".into(), NbtTag::Int(self.free_tickets)),
+        ])
+    }
+}
+
+/// A section *(16\*16\*16 blocks)* within a [`PoiChunk`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PoiSection {
+    /// If `false`, Minecraft rebuilds the section's records from the blocks when it's loaded
+    pub valid: bool,
+    /// The points of interest within the section
+    pub records: Vec<PoiRecord>,
+}
+
+/// A chunk within a [`PoiRegion`] and the points of interest within it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PoiChunk {
+    /// The `DataVersion` of the chunk
+    pub data_version: i32,
+    /// The sections within the chunk, mapped to their section Y
+    pub(crate) sections: BTreeMap<i32, PoiSection>,
+    /// The chunk exactly as it was read from the region file, dropped once modified
+    pub(crate) raw: Option<RawChunk>,
+    /// The compression the chunk was read with
+    pub(crate) compression: Option<Compression>,
+}
+
+impl PoiChunk {
+    /// Creates a new [`PoiChunk`] without any sections.
+    pub fn new(data_version: i32) -> Self {
+        Self {
+            data_version,
+            sections: BTreeMap::new(),
+            raw: None,
+            compression: None,
+        }
+    }
+
+    /// Creates a [`PoiChunk`] from the root compound of a POI chunk.
+    pub fn from_nbt(nbt: &NbtCompound) -> Result<Self> {
+        let data_version = nbt
+            .int("DataVersion")
+            .ok_or(Error::MissingNbtTag("DataVersion"))?;
+
+        let mut chunk = Self::new(data_version);
+        if let Some(sections) = nbt.compound("Sections") {
+            for (y, section) in sections.iter() {
+                let y = y
+                    .to_str()
+                    .parse::<i32>()
+                    .map_err(|_| Error::InvalidNbtType("Sections > key"))?;
+                let section = section
+                    .compound()
+                    .ok_or(Error::InvalidNbtType("Sections > value"))?;
+
+                let records = match section.list("Records") {
+                    Some(NbtList::Compound(records)) => records
+                        .iter()
+                        .map(PoiRecord::from_nbt)
+                        .collect::<Result<Vec<PoiRecord>>>()?,
+                    Some(NbtList::Empty) | None => vec![],
+                    Some(_) => return Err(Error::InvalidNbtList("Records")),
+                };
+
+                chunk.sections.insert(
+                    y,
+                    PoiSection {
+                        valid: section.byte("Valid").unwrap_or(0) != 0,
+                        records,
+                    },
+                );
+            }
+        }
+
+        Ok(chunk)
+    }
+
+    /// Returns the chunk's root compound.
+    pub fn to_nbt(&self) -> NbtCompound {
+        let mut sections = NbtCompound::new();
+        for (y, section) in self.sections.iter() {
+            let records = if section.records.is_empty() {
+                NbtList::Empty
+            } else {
+                NbtList::Compound(section.records.iter().map(PoiRecord::to_nbt).collect())
+            };
+
+            sections.insert(
+                y.to_string().as_str(),
+                NbtTag::Compound(NbtCompound::from_values(vec![
+                    ("Valid".into(), NbtTag::Byte(section.valid as i8)),
+                    ("Records".into(), NbtTag::List(records)),
+                ])),
+            );
+        }
+
+        NbtCompound::from_values(vec![
+            ("DataVersion".into(), NbtTag::Int(self.data_version)),
+            ("Sections".into(), NbtTag::Compound(sections)),
+        ])
+    }
+
+    /// Returns the sections within the chunk, mapped to their section Y.
+    pub fn sections(&self) -> &BTreeMap<i32, PoiSection> {
+        &self.sections
+    }
+
+    /// Returns the sections within the chunk mutably, marking the chunk as modified.
+    pub fn sections_mut(&mut self) -> &mut BTreeMap<i32, PoiSection> {
+        self.mark_modified();
+        &mut self.sections
+    }
+
+    /// Returns every point of interest within the chunk.
+    pub fn records(&self) -> impl Iterator<Item = &PoiRecord> {
+        self.sections.values().flat_map(|s| s.records.iter())
+    }
+
+    /// Marks the chunk as modified, so it gets re-encoded when written.
+    pub fn mark_modified(&mut self) {
+        self.raw = None;
+    }
+
+    /// Returns `true` if the chunk has been modified since it was read from the region file.
+    pub fn is_modified(&self) -> bool {
+        self.raw.is_none()
+    }
+
+    /// Removes the record at the *global* coordinates, if there is one.
+    fn remove_record(&mut self, position: (i32, i32, i32)) -> Option<PoiRecord> {
+        let section = self.sections.get_mut(&(position.1 >> 4))?;
+        let index = section
+            .records
+            .iter()
+            .position(|r| r.position == position)?;
+        self.raw = None;
+
+        Some(section.records.remove(index))
+    }
+
+    /// Adds a record to it's section, replacing any record already at the same coordinates.
+    fn insert_record(&mut self, record: PoiRecord) {
+        self.remove_record(record.position);
+        self.mark_modified();
+
+        let section = self
+            .sections
+            .entry(record.position.1 >> 4)
+            .or_insert_with(|| PoiSection {
+                valid: true,
+                records: vec![],
+            });
+        section.records.push(record);
+    }
+}
+
+/// An in-memory POI region, read from `poi/r.<x>.<z>.mca`.
+///
+/// All coordinates given to a [`PoiRegion`] are local to within the region,
+/// just like with [`Region`].
+///
+/// ## Example
+/// ```no_run
+/// # use silverfish::{Config, PoiRegion, Region};
+/// let mut region = Region::open("saves/New World/region/r.0.0.mca")?;
+/// region.set_config(Config { update_poi: true, ..Default::default() })?;
+/// region.set_poi_region(PoiRegion::open("saves/New World/poi/r.0.0.mca")?)?;
+///
+/// region.set_block((40, 70, 12), "minecraft:bell")?;
+/// region.write_blocks()?;
+///
+/// region.take_poi_region().unwrap().save("saves/New World/poi/r.0.0.mca")?;
+/// region.save("saves/New World/region/r.0.0.mca")?;
+/// # Ok::<(), silverfish::Error>(())
+/// ```
+#[derive(Clone)]
+pub struct PoiRegion {
+    /// The chunks within the region, mapped to their coordinates
+    pub(crate) chunks: AHashMap<(u8, u8), PoiChunk>,
+    /// Coordinates for this specific region
+    pub region_coords: (i32, i32),
+    /// Where oversized chunks are stored, if read from a region file
+    pub(crate) external: Option<ExternalChunks>,
+}
+
+/// A point of interest type and how many tickets it starts with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct PoiType {
+    pub(crate) kind: &'static str,
+    pub(crate) max_tickets: i32,
+}
+
+/// A point of interest that changed when a block was written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PoiChange {
+    /// The block coordinates *(local to within the region)*
+    pub(crate) coords: Coords,
+    /// The new point of interest at the coordinates, `None` if it was removed
+    pub(crate) poi: Option<PoiType>,
+}
+
+impl PoiRegion {
+    /// Creates an empty [`PoiRegion`] with no chunks.
+    pub fn empty(region_coords: (i32, i32)) -> Self {
+        Self {
+            chunks: AHashMap::new(),
+            region_coords,
+            external: None,
+        }
+    }
+
+    /// Creates a [`PoiRegion`] from an already existing POI region file.
+    pub fn from_region<R: Read>(reader: &mut R, region_coords: (i32, i32)) -> Result<Self> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+
+        Self::from_region_bytes(&bytes, region_coords, None)
+    }
+
+    /// Opens a POI region file, working out the region coordinates from it's `r.<x>.<z>.mca` file name.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let (region_coords, bytes, external) = region_file::open_region(path.as_ref())?;
+        Self::from_region_bytes(&bytes, region_coords, Some(external))
+    }
+
+    /// Reads every chunk from the bytes of a POI region file.
+    fn from_region_bytes(
+        bytes: &[u8],
+        region_coords: (i32, i32),
+        external: Option<ExternalChunks>,
+    ) -> Result<Self> {
+        let mut region = Self::empty(region_coords);
+        for (coords, raw) in region_file::read_chunks(bytes, external.as_ref())? {
+            let mut chunk = PoiChunk::from_nbt(&read_chunk_nbt(&raw.decompress()?)?)?;
+            chunk.compression = Some(raw.compression);
+            chunk.raw = Some(raw);
+            region.chunks.insert(coords, chunk);
+        }
+
+        region.external = external;
+
+        Ok(region)
+    }
+
+    /// Writes the POI region to the specified writer.
+    ///
+    /// Chunks that haven't been modified are copied over as is.
+    pub fn write<W: Write>(self, writer: &mut W) -> Result<()> {
+        region_file::write_region(writer, self.into_raw_chunks()?)
+    }
+
+    /// Saves the POI region to a POI region file.
+    ///
+    /// The file name must be `r.<x>.<z>.mca` and match [`PoiRegion::region_coords`].
+    pub fn save<P: AsRef<Path>>(self, path: P) -> Result<()> {
+        let (region_coords, external) = (self.region_coords, self.external.clone());
+        region_file::save_region(
+            path.as_ref(),
+            region_coords,
+            self.into_raw_chunks()?,
+            external.as_ref(),
+        )
+    }
+
+    /// Turns every chunk into it's compressed form, ready to be written to a region file.
+    fn into_raw_chunks(self) -> Result<Vec<((u8, u8), RawChunk)>> {
+        let now = region_file::now_timestamp();
+        let mut chunks = Vec::with_capacity(self.chunks.len());
+        for (coords, chunk) in self.chunks.into_iter() {
+            let raw = match chunk.raw {
+                Some(raw) => raw,
+                None => {
+                    let mut raw_nbt = vec![];
+                    Nbt::Some(BaseNbt::new("", chunk.to_nbt())).write(&mut raw_nbt);
+                    RawChunk::compress(&raw_nbt, chunk.compression.unwrap_or_default(), now)?
+                }
+            };
+            chunks.push((coords, raw));
+        }
+
+        Ok(chunks)
+    }
+
+    /// Returns `true` if any chunk has been modified since the region was read.
+    pub fn is_modified(&self) -> bool {
+        self.chunks.values().any(|c| c.is_modified())
+    }
+
+    /// Returns the POI chunk at the given chunk coordinates.
+    pub fn get_chunk(&self, x: u8, z: u8) -> Option<&PoiChunk> {
+        self.chunks.get(&(x, z))
+    }
+
+    /// Returns the POI chunk at the given chunk coordinates mutably, marking it as modified.
+    pub fn get_chunk_mut(&mut self, x: u8, z: u8) -> Option<&mut PoiChunk> {
+        let chunk = self.chunks.get_mut(&(x, z))?;
+        chunk.mark_modified();
+        Some(chunk)
+    }
+
+    /// Returns every point of interest within the region.
+    pub fn records(&self) -> impl Iterator<Item = &PoiRecord> {
+        self.chunks.values().flat_map(|c| c.records())
+    }
+
+    /// Returns the point of interest at the specified coordinates *(local to within the region)*.
+    ///
+    /// ## Example
+    /// ```
+    /// # use silverfish::{PoiRecord, PoiRegion};
+    /// let mut region = PoiRegion::empty((0, 0));
+    /// region.set_record(PoiRecord::new("minecraft:home", (12, 64, 3), 1))?;
+    /// assert_eq!(region.get_record((12, 64, 3)).unwrap().kind, "minecraft:home");
+    /// # Ok::<(), silverfish::Error>(())
+    /// ```
+    pub fn get_record<C: Into<Coords>>(&self, coords: C) -> Option<&PoiRecord> {
+        let coords: Coords = coords.into();
+        let position = self.global_position(coords);
+
+        self.chunks
+            .get(&((coords.x >> 4) as u8, (coords.z >> 4) as u8))?
+            .sections
+            .get(&(position.1 >> 4))?
+            .records
+            .iter()
+            .find(|r| r.position == position)
+    }
+
+    /// Adds a point of interest, replacing any record already at the same coordinates.
+    ///
+    /// Returns an error if the record is outside of this region.
+    pub fn set_record(&mut self, record: PoiRecord) -> Result<()> {
+        if to_region_coords(record.position) != self.region_coords {
+            return Err(Error::PoiOutsideRegion {
+                region: self.region_coords,
+                position: record.position,
+            });
+        }
+
+        let local = to_region_local(record.position);
+        let chunk = ((local.x >> 4) as u8, (local.z >> 4) as u8);
+        let data_version = self.data_version();
+        self.chunks
+            .entry(chunk)
+            .or_insert_with(|| PoiChunk::new(data_version))
+            .insert_record(record);
+
+        Ok(())
+    }
+
+    /// Removes the point of interest at the specified coordinates *(local to within the region)*, returning it if it existed.
+    pub fn remove_record<C: Into<Coords>>(&mut self, coords: C) -> Option<PoiRecord> {
+        let coords: Coords = coords.into();
+        let position = self.global_position(coords);
+
+        self.chunks
+            .get_mut(&((coords.x >> 4) as u8, (coords.z >> 4) as u8))?
+            .remove_record(position)
+    }
+
+    /// Applies the point of interest changes made by writing blocks to a chunk.
+    pub(crate) fn apply_changes(
+        &mut self,
+        chunk: (u8, u8),
+        data_version: i32,
+        changes: Vec<PoiChange>,
+    ) {
+        for change in changes {
+            let position = self.global_position(change.coords);
+            let poi_chunk = self
+                .chunks
+                .entry(chunk)
+                .or_insert_with(|| PoiChunk::new(data_version));
+
+            match change.poi {
+                Some(poi) => {
+                    poi_chunk.insert_record(PoiRecord::new(poi.kind, position, poi.max_tickets))
+                }
+                None => {
+                    poi_chunk.remove_record(position);
+                }
+            }
+        }
+    }
+
+    /// Converts coordinates local to this region into global coordinates.
+    fn global_position(&self, coords: Coords) -> (i32, i32, i32) {
+        (
+            self.region_coords.0 * BLOCKS_PER_REGION as i32 + coords.x as i32,
+            coords.y,
+            self.region_coords.1 * BLOCKS_PER_REGION as i32 + coords.z as i32,
+        )
+    }
+
+    /// The `DataVersion` new chunks are created with, taken from any existing chunk.
+    fn data_version(&self) -> i32 {
+        self.chunks
+            .values()
+            .map(|c| c.data_version)
+            .max()
+            .unwrap_or(Region::MIN_DATA_VERSION)
+    }
+}
+
+impl Region {
+    /// Attaches the [`PoiRegion`] that point of interest changes are written to.
+    ///
+    /// Required when [`Config::update_poi`](crate::Config::update_poi) is set,
+    /// since the POI region has to be written back to it's own file via [`Region::take_poi_region`].
+    pub fn set_poi_region(&mut self, poi: PoiRegion) -> Result<()> {
+        if poi.region_coords != self.region_coords {
+            return Err(Error::PoiRegionMismatch {
+                region: self.region_coords,
+                poi: poi.region_coords,
+            });
+        }
+        self.poi = Some(poi);
+
+        Ok(())
+    }
+
+    /// Returns the attached [`PoiRegion`].
+    pub fn get_poi_region(&self) -> Option<&PoiRegion> {
+        self.poi.as_ref()
+    }
+
+    /// Returns the attached [`PoiRegion`] mutably.
+    pub fn get_poi_region_mut(&mut self) -> Option<&mut PoiRegion> {
+        self.poi.as_mut()
+    }
+
+    /// Detaches the [`PoiRegion`] from the region, so it can be saved.
+    pub fn take_poi_region(&mut self) -> Option<PoiRegion> {
+        self.poi.take()
+    }
+
+    /// Makes sure there is a [`PoiRegion`] to write to if [`Config::update_poi`](crate::Config::update_poi) is set.
+    pub(crate) fn check_poi_region(&self) -> Result<()> {
+        if self.config.update_poi && self.poi.is_none() {
+            return Err(Error::MissingPoiRegion(
+                self.region_coords.0,
+                self.region_coords.1,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Moves the point of interest changes collected by the chunks over to the attached [`PoiRegion`].
+    pub(crate) fn apply_poi_changes(&mut self) {
+        let poi = match self.poi.as_mut() {
+            Some(poi) => poi,
+            None => return,
+        };
+
+        for mut chunk in self.chunks.iter_mut() {
+            if chunk.poi_changes.is_empty() {
+                continue;
+            }
+
+            let coords = *chunk.key();
+            let data_version = chunk
+                .nbt
+                .int("DataVersion")
+                .unwrap_or(Region::MIN_DATA_VERSION);
+            let changes = std::mem::take(&mut chunk.poi_changes);
+            poi.apply_changes(coords, data_version, changes);
+        }
+    }
+}
+
+/// Returns the point of interest type of a block, if it has one.
+pub(crate) fn poi_of_block(block: &Block) -> Option<PoiType> {
+    poi_type(&block.name.to_str(), || {
+        let part: NbtString = "part".into();
+        block
+            .properties
+            .as_ref()
+            .and_then(|p| p.get(&part))
+            .is_some_and(|part| *part == "head")
+    })
+}
+
+/// Returns the point of interest type of a block palette entry, if it has one.
+pub(crate) fn poi_of_compound(block: &NbtCompound) -> Option<PoiType> {
+    let name = block.string("Name")?;
+    poi_type(&name.to_str(), || {
+        block
+            .compound("Properties")
+            .and_then(|p| p.string("part"))
+            .is_some_and(|part| part.to_str() == "head")
+    })
+}
+
+/// The point of interest table, mapping blocks to the type Minecraft registers them as.
+///
+/// Beds only count their head half, which is checked lazily via `is_bed_head`.
+fn poi_type(name: &str, is_bed_head: impl FnOnce() -> bool) -> Option<PoiType> {
+    let id = name.strip_prefix("minecraft:").unwrap_or(name);
+    let (kind, max_tickets) = match id {
+        "blast_furnace" => ("minecraft:armorer", 1),
+        "smoker" => ("minecraft:butcher", 1),
+        "cartography_table" => ("minecraft:cartographer", 1),
+        "brewing_stand" => ("minecraft:cleric", 1),
+        "composter" => ("minecraft:farmer", 1),
+        "barrel" => ("minecraft:fisherman", 1),
+        "fletching_table" => ("minecraft:fletcher", 1),
+        "cauldron" | "water_cauldron" | "lava_cauldron" | "powder_snow_cauldron" => {
+            ("minecraft:leatherworker", 1)
+        }
+        "lectern" => ("minecraft:librarian", 1),
+        "stonecutter" => ("minecraft:mason", 1),
+        "loom" => ("minecraft:shepherd", 1),
+        "smithing_table" => ("minecraft:toolsmith", 1),
+        "grindstone" => ("minecraft:weaponsmith", 1),
+        "bell" => ("minecraft:meeting", 32),
+        "beehive" => ("minecraft:beehive", 0),
+        "bee_nest" => ("minecraft:bee_nest", 0),
+        "nether_portal" => ("minecraft:nether_portal", 0),
+        "lodestone" => ("minecraft:lodestone", 0),
+        "lightning_rod" => ("minecraft:lightning_rod", 0),
+        id if id.ends_with("_bed") && !id.contains(':') && is_bed_head() => ("minecraft:home", 1),
+        _ => return None,
+    };
+
+    Some(PoiType { kind, max_tickets })
+}
+
+impl Debug for PoiRegion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "PoiRegion({}, {})\n  > chunks: {}\n  > records: {}",
+            self.region_coords.0,
+            self.region_coords.1,
+            self.chunks.len(),
+            self.records().count()
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Config;
+
+    fn poi_config() -> Config {
+        Config {
+            update_poi: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn poi_types() {
+        let kind = |block: Block| poi_of_block(&block).map(|p| p.kind);
+
+        assert_eq!(
+            kind(Block::new("minecraft:bell")),
+            Some("minecraft:meeting")
+        );
+        assert_eq!(kind(Block::new("composter")), Some("minecraft:farmer"));
+        assert_eq!(
+            kind(Block::new_with_props("red_bed", [("part", "head")])),
+            Some("minecraft:home")
+        );
+        assert_eq!(
+            kind(Block::new_with_props("red_bed", [("part", "foot")])),
+            None
+        );
+        assert_eq!(kind(Block::new("custom:bell")), None);
+        assert_eq!(kind(Block::new("minecraft:stone")), None);
+
+        let bed = Block::new_with_props("minecraft:blue_bed", [("part", "head")])
+            .to_compound()
+            .unwrap();
+        assert_eq!(
+            poi_of_compound(&bed).map(|p| p.kind),
+            Some("minecraft:home")
+        );
+    }
+
+    #[test]
+    fn set_and_remove_records() -> Result<()> {
+        let mut region = PoiRegion::empty((-1, 2));
+        region.set_record(PoiRecord::new("minecraft:home", (-500, 64, 1030), 1))?;
+        region.set_record(PoiRecord::new("minecraft:meeting", (-500, 64, 1030), 32))?;
+        assert!(
+            region
+                .set_record(PoiRecord::new("minecraft:home", (5, 64, 5), 1))
+                .is_err()
+        );
+
+        assert_eq!(region.records().count(), 1);
+        let record = region.get_record((12, 64, 6)).unwrap();
+        assert_eq!(record.kind, "minecraft:meeting");
+        assert_eq!(record.free_tickets, 32);
+
+        assert!(region.remove_record((12, 64, 6)).is_some());
+        assert!(region.get_record((12, 64, 6)).is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn write_and_read() -> Result<()> {
+        let mut region = PoiRegion::empty((0, 0));
+        region.set_record(PoiRecord::new("minecraft:farmer", (20, -40, 30), 1))?;
+
+        let mut buf = vec![];
+        region.write(&mut buf)?;
+
+        let region = PoiRegion::from_region(&mut buf.as_slice(), (0, 0))?;
+        let chunk = region.get_chunk(1, 1).unwrap();
+        assert!(!chunk.is_modified());
+        assert!(chunk.sections().get(&-3).unwrap().valid);
+        assert_eq!(
+            region.get_record((20, -40, 30)).unwrap().kind,
+            "minecraft:farmer"
+        );
+
+        let nbt = chunk.to_nbt();
+        let section = nbt.compound("Sections").unwrap().compound("-3").unwrap();
+        assert_eq!(section.byte("Valid"), Some(1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn set_block_updates_poi() -> Result<()> {
+        let mut region = Region::default();
+        region.set_config(poi_config())?;
+
+        region.set_block((5, 70, 5), "minecraft:bell")?;
+        assert!(region.write_blocks().is_err());
+
+        region.set_poi_region(PoiRegion::empty((0, 0)))?;
+        region.write_blocks()?;
+        let record = region
+            .get_poi_region()
+            .unwrap()
+            .get_record((5, 70, 5))
+            .unwrap();
+        assert_eq!(record.kind, "minecraft:meeting");
+        assert_eq!(record.free_tickets, 32);
+
+        region.set_block((5, 70, 5), "minecraft:air")?;
+        region.write_blocks()?;
+        assert!(
+            region
+                .get_poi_region()
+                .unwrap()
+                .get_record((5, 70, 5))
+                .is_none()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn set_section_updates_poi() -> Result<()> {
+        let mut region = Region::default();
+        region.set_config(poi_config())?;
+        region.set_poi_region(PoiRegion::empty((0, 0)))?;
+
+        region.set_section((1, 0), 2, "minecraft:lodestone")?;
+        let poi = region.get_poi_region().unwrap();
+        assert_eq!(poi.records().count(), 4096);
+        assert_eq!(
+            poi.get_record((19, 37, 4)).unwrap().kind,
+            "minecraft:lodestone"
+        );
+
+        region.set_section((1, 0), 2, "minecraft:stone")?;
+        assert_eq!(region.get_poi_region().unwrap().records().count(), 0);
+
+        Ok(())
+    }
+}
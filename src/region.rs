@@ -8,29 +8,42 @@ use crate::{
     config::Config,
     error::{Error, Result},
     nbt::Block,
+    poi::PoiRegion,
+    region_file::{self, Compression, ExternalChunks, RawChunk},
 };
 use ahash::AHashMap;
 use dashmap::{
     DashMap,
     mapref::one::{Ref, RefMut},
 };
-use mca::{CompressionType, RegionIter, RegionReader, RegionWriter};
 use simdnbt::owned::{BaseNbt, Nbt, NbtCompound, NbtList, NbtTag};
 use std::{
     fmt::Debug,
     io::{Cursor, Read, Write},
     ops::{Deref, Range},
+    path::Path,
+    sync::Arc,
 };
 
 /// An in-memory region to read and write blocks to the chunks within.  
 #[derive(Clone)]
 pub struct Region {
-    /// The chunks within the Region, mapped to their coordinates
+    /// The chunks within the Region, mapped to their coordinates.  
+    ///
+    /// If you modify [`ChunkData::nbt`] through here, call [`ChunkData::mark_modified`] so it gets written.  
     pub chunks: DashMap<(u8, u8), ChunkData>,
     /// Config on how it should handle certain scenarios
     pub(crate) config: Config,
     /// Coordinates for this specific region
     pub region_coords: (i32, i32),
+    /// The raw region file that chunks are lazily loaded from.  
+    ///
+    /// Only set for regions created via [`Region::from_region_lazy`] or [`Region::from_region_chunks`].  
+    pub(crate) source: Option<Arc<Vec<u8>>>,
+    /// Where oversized chunks are read from, only set when the region was opened from a path.  
+    pub(crate) external: Option<ExternalChunks>,
+    /// The points of interest that [`Config::update_poi`] writes changes to.  
+    pub(crate) poi: Option<PoiRegion>,
 }
 
 /// Just a [`Block`] but with a set of coordinates attached to them.  
@@ -76,10 +89,13 @@ impl Region {
     /// #### Why is `world_height` private in [`Config`] and only mutated through [`Region`] ?
     /// Well, when a region is first constructed it defaults an internal bitset to a certain size.  
     /// for performance reasons, and if you update world_height, we also need to re-init that bitset.
-    /// *(this function also clears all internal buffers related to biomes)*.
+    /// *(this function also clears all internal buffers related to blocks and biomes)*.
     /// and a config can only be mutated on a region after the consumer has gotten it.  
     /// So when you get a region, it always defaults to Minecrafts vanilla range of world_height.  
     ///
+    /// If you're working with other dimensions, [`World::open_dimension`](crate::World::open_dimension)
+    /// picks the right world height for you.  
+    ///
     /// ## Example
     /// ```
     /// # use silverfish::Region;
@@ -89,15 +105,15 @@ impl Region {
     /// ```
     pub fn set_world_height(&mut self, range: Range<isize>) -> Result<()> {
         // clear all the chunks buffers
+        // only the chunks that exists, otherwise missing chunks would either error or get created
         let world_height_count = range.clone().count();
-        for x in 0..32 {
-            for z in 0..32 {
-                let mut chunk = self.get_chunk_mut(x, z)?;
-                chunk.pending_blocks = AHashMap::new();
-                chunk.pending_biomes = AHashMap::new();
-                chunk.seen_blocks = ChunkData::block_bitset(world_height_count);
-                chunk.seen_biomes = ChunkData::biome_bitset(world_height_count);
-            }
+        for mut chunk in self.chunks.iter_mut() {
+            chunk.world_height = range.clone();
+            chunk.pending_blocks = AHashMap::new();
+            chunk.pending_biomes = AHashMap::new();
+            chunk.pending_block_entities = Vec::new();
+            chunk.seen_blocks = ChunkData::block_bitset(world_height_count);
+            chunk.seen_biomes = ChunkData::biome_bitset(world_height_count);
         }
 
         self.config.world_height = range;
@@ -118,6 +134,9 @@ impl Region {
             chunks: DashMap::new(),
             region_coords,
             config,
+            source: None,
+            external: None,
+            poi: None,
         }
     }
 
@@ -150,6 +169,9 @@ impl Region {
             chunks,
             region_coords,
             config,
+            source: None,
+            external: None,
+            poi: None,
         }
     }
 
@@ -163,28 +185,130 @@ impl Region {
     /// # Ok::<(), silverfish::Error>(())
     /// ```
     pub fn from_region<R: Read>(reader: &mut R, region_coords: (i32, i32)) -> Result<Self> {
+        let mut region = Self::from_region_lazy(reader, region_coords)?;
+        region.load_all_chunks()?;
+
+        Ok(region)
+    }
+
+    /// Creates a [`Region`] from an already existing region, but only reads the chunks in `chunks`.  
+    ///
+    /// Any other chunk in the region file is loaded the first time it's accessed,
+    /// just like with [`Region::from_region_lazy`].  
+    ///
+    /// ## Example
+    /// ```
+    /// # use silverfish::Region;
+    /// # use std::fs::File;
+    /// let spawn = [(0, 0), (0, 1), (1, 0), (1, 1)];
+    /// let region = Region::from_region_chunks(&mut File::open("tests/full_region.mca")?, (0, 0), &spawn)?;
+    /// assert_eq!(region.loaded_chunks(), 4);
+    /// # Ok::<(), silverfish::Error>(())
+    /// ```
+    pub fn from_region_chunks<R: Read>(
+        reader: &mut R,
+        region_coords: (i32, i32),
+        chunks: &[(u8, u8)],
+    ) -> Result<Self> {
+        let region = Self::from_region_lazy(reader, region_coords)?;
+        for (x, z) in chunks {
+            region.load_chunk(*x, *z)?;
+        }
+
+        Ok(region)
+    }
+
+    /// Creates a [`Region`] from an already existing region without decompressing any chunks.  
+    ///
+    /// Each chunk is read from the region file the first time it's accessed,
+    /// via [`Region::get_chunk`], [`Region::get_chunk_mut`] or any function that uses them.  
+    /// Chunks that were never accessed are still written back as is on [`Region::write`].  
+    ///
+    /// ## Example
+    /// ```
+    /// # use silverfish::Region;
+    /// # use std::fs::File;
+    /// let region = Region::from_region_lazy(&mut File::open("tests/full_region.mca")?, (0, 0))?;
+    /// let block = region.get_block((5, 97, 385))?;
+    /// assert_eq!(region.loaded_chunks(), 1);
+    /// # Ok::<(), silverfish::Error>(())
+    /// ```
+    pub fn from_region_lazy<R: Read>(reader: &mut R, region_coords: (i32, i32)) -> Result<Self> {
         let mut bytes = Vec::with_capacity(4_194_304); // 4 MB, just an average start on the vec to skip a few common re-allocations
         reader.read_to_end(&mut bytes)?;
-        let region_reader = RegionReader::new(&bytes)?;
 
-        let mut chunks = AHashMap::new();
-        for (i, chunk) in region_reader.iter().enumerate() {
-            let chunk = chunk?;
-            let chunk = match chunk {
-                Some(c) => c.decompress()?,
-                None => continue,
-            };
+        Self::from_region_bytes(bytes, region_coords, None)
+    }
 
-            let chunk_nbt = match simdnbt::owned::read(&mut Cursor::new(&chunk))? {
-                Nbt::Some(nbt) => nbt.as_compound(),
-                Nbt::None => return Err(Error::InvalidNbtType("base_nbt")),
-            };
-            let (x, z) = RegionIter::get_chunk_coordinate(i);
+    /// Creates a lazy [`Region`] from the bytes of a region file.  
+    fn from_region_bytes(
+        bytes: Vec<u8>,
+        region_coords: (i32, i32),
+        external: Option<ExternalChunks>,
+    ) -> Result<Self> {
+        // makes sure the header is valid before any chunk is requested
+        region_file::validate_header(&bytes)?;
 
-            chunks.insert((x as u8, z as u8), chunk_nbt);
-        }
+        let mut region = Self::from_nbt(AHashMap::new(), region_coords);
+        region.source = Some(Arc::new(bytes));
+        region.external = external;
+
+        Ok(region)
+    }
+
+    /// Opens a region file, working out the region coordinates from it's `r.<x>.<z>.mca` file name.  
+    ///
+    /// Oversized chunks stored in `c.<x>.<z>.mcc` files next to the region file are read as well.  
+    ///
+    /// ## Example
+    /// ```no_run
+    /// # use silverfish::Region;
+    /// let region = Region::open("saves/New World/region/r.-2.9.mca")?;
+    /// assert_eq!(region.region_coords, (-2, 9));
+    /// # Ok::<(), silverfish::Error>(())
+    /// ```
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let mut region = Self::open_lazy(path)?;
+        region.load_all_chunks()?;
 
-        Ok(Self::from_nbt(chunks, region_coords))
+        Ok(region)
+    }
+
+    /// Opens a region file like [`Region::open`], but lazily loads each chunk when it's first accessed.  
+    ///
+    /// See [`Region::from_region_lazy`] for more info.  
+    pub fn open_lazy<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let (region_coords, bytes, external) = region_file::open_region(path.as_ref())?;
+        Self::from_region_bytes(bytes, region_coords, Some(external))
+    }
+
+    /// Saves the region to a region file.  
+    ///
+    /// The file name must be `r.<x>.<z>.mca` and match [`Region::region_coords`].  
+    ///
+    /// The region is first written to a temporary file next to it, which is then renamed over the real one.  
+    /// So a crash mid-write never leaves a half-written region file behind.  
+    ///
+    /// Chunks too big to fit in a region file *(over 1 MiB compressed)* are written to `c.<x>.<z>.mcc` files next to it.  
+    /// These are renamed into place before the region file, and old ones are removed last.  
+    ///
+    /// ## Example
+    /// ```no_run
+    /// # use silverfish::Region;
+    /// let mut region = Region::open("saves/New World/region/r.0.0.mca")?;
+    /// region.set_block((5, 97, 385), "dirt")?;
+    /// region.write_blocks()?;
+    /// region.save("saves/New World/region/r.0.0.mca")?;
+    /// # Ok::<(), silverfish::Error>(())
+    /// ```
+    pub fn save<P: AsRef<Path>>(self, path: P) -> Result<()> {
+        let (region_coords, external) = (self.region_coords, self.external.clone());
+        region_file::save_region(
+            path.as_ref(),
+            region_coords,
+            self.into_raw_chunks()?,
+            external.as_ref(),
+        )
     }
 
     /// Writes the region to the specified writer.  
@@ -192,6 +316,13 @@ impl Region {
     /// **Note:** If you haven't called [`Region::write_blocks`] this will most likely  
     /// just return whatever input you gave it initially
     ///
+    /// Chunks that haven't been modified since they were read are copied over as is,  
+    /// only modified chunks gets serialized & compressed again.  
+    /// Each chunk keeps the [`Compression`] it was read with, unless [`Config::compression`] is set.  
+    /// Untouched chunks also keeps their timestamp, while modified chunks gets the current time.  
+    ///
+    /// Chunks too big to fit in a region file can't be written to a writer, use [`Region::save`] for those.  
+    ///
     /// ## Example
     /// ```
     /// # let mut region = silverfish::Region::default();
@@ -200,18 +331,55 @@ impl Region {
     /// # Ok::<(), silverfish::Error>(())
     /// ```
     pub fn write<W: Write>(self, writer: &mut W) -> Result<()> {
-        let mut region_writer = RegionWriter::new();
+        let chunks = self.into_raw_chunks()?;
+        region_file::write_region(writer, chunks)
+    }
 
-        for ((x, z), chunk_data) in self.chunks {
-            let mut raw_nbt = vec![];
-            let wrapped = Nbt::Some(BaseNbt::new("", chunk_data.nbt));
-            wrapped.write(&mut raw_nbt);
-            region_writer.push_chunk_with_compression(&raw_nbt, (x, z), CompressionType::Zlib)?;
+    /// Turns every chunk into it's compressed form, ready to be written to a region file.  
+    fn into_raw_chunks(self) -> Result<Vec<((u8, u8), RawChunk)>> {
+        let mut chunks = Vec::with_capacity(self.chunks.len());
+        let forced_compression = self.config.compression;
+        let now = region_file::now_timestamp();
+
+        // chunks that were never loaded from a lazy region are copied straight from the source
+        if let Some(source) = &self.source {
+            for x in 0..mca::REGION_SIZE as u8 {
+                for z in 0..mca::REGION_SIZE as u8 {
+                    if self.chunks.contains_key(&(x, z)) {
+                        continue;
+                    }
+                    if let Some(mut raw) =
+                        region_file::read_chunk(source, x, z, self.external.as_ref())?
+                    {
+                        if let Some(compression) = forced_compression {
+                            raw = raw.recompress(compression)?;
+                        }
+                        chunks.push(((x, z), raw));
+                    }
+                }
+            }
         }
 
-        region_writer.write(writer)?;
+        for ((x, z), chunk_data) in self.chunks {
+            let raw = match chunk_data.raw {
+                Some(raw) => match forced_compression {
+                    Some(compression) => raw.recompress(compression)?,
+                    None => raw,
+                },
+                None => {
+                    let compression = forced_compression
+                        .or(chunk_data.compression)
+                        .unwrap_or_default();
+                    let mut raw_nbt = vec![];
+                    let wrapped = Nbt::Some(BaseNbt::new("", chunk_data.nbt));
+                    wrapped.write(&mut raw_nbt);
+                    RawChunk::compress(&raw_nbt, compression, now)?
+                }
+            };
+            chunks.push(((x, z), raw));
+        }
 
-        Ok(())
+        Ok(chunks)
     }
 
     /// Returns the chunk nbt data found at the given chunk coordinates.  
@@ -228,6 +396,7 @@ impl Region {
         if x >= mca::REGION_SIZE as u8 || z >= mca::REGION_SIZE as u8 {
             return Err(Error::ChunkOutOfRegionBounds(x, z));
         }
+        self.load_chunk(x, z)?;
 
         Ok(self.chunks.get(&(x, z)))
     }
@@ -246,6 +415,8 @@ impl Region {
     ///
     /// Do note that these chunk coordinates are local to within the region itself.
     ///
+    /// The chunk is marked as modified, so any change to [`ChunkData::nbt`] gets written.
+    ///
     /// ## Example
     /// ```
     /// # let mut region = silverfish::Region::default();
@@ -254,9 +425,24 @@ impl Region {
     /// # Ok::<(), silverfish::Error>(())
     /// ```
     pub fn get_chunk_mut<'a>(&'a self, x: u8, z: u8) -> Result<RefMut<'a, (u8, u8), ChunkData>> {
+        let mut chunk = self.get_chunk_mut_unmarked(x, z)?;
+        chunk.mark_modified();
+
+        Ok(chunk)
+    }
+
+    /// Returns a mutable reference to a chunk entry like [`Region::get_chunk_mut`], without marking it as modified.  
+    ///
+    /// For changes that only go to the pending buffers, the chunk is marked once they're written to the NBT.  
+    pub(crate) fn get_chunk_mut_unmarked<'a>(
+        &'a self,
+        x: u8,
+        z: u8,
+    ) -> Result<RefMut<'a, (u8, u8), ChunkData>> {
         if x >= mca::REGION_SIZE as u8 || z >= mca::REGION_SIZE as u8 {
             return Err(Error::ChunkOutOfRegionBounds(x, z));
         }
+        self.load_chunk(x, z)?;
 
         match self.chunks.get_mut(&(x, z)) {
             Some(ch) => return Ok(ch),
@@ -278,6 +464,55 @@ impl Region {
         };
     }
 
+    /// Reads a chunk from the lazy region file source if it hasn't been loaded yet.  
+    ///
+    /// Does nothing if the region wasn't created lazily or the chunk doesn't exist in the region file.  
+    /// Note that this inserts into [`Region::chunks`] so it can't be called while holding a reference to a chunk.  
+    pub(crate) fn load_chunk(&self, x: u8, z: u8) -> Result<()> {
+        let source = match &self.source {
+            Some(source) => source,
+            None => return Ok(()),
+        };
+        if self.chunks.contains_key(&(x, z)) {
+            return Ok(());
+        }
+
+        let raw = match region_file::read_chunk(source, x, z, self.external.as_ref())? {
+            Some(raw) => raw,
+            None => return Ok(()),
+        };
+        let chunk_data = ChunkData::from_raw(raw, self.config.world_height.clone())?;
+
+        // another thread might've loaded it in the meantime, and we don't want to override their changes
+        self.chunks.entry((x, z)).or_insert(chunk_data);
+
+        Ok(())
+    }
+
+    /// Reads every chunk that hasn't been loaded yet, after this the region is no longer lazy.  
+    fn load_all_chunks(&mut self) -> Result<()> {
+        for x in 0..mca::REGION_SIZE as u8 {
+            for z in 0..mca::REGION_SIZE as u8 {
+                self.load_chunk(x, z)?;
+            }
+        }
+        self.source = None;
+
+        Ok(())
+    }
+
+    /// Returns if the chunk has been loaded into memory.  
+    ///
+    /// Always `true` for chunks that exist in a region that wasn't created lazily.  
+    pub fn is_chunk_loaded(&self, x: u8, z: u8) -> bool {
+        self.chunks.contains_key(&(x, z))
+    }
+
+    /// Returns how many chunks that are currently loaded into memory.  
+    pub fn loaded_chunks(&self) -> usize {
+        self.chunks.len()
+    }
+
     /// Returns if all chunks inside the region has been generated and is [`Region::REQUIRED_STATUS`]
     pub fn is_region_generated(&self) -> Result<bool> {
         for x in 0..mca::REGION_SIZE as u8 {
@@ -415,6 +650,14 @@ pub fn get_empty_chunk(
     chunk
 }
 
+/// Parses decompressed chunk bytes into it's root compound.  
+pub(crate) fn read_chunk_nbt(chunk: &[u8]) -> Result<NbtCompound> {
+    match simdnbt::owned::read(&mut Cursor::new(chunk))? {
+        Nbt::Some(nbt) => Ok(nbt.as_compound()),
+        Nbt::None => Err(Error::InvalidNbtType("base_nbt")),
+    }
+}
+
 /// Converts a piece of global world coordinates to coordinates within it's region.  
 ///
 /// ## Example
@@ -433,6 +676,49 @@ pub fn to_region_local(coords: (i32, i32, i32)) -> Coords {
         .into()
 }
 
+/// Parses the region coordinates from a region file path, `r.<x>.<z>.mca`.  
+///
+/// ## Example
+/// ```
+/// # use silverfish::region_coords_from_path;
+/// let coords = region_coords_from_path("world/region/r.-2.9.mca")?;
+/// assert_eq!(coords, (-2, 9));
+/// # Ok::<(), silverfish::Error>(())
+/// ```
+pub fn region_coords_from_path<P: AsRef<Path>>(path: P) -> Result<(i32, i32)> {
+    let path = path.as_ref();
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let invalid = || Error::InvalidRegionFileName(name.clone());
+
+    let parts = name.split('.').collect::<Vec<&str>>();
+    match parts.as_slice() {
+        ["r", x, z, "mca"] => Ok((
+            x.parse::<i32>().map_err(|_| invalid())?,
+            z.parse::<i32>().map_err(|_| invalid())?,
+        )),
+        _ => Err(invalid()),
+    }
+}
+
+/// Returns the coordinates of the region that a piece of global world coordinates is within.  
+///
+/// ## Example
+/// ```
+/// # use silverfish::to_region_coords;
+/// let coords = (-841, -17, 4821);
+/// let region_coords = to_region_coords(coords);
+/// assert_eq!(region_coords, (-2, 9))
+/// ```
+pub fn to_region_coords(coords: (i32, i32, i32)) -> (i32, i32) {
+    (
+        coords.0.div_euclid(BLOCKS_PER_REGION as i32),
+        coords.2.div_euclid(BLOCKS_PER_REGION as i32),
+    )
+}
+
 /// Checks the data_version and status of the chunk if it's valid to operate on
 pub(crate) fn is_valid_chunk(chunk: &NbtCompound, coordinate: (u8, u8)) -> Result<()> {
     let status = chunk
@@ -512,6 +798,49 @@ mod test {
         assert_eq!((339, 85, 31), local);
     }
 
+    #[test]
+    fn region_coordinates() {
+        assert_eq!(to_region_coords((52, -81, 381)), (0, 0));
+        assert_eq!(to_region_coords((851, 85, -481)), (1, -1));
+        assert_eq!(to_region_coords((-512, 0, -513)), (-1, -2));
+    }
+
+    #[test]
+    fn region_file_names() -> Result<()> {
+        assert_eq!(region_coords_from_path("r.0.0.mca")?, (0, 0));
+        assert_eq!(region_coords_from_path("world/region/r.-14.3.mca")?, (-14, 3));
+        assert!(region_coords_from_path("r.0.mca").is_err());
+        assert!(region_coords_from_path("r.a.0.mca").is_err());
+        assert!(region_coords_from_path("c.0.0.mcc").is_err());
+        assert!(region_coords_from_path("r.0.0.mca.tmp").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn open_and_save() -> Result<()> {
+        let folder = std::env::temp_dir().join("silverfish_region_open_and_save");
+        let _ = std::fs::remove_dir_all(&folder);
+        std::fs::create_dir_all(&folder)?;
+        let path = folder.join("r.-1.2.mca");
+
+        let mut region = Region::empty((-1, 2));
+        region.set_block((4, 12, 500), "minecraft:obsidian")?;
+        region.write_blocks()?;
+        region.save(&path)?;
+        assert!(!folder.join("r.-1.2.mca.tmp").exists());
+
+        let region = Region::open(&path)?;
+        assert_eq!(region.region_coords, (-1, 2));
+        assert_eq!(
+            region.get_block((4, 12, 500))?,
+            Block::new("minecraft:obsidian")
+        );
+
+        assert!(region.save(folder.join("r.0.0.mca")).is_err());
+
+        Ok(())
+    }
+
     #[test]
     fn empty_chunk() -> Result<()> {
         let chunk = get_empty_chunk((15, 9), (2, -5), Config::DEFAULT_WORLD_HEIGHT);
@@ -637,6 +966,256 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn set_world_height() -> Result<()> {
+        let mut region = Region::from_nbt(AHashMap::new(), (0, 0));
+        region.chunks.insert(
+            (3, 3),
+            ChunkData::new(get_empty_chunk((3, 3), (0, 0), 0..256), 0..256),
+        );
+
+        region.set_world_height(0..256)?;
+        assert_eq!(region.chunks.len(), 1);
+        assert_eq!(region.get_chunk(3, 3)?.unwrap().world_height, 0..256);
+
+        Ok(())
+    }
+
+    /// Writes a region with blocks placed in a few chunks and returns the raw region file.  
+    fn region_with_chunks(chunks: &[(u8, u8)]) -> Result<Vec<u8>> {
+        let mut region = Region::empty((0, 0));
+        for (x, z) in chunks {
+            let coords = (*x as u32 * 16 + 3, 70, *z as u32 * 16 + 9);
+            region.set_block(coords, "minecraft:emerald_block")?;
+        }
+        region.write_blocks()?;
+
+        let mut buf = vec![];
+        region.write(&mut buf)?;
+        Ok(buf)
+    }
+
+    #[test]
+    fn from_region_chunks() -> Result<()> {
+        let buf = region_with_chunks(&[(0, 0), (4, 7), (31, 31)])?;
+        let region = Region::from_region_chunks(&mut buf.as_slice(), (0, 0), &[(4, 7)])?;
+
+        assert_eq!(region.loaded_chunks(), 1);
+        assert!(region.is_chunk_loaded(4, 7));
+        assert!(!region.is_chunk_loaded(0, 0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn lazy_region() -> Result<()> {
+        let buf = region_with_chunks(&[(0, 0), (4, 7), (31, 31)])?;
+        let region = Region::from_region_lazy(&mut buf.as_slice(), (0, 0))?;
+        assert_eq!(region.loaded_chunks(), 0);
+
+        assert_eq!(
+            region.get_block((4 * 16 + 3, 70, 7 * 16 + 9))?,
+            Block::new("minecraft:emerald_block")
+        );
+        assert_eq!(region.loaded_chunks(), 1);
+
+        // chunks that doesn't exist in the file are still missing
+        assert!(region.get_chunk(10, 10)?.is_none());
+        assert_eq!(region.loaded_chunks(), 1);
+
+        region.get_chunk_mut(31, 31)?.set_block((0, 0, 0), "minecraft:stone")?;
+        assert_eq!(region.loaded_chunks(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn lazy_region_writes_untouched_chunks() -> Result<()> {
+        let buf = region_with_chunks(&[(0, 0), (4, 7), (31, 31)])?;
+        let region = Region::from_region_lazy(&mut buf.as_slice(), (0, 0))?;
+        let _ = region.get_chunk(0, 0)?;
+
+        let mut written = vec![];
+        region.write(&mut written)?;
+
+        let region = Region::from_region(&mut written.as_slice(), (0, 0))?;
+        assert_eq!(region.chunks.len(), 3);
+        assert_eq!(
+            region.get_block((31 * 16 + 3, 70, 31 * 16 + 9))?,
+            Block::new("minecraft:emerald_block")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn untouched_chunks_are_passed_through() -> Result<()> {
+        let mut original = Region::empty((0, 0));
+        let _ = original.get_chunk_mut(1, 1)?;
+        let _ = original.get_chunk_mut(2, 2)?;
+        let mut original_nbt = AHashMap::new();
+        for chunk in original.chunks.iter() {
+            let mut raw_nbt = vec![];
+            Nbt::Some(BaseNbt::new("", chunk.nbt.clone())).write(&mut raw_nbt);
+            original_nbt.insert(*chunk.key(), raw_nbt);
+        }
+
+        // gzip so we can tell if the chunk was re-encoded or not
+        let mut buf = vec![];
+        region_file::write_region(
+            &mut buf,
+            original_nbt
+                .iter()
+                .map(|(c, nbt)| Ok((*c, RawChunk::compress(nbt, Compression::GZip, 1234)?)))
+                .collect::<Result<Vec<_>>>()?,
+        )?;
+
+        let mut region = Region::from_region(&mut buf.as_slice(), (0, 0))?;
+        region.set_block((35, 70, 35), "minecraft:stone")?;
+        region.write_blocks()?;
+        // setting blocks borrows chunks without marking them until the blocks are written
+        let _ = region.get_chunk_mut_unmarked(1, 1)?;
+        assert!(!region.get_chunk(1, 1)?.unwrap().is_modified());
+        assert!(region.get_chunk(2, 2)?.unwrap().is_modified());
+
+        let mut written = vec![];
+        region.write(&mut written)?;
+
+        assert_eq!(
+            region_file::read_chunk(&buf, 1, 1, None)?,
+            region_file::read_chunk(&written, 1, 1, None)?
+        );
+        let modified = region_file::read_chunk(&written, 2, 2, None)?.unwrap();
+        assert_ne!(region_file::read_chunk(&buf, 2, 2, None)?.unwrap(), modified);
+        // modified chunks keeps their original compression
+        assert_eq!(modified.compression, Compression::GZip);
+
+        Ok(())
+    }
+
+    #[test]
+    fn get_chunk_mut_marks_modified() -> Result<()> {
+        let buf = region_with_chunks(&[(0, 0), (3, 3)])?;
+        let region = Region::from_region(&mut buf.as_slice(), (0, 0))?;
+        region
+            .get_chunk_mut(3, 3)?
+            .nbt
+            .insert("silverfish", NbtTag::Int(7));
+        assert!(region.get_chunk(3, 3)?.unwrap().is_modified());
+        assert!(!region.get_chunk(0, 0)?.unwrap().is_modified());
+
+        let mut written = vec![];
+        region.write(&mut written)?;
+        let region = Region::from_region(&mut written.as_slice(), (0, 0))?;
+        assert_eq!(
+            region.get_chunk(3, 3)?.unwrap().nbt.int("silverfish"),
+            Some(7)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn external_chunks() -> Result<()> {
+        let folder = std::env::temp_dir().join("silverfish_region_external_chunks");
+        let _ = std::fs::remove_dir_all(&folder);
+        std::fs::create_dir_all(&folder)?;
+        let path = folder.join("r.1.0.mca");
+
+        let mut region = Region::empty((1, 0));
+        region.set_config(Config {
+            create_chunk_if_missing: true,
+            compression: Some(Compression::Uncompressed),
+            ..Default::default()
+        })?;
+        // uncompressed 2 MiB of padding can't fit within the region file
+        region
+            .get_chunk_mut(5, 6)?
+            .nbt
+            .insert("Padding", NbtTag::ByteArray(vec![1; 2_097_152]));
+        region.set_block((0, 0, 0), "minecraft:stone")?;
+        region.write_blocks()?;
+
+        assert!(region.clone().write(&mut vec![]).is_err());
+        region.save(&path)?;
+        assert!(folder.join("c.37.6.mcc").exists());
+
+        let region = Region::open(&path)?;
+        assert_eq!(region.loaded_chunks(), 2);
+        assert!(region.get_chunk(5, 6)?.unwrap().nbt.byte_array("Padding").is_some());
+
+        // reading it without knowing where the region file is isn't possible
+        let bytes = std::fs::read(&path)?;
+        assert!(Region::from_region(&mut bytes.as_slice(), (1, 0)).is_err());
+
+        // the external file is removed once the chunk fits again
+        {
+            region.get_chunk_mut(5, 6)?.nbt.remove("Padding");
+        }
+        region.save(&path)?;
+        assert!(!folder.join("c.37.6.mcc").exists());
+        assert!(Region::open(&path)?.get_chunk(5, 6)?.is_some());
+
+        Ok(())
+    }
+
+    #[test]
+    fn keeps_timestamps() -> Result<()> {
+        let mut original = Region::empty((0, 0));
+        let _ = original.get_chunk_mut(0, 0)?;
+        let _ = original.get_chunk_mut(0, 1)?;
+        let mut raw_chunks = vec![];
+        for chunk in original.chunks.iter() {
+            let mut raw_nbt = vec![];
+            Nbt::Some(BaseNbt::new("", chunk.nbt.clone())).write(&mut raw_nbt);
+            raw_chunks.push((*chunk.key(), RawChunk::compress(&raw_nbt, Compression::Zlib, 1000)?));
+        }
+        let mut buf = vec![];
+        region_file::write_region(&mut buf, raw_chunks)?;
+
+        let mut region = Region::from_region(&mut buf.as_slice(), (0, 0))?;
+        assert_eq!(region.get_chunk(0, 0)?.unwrap().timestamp(), 1000);
+
+        region.set_block((3, 3, 20), "minecraft:stone")?;
+        region.write_blocks()?;
+        let mut written = vec![];
+        region.write(&mut written)?;
+
+        let region = Region::from_region(&mut written.as_slice(), (0, 0))?;
+        assert_eq!(region.get_chunk(0, 0)?.unwrap().timestamp(), 1000);
+        assert!(region.get_chunk(0, 1)?.unwrap().timestamp() > 1000);
+
+        Ok(())
+    }
+
+    #[test]
+    fn forced_compression() -> Result<()> {
+        let buf = region_with_chunks(&[(0, 0), (4, 7)])?;
+        let mut region = Region::from_region_lazy(&mut buf.as_slice(), (0, 0))?;
+        let _ = region.get_chunk(0, 0)?;
+        region.set_config(Config {
+            compression: Some(Compression::LZ4),
+            ..Default::default()
+        })?;
+
+        let mut written = vec![];
+        region.write(&mut written)?;
+
+        for (x, z) in [(0, 0), (4, 7)] {
+            let raw = region_file::read_chunk(&written, x, z, None)?.unwrap();
+            assert_eq!(raw.compression, Compression::LZ4);
+        }
+
+        let region = Region::from_region(&mut written.as_slice(), (0, 0))?;
+        assert_eq!(region.get_chunk(4, 7)?.unwrap().compression(), Some(Compression::LZ4));
+        assert_eq!(
+            region.get_block((4 * 16 + 3, 70, 7 * 16 + 9))?,
+            Block::new("minecraft:emerald_block")
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn fully_generated() -> Result<()> {
         let region = Region::default();
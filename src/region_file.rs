@@ -0,0 +1,649 @@
+//! `region_file` reads and writes the raw `.mca` region file format on top of [`mca`].
+//!
+//! Chunks are kept in their compressed form, so chunks that never got modified
+//! can be written back exactly as they were read.
+//! Timestamps & the external flag are handled here, since [`mca`] doesn't keep track of them.
+//!
+//! Chunks too big to fit in the region file are stored in `c.<x>.<z>.mcc` files next to it,
+//! which is handled through [`ExternalChunks`].
+
+use crate::{Error, Result, region::region_coords_from_path};
+use mca::{CompressionType, PendingChunk, RegionReader, RegionWriter};
+use std::{
+    fs::File,
+    io::{BufWriter, Read, Write},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// How big a single sector within a region file is.
+pub(crate) const SECTOR_SIZE: usize = 4096;
+/// The location table + the timestamp table.
+pub(crate) const HEADER_SIZE: usize = SECTOR_SIZE * 2;
+/// The most sectors a single chunk can use, since the sector count is stored in a single byte.
+pub(crate) const MAX_CHUNK_SECTORS: usize = u8::MAX as usize;
+/// Set on the compression byte if the chunk is stored in an external `.mcc` file.
+const EXTERNAL_FLAG: u8 = 0x80;
+
+/// The compression a chunk is stored with in a region file.
+///
+/// Minecraft uses [`Compression::Zlib`] by default, but servers can pick another one
+/// via `region-file-compression` in `server.properties` *(1.20.5+)*.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Compression {
+    /// GZip (RFC1952), id `1`, never used by Minecraft itself
+    GZip,
+    /// Zlib (RFC1950), id `2`, the default
+    #[default]
+    Zlib,
+    /// No compression at all, id `3`
+    Uncompressed,
+    /// LZ4 in the `LZ4BlockOutputStream` format, id `4`
+    LZ4,
+}
+
+impl Compression {
+    /// Returns the compression from it's id in the region file.
+    pub(crate) fn from_id(id: u8) -> Result<Self> {
+        match id {
+            1 => Ok(Compression::GZip),
+            2 => Ok(Compression::Zlib),
+            3 => Ok(Compression::Uncompressed),
+            4 => Ok(Compression::LZ4),
+            _ => Err(Error::UnknownCompression(id)),
+        }
+    }
+
+    /// Returns the id used for this compression in the region file.
+    pub(crate) fn id(&self) -> u8 {
+        match self {
+            Compression::GZip => 1,
+            Compression::Zlib => 2,
+            Compression::Uncompressed => 3,
+            Compression::LZ4 => 4,
+        }
+    }
+}
+
+impl From<Compression> for CompressionType {
+    fn from(compression: Compression) -> Self {
+        match compression {
+            Compression::GZip => CompressionType::GZip,
+            Compression::Zlib => CompressionType::Zlib,
+            Compression::Uncompressed => CompressionType::Uncompressed,
+            Compression::LZ4 => CompressionType::LZ4,
+        }
+    }
+}
+
+/// A chunk exactly as it's stored in the region file.
+#[derive(Clone, PartialEq, Eq)]
+pub(crate) struct RawChunk {
+    /// The compressed chunk NBT
+    pub(crate) data: Vec<u8>,
+    /// What `data` is compressed with
+    pub(crate) compression: Compression,
+    /// When the chunk was last saved, in seconds since the unix epoch
+    pub(crate) timestamp: u32,
+}
+
+impl RawChunk {
+    /// Compresses raw NBT bytes into a new [`RawChunk`].
+    pub(crate) fn compress(bytes: &[u8], compression: Compression, timestamp: u32) -> Result<Self> {
+        let data = match compression {
+            // `mca` doesn't implement GZip
+            Compression::GZip => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(bytes)?;
+                encoder.finish()?
+            }
+            _ => CompressionType::from(compression).compress(bytes)?,
+        };
+
+        Ok(Self {
+            data,
+            compression,
+            timestamp,
+        })
+    }
+
+    /// Returns the decompressed NBT bytes.
+    pub(crate) fn decompress(&self) -> Result<Vec<u8>> {
+        match self.compression {
+            // `mca` doesn't implement GZip
+            Compression::GZip => {
+                // chunks are usually a lot bigger once decompressed
+                let mut bytes = Vec::with_capacity(self.data.len() * 4);
+                flate2::read::GzDecoder::new(self.data.as_slice()).read_to_end(&mut bytes)?;
+                Ok(bytes)
+            }
+            _ => Ok(CompressionType::from(self.compression).decompress(&self.data)?),
+        }
+    }
+
+    /// Re-compresses the chunk with another compression, keeping the timestamp.  
+    ///
+    /// Does nothing if the chunk already uses that compression.
+    pub(crate) fn recompress(self, compression: Compression) -> Result<Self> {
+        if self.compression == compression {
+            return Ok(self);
+        }
+
+        RawChunk::compress(&self.decompress()?, compression, self.timestamp)
+    }
+
+    /// How many sectors this chunk takes up in the region file.
+    fn sector_count(&self) -> usize {
+        // 4 bytes for the length and 1 for the compression
+        (self.data.len() + 5).div_ceil(SECTOR_SIZE)
+    }
+}
+
+impl std::fmt::Debug for RawChunk {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "RawChunk({:?}, {} bytes, timestamp: {})",
+            self.compression,
+            self.data.len(),
+            self.timestamp
+        )
+    }
+}
+
+/// The folder that oversized chunks of a region are stored in, as `c.<x>.<z>.mcc` files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ExternalChunks {
+    folder: PathBuf,
+    region_coords: (i32, i32),
+    /// The chunks the region file on disk stores in external files
+    stored: Vec<(u8, u8)>,
+}
+
+impl ExternalChunks {
+    /// Creates a new [`ExternalChunks`] for the region file at `path`.
+    pub(crate) fn new(path: &Path, region_coords: (i32, i32)) -> Self {
+        Self {
+            folder: path.parent().unwrap_or(Path::new("")).to_path_buf(),
+            region_coords,
+            stored: vec![],
+        }
+    }
+
+    /// Creates a new [`ExternalChunks`] for the region file at `path` that has been read into `bytes`,
+    /// remembering which of it's chunks are stored in external files.
+    pub(crate) fn read(path: &Path, region_coords: (i32, i32), bytes: &[u8]) -> Result<Self> {
+        let reader = RegionReader::new(bytes)?;
+        let mut external = Self::new(path, region_coords);
+        for x in 0..mca::REGION_SIZE as u8 {
+            for z in 0..mca::REGION_SIZE as u8 {
+                match chunk_payload(&reader, x, z)? {
+                    Some((_, compression)) if compression & EXTERNAL_FLAG != 0 => {
+                        external.stored.push((x, z));
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        Ok(external)
+    }
+
+    /// Takes over which chunks are stored in external files from `other`, if it uses the same folder.
+    ///
+    /// Used when saving a region that was read from a region file,
+    /// so external files of chunks that now fits can be removed.
+    pub(crate) fn with_stored_from(mut self, other: Option<&ExternalChunks>) -> Self {
+        if let Some(other) = other.filter(|o| o.folder == self.folder) {
+            self.stored = other.stored.clone();
+        }
+
+        self
+    }
+
+    /// Returns the path to the external file of a chunk, these use global chunk coordinates.
+    pub(crate) fn path(&self, x: u8, z: u8) -> PathBuf {
+        self.folder.join(format!(
+            "c.{}.{}.mcc",
+            self.region_coords.0 * mca::REGION_SIZE as i32 + x as i32,
+            self.region_coords.1 * mca::REGION_SIZE as i32 + z as i32
+        ))
+    }
+}
+
+/// Returns the current time as a region file timestamp.
+pub(crate) fn now_timestamp() -> u32 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as u32)
+        .unwrap_or(0)
+}
+
+/// Returns the index of a chunk within the region file header.
+fn chunk_index(x: u8, z: u8) -> usize {
+    x as usize + z as usize * mca::REGION_SIZE
+}
+
+/// Reads a big endian u32 at `offset`.
+fn read_u32(bytes: &[u8], offset: usize) -> Result<u32> {
+    let slice = bytes
+        .get(offset..offset + 4)
+        .ok_or(Error::InvalidRegionFile("unexpected end of file"))?;
+    Ok(u32::from_be_bytes([slice[0], slice[1], slice[2], slice[3]]))
+}
+
+/// Makes sure the region file is atleast big enough to hold it's header.
+pub(crate) fn validate_header(bytes: &[u8]) -> Result<()> {
+    RegionReader::new(bytes)?;
+
+    Ok(())
+}
+
+/// Reads the region file at `path`, working out the region coordinates from it's `r.<x>.<z>.mca` file name.
+///
+/// Returns the region coordinates, the bytes of the file & which of it's chunks are stored in external files.
+pub(crate) fn open_region(path: &Path) -> Result<((i32, i32), Vec<u8>, ExternalChunks)> {
+    let region_coords = region_coords_from_path(path)?;
+    let bytes = std::fs::read(path)?;
+    let external = ExternalChunks::read(path, region_coords, &bytes)?;
+
+    Ok((region_coords, bytes, external))
+}
+
+/// Reads every chunk that exists in a region file.
+pub(crate) fn read_chunks(
+    bytes: &[u8],
+    external: Option<&ExternalChunks>,
+) -> Result<Vec<((u8, u8), RawChunk)>> {
+    validate_header(bytes)?;
+
+    let mut chunks = vec![];
+    for x in 0..mca::REGION_SIZE as u8 {
+        for z in 0..mca::REGION_SIZE as u8 {
+            if let Some(raw) = read_chunk(bytes, x, z, external)? {
+                chunks.push(((x, z), raw));
+            }
+        }
+    }
+
+    Ok(chunks)
+}
+
+/// Reads a single chunk from a region file.
+///
+/// Returns `None` if the chunk doesn't exist in the region file.
+/// Chunks stored in an external file can only be read if `external` is given.
+pub(crate) fn read_chunk(
+    bytes: &[u8],
+    x: u8,
+    z: u8,
+    external: Option<&ExternalChunks>,
+) -> Result<Option<RawChunk>> {
+    let reader = RegionReader::new(bytes)?;
+    let compression = match chunk_payload(&reader, x, z)? {
+        Some((_, compression)) => compression,
+        None => return Ok(None),
+    };
+    let offset = RegionReader::chunk_offset(x as usize, z as usize);
+    let timestamp = reader.get_u32_timestamp(reader.get_timestamp(offset)?);
+
+    let data = if compression & EXTERNAL_FLAG != 0 {
+        match external {
+            Some(external) => std::fs::read(external.path(x, z))?,
+            None => return Err(Error::ExternalChunk(x, z)),
+        }
+    } else {
+        // `mca` panics on unknown compressions, so it's only asked once the compression is known
+        Compression::from_id(compression)?;
+        match reader.get_chunk(x as usize, z as usize)? {
+            Some(chunk) => chunk.raw_data.to_vec(),
+            None => return Ok(None),
+        }
+    };
+
+    Ok(Some(RawChunk {
+        data,
+        compression: Compression::from_id(compression & !EXTERNAL_FLAG)?,
+        timestamp,
+    }))
+}
+
+/// Returns where a chunk's payload starts in the region file & it's compression byte.
+///
+/// Returns `None` if the chunk doesn't exist in the region file.
+/// Also makes sure the payload is within the file, since `mca` would panic on those.
+fn chunk_payload(reader: &RegionReader, x: u8, z: u8) -> Result<Option<(usize, u8)>> {
+    let offset = RegionReader::chunk_offset(x as usize, z as usize);
+    let location = match reader.get_location(offset) {
+        Some(location) => location,
+        None => return Ok(None),
+    };
+
+    let bytes = reader.inner();
+    let start =
+        u32::from_be_bytes([0, location[0], location[1], location[2]]) as usize * SECTOR_SIZE;
+    let length = read_u32(bytes, start)? as usize;
+    if length == 0 {
+        return Err(Error::InvalidRegionFile("chunk has a length of 0"));
+    }
+    // the length includes the compression byte
+    if bytes.len() < start + 4 + length {
+        return Err(Error::InvalidRegionFile(
+            "chunk data goes past the end of file",
+        ));
+    }
+
+    Ok(Some((start, bytes[start + 4])))
+}
+
+/// Writes a full region file with the given chunks.
+///
+/// Chunks are laid out in the same order as their header index.
+/// Chunks that doesn't fit results in an error, see [`save_region`] for those.
+pub(crate) fn write_region<W: Write>(
+    writer: &mut W,
+    chunks: Vec<((u8, u8), RawChunk)>,
+) -> Result<()> {
+    write_chunks(writer, chunks, false)?;
+
+    Ok(())
+}
+
+/// Writes a full region file with the given chunks.
+///
+/// Chunks that doesn't fit are only marked as external in the region file if `external` is `true`,
+/// these are returned so they can be written to their external files.
+fn write_chunks<W: Write>(
+    writer: &mut W,
+    mut chunks: Vec<((u8, u8), RawChunk)>,
+    external: bool,
+) -> Result<Vec<((u8, u8), RawChunk)>> {
+    chunks.sort_unstable_by_key(|((x, z), _)| chunk_index(*x, *z));
+
+    let mut timestamps = vec![0u8; SECTOR_SIZE];
+    let mut region_writer = RegionWriter::new();
+    let mut oversized = vec![];
+    for ((x, z), chunk) in chunks {
+        let index = chunk_index(x, z);
+        timestamps[index * 4..index * 4 + 4].copy_from_slice(&chunk.timestamp.to_be_bytes());
+
+        if chunk.sector_count() > MAX_CHUNK_SECTORS {
+            if !external {
+                return Err(Error::ChunkTooLarge(x, z));
+            }
+
+            // external chunks only stores the length and compression byte in the region file
+            region_writer.push_pending_chunk(PendingChunk::new_compressed(
+                vec![],
+                chunk.compression.into(),
+                chunk.timestamp,
+                (x, z),
+            )?);
+            oversized.push(((x, z), chunk));
+            continue;
+        }
+
+        region_writer.push_pending_chunk(PendingChunk::new_compressed(
+            chunk.data,
+            chunk.compression.into(),
+            chunk.timestamp,
+            (x, z),
+        )?);
+    }
+
+    let mut bytes = Vec::new();
+    region_writer.write(&mut bytes)?;
+
+    // `mca` fills the timestamp table in the order chunks were pushed, so it's replaced
+    bytes[SECTOR_SIZE..HEADER_SIZE].copy_from_slice(&timestamps);
+    let reader = RegionReader::new(&bytes)?;
+    let flagged = oversized
+        .iter()
+        .map(|((x, z), _)| chunk_payload(&reader, *x, *z))
+        .collect::<Result<Vec<_>>>()?;
+    for (start, _) in flagged.into_iter().flatten() {
+        bytes[start + 4] |= EXTERNAL_FLAG;
+    }
+
+    writer.write_all(&bytes)?;
+
+    Ok(oversized)
+}
+
+/// Writes a full region file to `path`, with oversized chunks written next to it.
+///
+/// The file name must be `r.<x>.<z>.mca` and match `region_coords`.
+/// `previous` is where the chunks were read from, so external files of chunks that now fits can be removed.
+///
+/// The region and it's external files are first written to temporary files, which are then renamed over the real ones.
+/// So a crash mid-write never leaves a half-written region file behind.
+/// The external files are renamed first, so the new region file never points to missing ones,
+/// and external files of chunks that now fits are only removed once the new region file is in place.
+pub(crate) fn save_region(
+    path: &Path,
+    region_coords: (i32, i32),
+    chunks: Vec<((u8, u8), RawChunk)>,
+    previous: Option<&ExternalChunks>,
+) -> Result<()> {
+    let file_coords = region_coords_from_path(path)?;
+    if file_coords != region_coords {
+        return Err(Error::RegionFileMismatch {
+            region: region_coords,
+            file: file_coords,
+        });
+    }
+    let external = ExternalChunks::new(path, region_coords).with_stored_from(previous);
+
+    let temp_path = temp_path_of(path);
+    let oversized = write_synced(&temp_path, |writer| write_chunks(writer, chunks, true))?;
+
+    let written = oversized.iter().try_for_each(|((x, z), chunk)| {
+        write_synced(&temp_path_of(&external.path(*x, *z)), |writer| {
+            Ok(writer.write_all(&chunk.data)?)
+        })
+    });
+    if let Err(e) = written {
+        let _ = std::fs::remove_file(&temp_path);
+        for ((x, z), _) in oversized.iter() {
+            let _ = std::fs::remove_file(temp_path_of(&external.path(*x, *z)));
+        }
+        return Err(e);
+    }
+
+    for ((x, z), _) in oversized.iter() {
+        let external_path = external.path(*x, *z);
+        std::fs::rename(temp_path_of(&external_path), external_path)?;
+    }
+    std::fs::rename(&temp_path, path)?;
+
+    // nothing points to these anymore now that the new region file is in place
+    for (x, z) in external.stored.iter() {
+        if oversized.iter().any(|(coords, _)| coords == &(*x, *z)) {
+            continue;
+        }
+
+        std::fs::remove_file(external.path(*x, *z)).or_else(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => Ok(()),
+            _ => Err(e),
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Returns the temporary path a file is written to before it's renamed to `path`.
+pub(crate) fn temp_path_of(path: &Path) -> PathBuf {
+    let mut temp_name = path.as_os_str().to_owned();
+    temp_name.push(".tmp");
+    PathBuf::from(temp_name)
+}
+
+/// Writes a file and makes sure it's actually on disk, removing it again if anything fails.
+pub(crate) fn write_synced<T>(
+    path: &Path,
+    write: impl FnOnce(&mut BufWriter<File>) -> Result<T>,
+) -> Result<T> {
+    let written = (|| {
+        let mut writer = BufWriter::new(File::create(path)?);
+        let value = write(&mut writer)?;
+        let file = writer.into_inner().map_err(|e| e.into_error())?;
+        file.sync_all()?;
+        Ok::<T, Error>(value)
+    })();
+
+    if written.is_err() {
+        let _ = std::fs::remove_file(path);
+    }
+
+    written
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn compression_ids() -> Result<()> {
+        for id in 1..=4 {
+            assert_eq!(Compression::from_id(id)?.id(), id);
+        }
+        assert!(Compression::from_id(0).is_err());
+        assert!(Compression::from_id(5).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn compress_roundtrip() -> Result<()> {
+        let bytes = b"some pretty compressable bytes bytes bytes bytes".to_vec();
+        for compression in [
+            Compression::GZip,
+            Compression::Zlib,
+            Compression::Uncompressed,
+            Compression::LZ4,
+        ] {
+            let chunk = RawChunk::compress(&bytes, compression, 0)?;
+            assert_eq!(chunk.decompress()?, bytes);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn recompress() -> Result<()> {
+        let bytes = vec![10, 0, 0, 0];
+        let chunk = RawChunk::compress(&bytes, Compression::GZip, 42)?;
+        let chunk = chunk.recompress(Compression::LZ4)?;
+        assert_eq!(chunk.compression, Compression::LZ4);
+        assert_eq!(chunk.timestamp, 42);
+        assert_eq!(chunk.decompress()?, bytes);
+        Ok(())
+    }
+
+    #[test]
+    fn empty_region() -> Result<()> {
+        let mut buf = vec![];
+        write_region(&mut buf, vec![])?;
+        assert_eq!(buf, vec![0u8; HEADER_SIZE]);
+        assert!(read_chunk(&buf, 0, 0, None)?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn write_and_read_chunks() -> Result<()> {
+        let small = RawChunk::compress(&[1, 2, 3], Compression::Zlib, 1_700_000_000)?;
+        let big = RawChunk::compress(&vec![7; 10_000], Compression::Uncompressed, 5)?;
+
+        let mut buf = vec![];
+        write_region(
+            &mut buf,
+            vec![((31, 31), small.clone()), ((4, 2), big.clone())],
+        )?;
+
+        // header + 3 sectors for the big chunk + 1 for the small one
+        assert_eq!(buf.len(), HEADER_SIZE + SECTOR_SIZE * 4);
+        assert_eq!(buf.len() % SECTOR_SIZE, 0);
+
+        assert_eq!(read_chunk(&buf, 31, 31, None)?, Some(small));
+        assert_eq!(read_chunk(&buf, 4, 2, None)?, Some(big));
+        assert!(read_chunk(&buf, 2, 4, None)?.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_compression() -> Result<()> {
+        let chunk = RawChunk::compress(&[1, 2, 3], Compression::Zlib, 0)?;
+        let mut buf = vec![];
+        write_region(&mut buf, vec![((0, 0), chunk)])?;
+
+        // the only chunk starts right after the header
+        buf[HEADER_SIZE + 4] = 9;
+        assert!(matches!(
+            read_chunk(&buf, 0, 0, None),
+            Err(Error::UnknownCompression(9))
+        ));
+
+        // a length going past the end of the file
+        buf[HEADER_SIZE..HEADER_SIZE + 4].copy_from_slice(&(SECTOR_SIZE as u32 * 2).to_be_bytes());
+        assert!(read_chunk(&buf, 0, 0, None).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn too_small_region() {
+        assert!(read_chunk(&[0; 100], 0, 0, None).is_err());
+    }
+
+    #[test]
+    fn external_chunks() -> Result<()> {
+        let folder = std::env::temp_dir().join("silverfish_region_file_external_chunks");
+        let _ = std::fs::remove_dir_all(&folder);
+        std::fs::create_dir_all(&folder)?;
+        let path = folder.join("r.-1.0.mca");
+        let external = ExternalChunks::new(&path, (-1, 0));
+        assert_eq!(external.path(3, 1), folder.join("c.-29.1.mcc"));
+
+        // 2 MiB uncompressed doesn't fit in 255 sectors
+        let huge = RawChunk::compress(&vec![3; 2_097_152], Compression::Uncompressed, 9)?;
+        let small = RawChunk::compress(&[1, 2, 3], Compression::Zlib, 9)?;
+        let chunks = vec![((3, 1), huge.clone()), ((0, 0), small.clone())];
+
+        assert!(write_region(&mut vec![], chunks.clone()).is_err());
+
+        save_region(&path, (-1, 0), chunks, None)?;
+        assert!(external.path(3, 1).exists());
+        assert!(!external.path(0, 0).exists());
+        assert!(!temp_path_of(&path).exists());
+        assert!(!temp_path_of(&external.path(3, 1)).exists());
+
+        let buf = std::fs::read(&path)?;
+        assert_eq!(buf.len(), HEADER_SIZE + SECTOR_SIZE * 2);
+        assert!(read_chunk(&buf, 3, 1, None).is_err());
+        assert_eq!(read_chunk(&buf, 3, 1, Some(&external))?, Some(huge));
+        assert_eq!(
+            read_chunk(&buf, 0, 0, Some(&external))?,
+            Some(small.clone())
+        );
+
+        // once the chunk fits again, the external file is removed
+        let (region_coords, bytes, read) = open_region(&path)?;
+        assert_eq!(region_coords, (-1, 0));
+        assert_eq!(read.stored, vec![(3, 1)]);
+        assert_eq!(read_chunks(&bytes, Some(&read))?.len(), 2);
+        save_region(&path, (-1, 0), vec![((3, 1), small)], Some(&read))?;
+        assert!(!external.path(3, 1).exists());
+
+        // only the chunks known to be external are removed
+        std::fs::write(external.path(4, 4), [0])?;
+        save_region(&path, (-1, 0), vec![], Some(&read))?;
+        assert!(external.path(4, 4).exists());
+
+        assert!(matches!(
+            save_region(&folder.join("r.0.0.mca"), (-1, 0), vec![], None),
+            Err(Error::RegionFileMismatch { .. })
+        ));
+
+        Ok(())
+    }
+}
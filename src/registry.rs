@@ -0,0 +1,486 @@
+//! `registry` contains the [`BlockRegistry`], which knows what blocks exist and which properties they take.
+//!
+//! It's loaded from the `blocks.json` report made by the vanilla data generator,
+//! `java -DbundlerMainClass=net.minecraft.data.Main -jar server.jar --reports`.
+
+use crate::{Block, Error, NbtString, Region, Result};
+use ahash::AHashMap;
+use serde_json::Value;
+use std::{borrow::Cow, collections::BTreeMap, fs::File, io::BufReader, path::Path};
+
+/// The block states of a single block, as reported by the data generator.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlockDefinition {
+    /// Every property the block takes, with all of it's allowed values.
+    pub properties: BTreeMap<String, Vec<String>>,
+    /// The properties of the block's default state.
+    pub default_state: BTreeMap<String, String>,
+}
+
+/// Every known block and it's properties.
+///
+/// Block names are always stored namespaced, ids without a namespace are looked up under `minecraft`.
+#[derive(Clone, Default)]
+pub struct BlockRegistry {
+    blocks: AHashMap<String, BlockDefinition>,
+}
+
+impl BlockRegistry {
+    /// Creates an empty [`BlockRegistry`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads a `blocks.json` report from the data generator.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let value: Value = serde_json::from_reader(BufReader::new(File::open(path)?))?;
+        Self::from_json(&value)
+    }
+
+    /// Parses the contents of a `blocks.json` report.
+    ///
+    /// ## Example
+    /// ```
+    /// # use silverfish::BlockRegistry;
+    /// let registry = BlockRegistry::from_json_str(r#"{
+    ///     "minecraft:stone": { "states": [{ "id": 1, "default": true }] }
+    /// }"#)?;
+    /// assert!(registry.contains("stone"));
+    /// # Ok::<(), silverfish::Error>(())
+    /// ```
+    pub fn from_json_str(json: &str) -> Result<Self> {
+        let value: Value = serde_json::from_str(json)?;
+        Self::from_json(&value)
+    }
+
+    /// Parses an already read `blocks.json` report.
+    pub fn from_json(value: &Value) -> Result<Self> {
+        let blocks = value
+            .as_object()
+            .ok_or_else(|| Error::InvalidBlockRegistry(String::from("expected an object")))?;
+
+        let mut registry = Self::new();
+        for (name, block) in blocks {
+            let invalid = |what: &str| Error::InvalidBlockRegistry(format!("{name} > {what}"));
+            let mut definition = BlockDefinition::default();
+
+            if let Some(properties) = block.get("properties") {
+                let properties = properties
+                    .as_object()
+                    .ok_or_else(|| invalid("properties"))?;
+                for (key, values) in properties {
+                    let values = values
+                        .as_array()
+                        .ok_or_else(|| invalid(key))?
+                        .iter()
+                        .map(|v| v.as_str().map(String::from).ok_or_else(|| invalid(key)))
+                        .collect::<Result<Vec<String>>>()?;
+                    definition.properties.insert(key.clone(), values);
+                }
+            }
+
+            let default_state = block
+                .get("states")
+                .and_then(|s| s.as_array())
+                .and_then(|s| {
+                    s.iter()
+                        .find(|s| s.get("default").and_then(|d| d.as_bool()) == Some(true))
+                })
+                .and_then(|s| s.get("properties"))
+                .and_then(|p| p.as_object());
+            if let Some(default_state) = default_state {
+                for (key, value) in default_state {
+                    let value = value.as_str().ok_or_else(|| invalid("states"))?;
+                    definition
+                        .default_state
+                        .insert(key.clone(), String::from(value));
+                }
+            }
+
+            registry.insert(name, definition);
+        }
+
+        Ok(registry)
+    }
+
+    /// Adds every block from another registry, like a report from a modded server.
+    ///
+    /// Blocks that already exist are replaced.
+    pub fn extend(&mut self, other: BlockRegistry) {
+        self.blocks.extend(other.blocks);
+    }
+
+    /// Adds or replaces a single block.
+    pub fn insert(&mut self, name: &str, definition: BlockDefinition) {
+        self.blocks
+            .insert(Block::populate_namespace(name).into_owned(), definition);
+    }
+
+    /// Returns the definition of a block, if it exists.
+    pub fn get(&self, name: &str) -> Option<&BlockDefinition> {
+        self.blocks.get(Block::populate_namespace(name).as_ref())
+    }
+
+    /// Returns if the block exists.
+    pub fn contains(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    /// Returns how many blocks are in the registry.
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Returns if the registry has no blocks.
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    /// Checks that the block exists and that all it's properties & values are valid.
+    ///
+    /// Missing properties are allowed, Minecraft uses the default state for those.
+    ///
+    /// ## Example
+    /// ```
+    /// # use silverfish::{Block, BlockRegistry};
+    /// # let registry = BlockRegistry::from_json_str(r#"{ "minecraft:stone": {} }"#)?;
+    /// assert!(registry.validate(&Block::new("stone")).is_ok());
+    /// assert!(registry.validate(&Block::new("stonee")).is_err());
+    /// # Ok::<(), silverfish::Error>(())
+    /// ```
+    pub fn validate(&self, block: &Block) -> Result<()> {
+        let name = block.name.to_str();
+        let definition = self
+            .get(&name)
+            .ok_or_else(|| Error::UnknownBlock(Block::populate_namespace(&name).into_owned()))?;
+
+        let properties = match &block.properties {
+            Some(properties) => properties,
+            None => return Ok(()),
+        };
+        for (key, value) in properties {
+            let (key, value) = (key.to_str(), value.to_str());
+            let allowed = definition.properties.get(key.as_ref()).ok_or_else(|| {
+                Error::UnknownBlockProperty {
+                    block: Block::populate_namespace(&name).into_owned(),
+                    property: key.to_string(),
+                    allowed: definition.properties.keys().cloned().collect(),
+                }
+            })?;
+
+            if !allowed.iter().any(|a| *a == value) {
+                return Err(Error::InvalidBlockPropertyValue {
+                    block: Block::populate_namespace(&name).into_owned(),
+                    property: key.to_string(),
+                    value: value.to_string(),
+                    allowed: allowed.clone(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Fills in every property the block is missing with the value from it's default state.
+    ///
+    /// Blocks that aren't in the registry are returned as is.
+    ///
+    /// ## Example
+    /// ```
+    /// # use silverfish::{Block, BlockRegistry};
+    /// # let registry = BlockRegistry::from_json_str(r#"{ "minecraft:furnace": { "states": [
+    /// #     { "default": true, "id": 1, "properties": { "facing": "north", "lit": "false" } }
+    /// # ] } }"#)?;
+    /// let furnace = registry.fill_defaults(Block::new_with_props("furnace", [("lit", "true")]));
+    /// assert_eq!(furnace, Block::new_with_props("furnace", [("facing", "north"), ("lit", "true")]));
+    /// # Ok::<(), silverfish::Error>(())
+    /// ```
+    pub fn fill_defaults(&self, mut block: Block) -> Block {
+        let definition = match self.get(&block.name.to_str()) {
+            Some(definition) if !definition.default_state.is_empty() => definition,
+            _ => return block,
+        };
+
+        let properties = block.properties.get_or_insert_default();
+        for (key, value) in &definition.default_state {
+            properties
+                .entry(key.as_str().into())
+                .or_insert_with(|| value.as_str().into());
+        }
+
+        block
+    }
+
+    /// Returns if two blocks are the same block state, where a missing property counts as it's default value.
+    ///
+    /// Blocks that aren't in the registry are only equal if their properties are.
+    ///
+    /// ## Example
+    /// ```
+    /// # use silverfish::{Block, BlockRegistry};
+    /// # let registry = BlockRegistry::from_json_str(r#"{ "minecraft:furnace": { "states": [
+    /// #     { "default": true, "id": 1, "properties": { "facing": "north", "lit": "false" } }
+    /// # ] } }"#)?;
+    /// let furnace = Block::new_with_props("minecraft:furnace", [("facing", "north"), ("lit", "false")]);
+    /// assert!(registry.same_state(&Block::new("furnace"), &furnace));
+    /// # Ok::<(), silverfish::Error>(())
+    /// ```
+    pub fn same_state(&self, a: &Block, b: &Block) -> bool {
+        if a.name.into_cow_namespaced() != b.name.into_cow_namespaced() {
+            return false;
+        }
+
+        let empty = BTreeMap::new();
+        let a_properties = a.properties.as_ref().unwrap_or(&empty);
+        let b_properties = b.properties.as_ref().unwrap_or(&empty);
+        let defaults = self.get(&a.name.to_str()).map(|d| &d.default_state);
+
+        a_properties.keys().chain(b_properties.keys()).all(|key| {
+            state_value(a_properties, defaults, key) == state_value(b_properties, defaults, key)
+        })
+    }
+}
+
+impl Region {
+    /// Checks a block against [`Config::registry`](crate::Config::registry) before it's set,
+    /// and fills in it's default properties with [`Config::fill_default_properties`](crate::Config::fill_default_properties).
+    pub(crate) fn prepare_block(&self, block: Block) -> Result<Block> {
+        let registry = match &self.config.registry {
+            Some(registry) => registry,
+            None => return Ok(block),
+        };
+
+        registry.validate(&block)?;
+        Ok(match self.config.fill_default_properties {
+            true => registry.fill_defaults(block),
+            false => block,
+        })
+    }
+}
+
+/// The value of a property, or it's default value if the block doesn't have it.
+fn state_value<'a>(
+    properties: &'a BTreeMap<NbtString, NbtString>,
+    defaults: Option<&'a BTreeMap<String, String>>,
+    key: &NbtString,
+) -> Option<Cow<'a, str>> {
+    match properties.get(key) {
+        Some(value) => Some(value.to_str()),
+        None => defaults
+            .and_then(|d| d.get(key.to_str().as_ref()))
+            .map(|v| Cow::Borrowed(v.as_str())),
+    }
+}
+
+impl std::fmt::Debug for BlockRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BlockRegistry")
+            .field("blocks", &self.blocks.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+    use crate::{Config, PalettedBlocks};
+    use std::sync::Arc;
+
+    /// A small part of the vanilla `blocks.json` report.
+    pub(crate) const BLOCKS_JSON: &str = r#"{
+        "minecraft:stone": {
+            "definition": { "type": "minecraft:block", "properties": {} },
+            "states": [{ "default": true, "id": 1 }]
+        },
+        "minecraft:furnace": {
+            "definition": { "type": "minecraft:furnace", "properties": {} },
+            "properties": {
+                "facing": ["north", "south", "west", "east"],
+                "lit": ["true", "false"]
+            },
+            "states": [
+                { "id": 4, "properties": { "facing": "north", "lit": "true" } },
+                { "default": true, "id": 5, "properties": { "facing": "north", "lit": "false" } }
+            ]
+        },
+        "minecraft:oak_slab": {
+            "properties": {
+                "type": ["top", "bottom", "double"],
+                "waterlogged": ["true", "false"]
+            },
+            "states": [
+                { "default": true, "id": 11, "properties": { "type": "bottom", "waterlogged": "false" } }
+            ]
+        }
+    }"#;
+
+    pub(crate) fn registry() -> BlockRegistry {
+        BlockRegistry::from_json_str(BLOCKS_JSON).unwrap()
+    }
+
+    #[test]
+    fn parse() {
+        let registry = registry();
+        assert_eq!(registry.len(), 3);
+
+        let furnace = registry.get("furnace").unwrap();
+        assert_eq!(furnace.properties["lit"], vec!["true", "false"]);
+        assert_eq!(furnace.default_state["facing"], "north");
+        assert_eq!(furnace.default_state["lit"], "false");
+        assert!(
+            registry
+                .get("minecraft:stone")
+                .unwrap()
+                .properties
+                .is_empty()
+        );
+        assert!(!registry.contains("stonee"));
+    }
+
+    #[test]
+    fn validate() {
+        let registry = registry();
+        assert!(registry.validate(&Block::new("stone")).is_ok());
+        assert!(
+            registry
+                .validate(&Block::new_with_props("furnace", [("lit", "true")]))
+                .is_ok()
+        );
+
+        assert!(matches!(
+            registry.validate(&Block::new("stonee")),
+            Err(Error::UnknownBlock(name)) if name == "minecraft:stonee"
+        ));
+        assert!(matches!(
+            registry.validate(&Block::new_with_props("furnace", [("powered", "true")])),
+            Err(Error::UnknownBlockProperty { property, .. }) if property == "powered"
+        ));
+        assert!(matches!(
+            registry.validate(&Block::new_with_props("furnace", [("facing", "up")])),
+            Err(Error::InvalidBlockPropertyValue { value, allowed, .. })
+                if value == "up" && allowed.len() == 4
+        ));
+    }
+
+    #[test]
+    fn extend() {
+        let mut registry = registry();
+        let modded = BlockRegistry::from_json_str(r#"{ "mymod:crusher": {} }"#).unwrap();
+        registry.extend(modded);
+
+        assert!(registry.contains("mymod:crusher"));
+        assert!(registry.contains("stone"));
+    }
+
+    #[test]
+    fn checked_block() {
+        let registry = registry();
+        assert!(Block::try_new_checked("oak_slab", &[("type", "top")], &registry).is_ok());
+        assert!(Block::try_new_checked("oak_slab", &[("type", "side")], &registry).is_err());
+    }
+
+    #[test]
+    fn set_block_validates() -> Result<()> {
+        let mut region = Region::empty((0, 0));
+        region.set_config(Config {
+            create_chunk_if_missing: true,
+            registry: Some(Arc::new(registry())),
+            ..Default::default()
+        })?;
+
+        region.set_block((1, 2, 3), "furnace")?;
+        assert!(region.set_block((1, 2, 3), "stonee").is_err());
+        assert!(
+            region
+                .set_block((1, 2, 3), Block::new_with_props("stone", [("lit", "true")]))
+                .is_err()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn fill_defaults() {
+        let registry = registry();
+        assert_eq!(
+            registry.fill_defaults(Block::new("furnace")),
+            Block::new_with_props("furnace", [("facing", "north"), ("lit", "false")])
+        );
+        assert_eq!(
+            registry.fill_defaults(Block::new_with_props("furnace", [("lit", "true")])),
+            Block::new_with_props("furnace", [("facing", "north"), ("lit", "true")])
+        );
+        assert_eq!(
+            registry.fill_defaults(Block::new("stone")),
+            Block::new("stone")
+        );
+        assert_eq!(
+            registry.fill_defaults(Block::new("mymod:crusher")),
+            Block::new("mymod:crusher")
+        );
+    }
+
+    #[test]
+    fn same_state() {
+        let registry = registry();
+        let furnace =
+            Block::new_with_props("minecraft:furnace", [("facing", "north"), ("lit", "false")]);
+
+        assert!(registry.same_state(&Block::new("furnace"), &furnace));
+        assert!(Block::new("furnace").eq_with_defaults(&furnace, &registry));
+        assert!(!registry.same_state(
+            &Block::new_with_props("furnace", [("lit", "true")]),
+            &furnace
+        ));
+        assert!(!registry.same_state(&Block::new("stone"), &furnace));
+        assert!(!registry.same_state(
+            &Block::new("mymod:crusher"),
+            &Block::new_with_props("mymod:crusher", [("on", "true")])
+        ));
+    }
+
+    #[test]
+    fn set_block_fills_defaults() -> Result<()> {
+        let mut region = Region::empty((0, 0));
+        region.set_config(Config {
+            create_chunk_if_missing: true,
+            registry: Some(Arc::new(registry())),
+            fill_default_properties: true,
+            ..Default::default()
+        })?;
+
+        region.set_block((1, 2, 3), "furnace")?;
+        region.set_block(
+            (2, 2, 3),
+            Block::new_with_props("furnace", [("lit", "true")]),
+        )?;
+        region.write_blocks()?;
+
+        assert_eq!(
+            region.get_block((1, 2, 3))?,
+            Block::new_with_props("furnace", [("facing", "north"), ("lit", "false")])
+        );
+        // given properties are kept, only the missing ones are filled in
+        assert_eq!(
+            region.get_block((2, 2, 3))?,
+            Block::new_with_props("furnace", [("facing", "north"), ("lit", "true")])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn paletted_blocks_contains_with_defaults() -> Result<()> {
+        let palette = vec![
+            Block::new_with_props("furnace", [("facing", "north"), ("lit", "false")])
+                .to_compound()?,
+        ];
+        let mut blocks = PalettedBlocks::new(-64..320, 16);
+        blocks.insert((0, 0, 0), &palette, 0);
+
+        assert!(blocks.contains_with_defaults(&Block::new("furnace"), &registry()));
+        assert!(!blocks.contains(&Block::new("furnace")));
+
+        Ok(())
+    }
+}
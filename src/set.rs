@@ -17,6 +17,9 @@ impl Region {
     ///
     /// To actually write the changes to the `chunks`, call [`Region::write_blocks`]
     ///
+    /// With [`Config::registry`](crate::Config::registry) set, the block is checked against it first  
+    /// and gets it's default properties filled in with [`Config::fill_default_properties`](crate::Config::fill_default_properties).  
+    ///
     /// ## Example
     /// ```
     /// # use silverfish::{Region, Block};
@@ -31,11 +34,13 @@ impl Region {
         C: Into<Coords>,
     {
         let coords: Coords = coords.into();
+        let block = self.prepare_block(block.into())?;
+
         let (chunk_x, chunk_z) = (
             (coords.x / ChunkData::WIDTH as u32) as u8,
             (coords.z / ChunkData::WIDTH as u32) as u8,
         );
-        let mut chunk_data = self.get_chunk_mut(chunk_x, chunk_z)?;
+        let mut chunk_data = self.get_chunk_mut_unmarked(chunk_x, chunk_z)?;
         // convert the coordinates into chunk local
         // we skip Y until writing since it gets only divided into sections then
         chunk_data.set_block(
@@ -76,7 +81,7 @@ impl Region {
         let cell: BiomeCell = cell.into();
         let biome: NbtString = biome.into();
 
-        let mut chunk_data = self.get_chunk_mut(cell.chunk.0, cell.chunk.1)?;
+        let mut chunk_data = self.get_chunk_mut_unmarked(cell.chunk.0, cell.chunk.1)?;
         chunk_data.set_biome(cell, biome)
     }
 
@@ -103,7 +108,7 @@ impl Region {
     ) -> Result<()> {
         for x in chunks_x {
             for z in chunk_z.clone() {
-                let mut chunk = self.get_chunk_mut(x, z)?;
+                let mut chunk = self.get_chunk_mut_unmarked(x, z)?;
 
                 let mut map = AHashMap::new();
                 for y in sections.clone() {
@@ -140,7 +145,7 @@ impl Region {
     ) -> Result<()> {
         for x in chunks_x {
             for z in chunk_z.clone() {
-                let mut chunk = self.get_chunk_mut(x, z)?;
+                let mut chunk = self.get_chunk_mut_unmarked(x, z)?;
 
                 let mut map = AHashMap::new();
                 for y in sections.clone() {
@@ -0,0 +1,827 @@
+//! `snbt` parses & prints stringified NBT, the format used in commands like `/data` & `/setblock`,
+//! and block state strings like `oak_stairs[facing=east,half=top]`.
+
+use crate::{Block, ChunkData, Error, NbtString, Result, nbt::set_tag};
+use simdnbt::owned::{NbtCompound, NbtList, NbtTag};
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, LowerExp, Write},
+};
+
+/// Parses a SNBT compound, like `{id: "minecraft:chest", Items: []}`.
+///
+/// ## Example
+/// ```
+/// # use silverfish::parse_snbt;
+/// let nbt = parse_snbt(r#"{Items: [{Slot: 0b, id: "minecraft:diamond", count: 3}]}"#)?;
+/// # Ok::<(), silverfish::Error>(())
+/// ```
+pub fn parse_snbt(input: &str) -> Result<NbtCompound> {
+    let mut reader = Reader::new(input);
+    let compound = reader.read_compound(0)?;
+
+    reader.skip_whitespace();
+    if !reader.is_end() {
+        return Err(reader.error("unexpected characters after the compound"));
+    }
+
+    Ok(compound)
+}
+
+/// Prints a compound as SNBT on a single line, the same way the `/data` command does.
+///
+/// ## Example
+/// ```
+/// # use silverfish::{parse_snbt, to_snbt};
+/// let nbt = parse_snbt("{Count: 3b, id: 'minecraft:stone'}")?;
+/// assert_eq!(to_snbt(&nbt), r#"{Count: 3b, id: "minecraft:stone"}"#);
+/// # Ok::<(), silverfish::Error>(())
+/// ```
+pub fn to_snbt(compound: &NbtCompound) -> String {
+    let mut out = String::new();
+    Printer { pretty: false }.compound(&mut out, compound, 0);
+    out
+}
+
+/// Prints a compound as SNBT, with every compound & nested list split into indented lines.
+pub fn to_snbt_pretty(compound: &NbtCompound) -> String {
+    let mut out = String::new();
+    Printer { pretty: true }.compound(&mut out, compound, 0);
+    out
+}
+
+impl ChunkData {
+    /// Prints the chunk NBT as SNBT, see [`to_snbt`].
+    pub fn to_snbt(&self) -> String {
+        to_snbt(&self.nbt)
+    }
+
+    /// Prints the chunk NBT as indented SNBT, see [`to_snbt_pretty`].
+    pub fn to_snbt_pretty(&self) -> String {
+        to_snbt_pretty(&self.nbt)
+    }
+}
+
+/// Writes tags as SNBT.
+struct Printer {
+    pretty: bool,
+}
+
+impl Printer {
+    fn newline(&self, out: &mut String, depth: usize) {
+        if self.pretty {
+            out.push('\n');
+            out.push_str(&"    ".repeat(depth));
+        }
+    }
+
+    fn separator(&self, out: &mut String, depth: usize) {
+        out.push(',');
+        match self.pretty {
+            true => self.newline(out, depth),
+            false => out.push(' '),
+        }
+    }
+
+    fn compound(&self, out: &mut String, compound: &NbtCompound, depth: usize) {
+        if compound.is_empty() {
+            out.push_str("{}");
+            return;
+        }
+
+        out.push('{');
+        self.newline(out, depth + 1);
+        for (i, (key, value)) in compound.iter().enumerate() {
+            if i > 0 {
+                self.separator(out, depth + 1);
+            }
+            let key = key.to_str();
+            match key.chars().all(is_unquoted) && !key.is_empty() {
+                true => out.push_str(&key),
+                false => quote(out, &key),
+            }
+            out.push_str(": ");
+            self.tag(out, value, depth + 1);
+        }
+        self.newline(out, depth);
+        out.push('}');
+    }
+
+    fn tag(&self, out: &mut String, tag: &NbtTag, depth: usize) {
+        match tag {
+            NbtTag::Byte(v) => write!(out, "{v}b").unwrap(),
+            NbtTag::Short(v) => write!(out, "{v}s").unwrap(),
+            NbtTag::Int(v) => write!(out, "{v}").unwrap(),
+            NbtTag::Long(v) => write!(out, "{v}L").unwrap(),
+            NbtTag::Float(v) => write!(out, "{}f", java_float(*v)).unwrap(),
+            NbtTag::Double(v) => write!(out, "{}d", java_float(*v)).unwrap(),
+            NbtTag::ByteArray(v) => byte_array(out, v),
+            NbtTag::IntArray(v) => int_array(out, v),
+            NbtTag::LongArray(v) => long_array(out, v),
+            NbtTag::String(v) => quote(out, &v.as_str().to_str()),
+            NbtTag::List(list) => self.list(out, list, depth),
+            NbtTag::Compound(compound) => self.compound(out, compound, depth),
+        }
+    }
+
+    fn list(&self, out: &mut String, list: &NbtList, depth: usize) {
+        match list {
+            NbtList::Empty => out.push_str("[]"),
+            NbtList::Byte(v) => inline(out, v.as_slice(), |out, v| write!(out, "{v}b").unwrap()),
+            NbtList::Short(v) => inline(out, v.as_slice(), |out, v| write!(out, "{v}s").unwrap()),
+            NbtList::Int(v) => inline(out, v.as_slice(), |out, v| write!(out, "{v}").unwrap()),
+            NbtList::Long(v) => inline(out, v.as_slice(), |out, v| write!(out, "{v}L").unwrap()),
+            NbtList::Float(v) => inline(out, v.as_slice(), |out, v| {
+                write!(out, "{}f", java_float(*v)).unwrap()
+            }),
+            NbtList::Double(v) => inline(out, v.as_slice(), |out, v| {
+                write!(out, "{}d", java_float(*v)).unwrap()
+            }),
+            NbtList::ByteArray(v) => inline(out, v.as_slice(), |out, v| byte_array(out, v)),
+            NbtList::IntArray(v) => inline(out, v.as_slice(), |out, v| int_array(out, v)),
+            NbtList::LongArray(v) => inline(out, v.as_slice(), |out, v| long_array(out, v)),
+            NbtList::String(v) => {
+                inline(out, v.as_slice(), |out, v| quote(out, &v.as_str().to_str()))
+            }
+            NbtList::List(v) => self.nested(out, v.as_slice(), depth, |out, v, depth| {
+                self.list(out, v, depth)
+            }),
+            NbtList::Compound(v) => self.nested(out, v.as_slice(), depth, |out, v, depth| {
+                self.compound(out, v, depth)
+            }),
+        }
+    }
+
+    /// A list of lists or compounds, which gets a line per element when pretty printing.
+    fn nested<T>(
+        &self,
+        out: &mut String,
+        values: &[T],
+        depth: usize,
+        print: impl Fn(&mut String, &T, usize),
+    ) {
+        out.push('[');
+        self.newline(out, depth + 1);
+        for (i, value) in values.iter().enumerate() {
+            if i > 0 {
+                self.separator(out, depth + 1);
+            }
+            print(out, value, depth + 1);
+        }
+        self.newline(out, depth);
+        out.push(']');
+    }
+}
+
+/// A list of values that's always printed on a single line.
+fn inline<T>(out: &mut String, values: &[T], print: impl Fn(&mut String, &T)) {
+    out.push('[');
+    for (i, value) in values.iter().enumerate() {
+        if i > 0 {
+            out.push_str(", ");
+        }
+        print(out, value);
+    }
+    out.push(']');
+}
+
+fn byte_array(out: &mut String, values: &[u8]) {
+    out.push_str("[B; ");
+    let values: Vec<String> = values.iter().map(|v| format!("{}B", *v as i8)).collect();
+    out.push_str(&values.join(", "));
+    out.push(']');
+}
+
+fn int_array(out: &mut String, values: &[i32]) {
+    out.push_str("[I; ");
+    let values: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+    out.push_str(&values.join(", "));
+    out.push(']');
+}
+
+fn long_array(out: &mut String, values: &[i64]) {
+    out.push_str("[L; ");
+    let values: Vec<String> = values.iter().map(|v| format!("{v}L")).collect();
+    out.push_str(&values.join(", "));
+    out.push(']');
+}
+
+/// Quotes a string the way Minecraft does, with double quotes unless the first quote inside is a double quote.
+fn quote(out: &mut String, string: &str) {
+    let mut quoted = String::with_capacity(string.len());
+    let mut quote = None;
+    for c in string.chars() {
+        if c == '\\' {
+            quoted.push('\\');
+        } else if c == '"' || c == '\'' {
+            let quote = *quote.get_or_insert(if c == '"' { '\'' } else { '"' });
+            if c == quote {
+                quoted.push('\\');
+            }
+        }
+        quoted.push(c);
+    }
+
+    let quote = quote.unwrap_or('"');
+    out.push(quote);
+    out.push_str(&quoted);
+    out.push(quote);
+}
+
+/// Formats a floating point number like Java does, always with a decimal point
+/// and in scientific notation for very big or small numbers.
+fn java_float<F: Into<f64> + Copy + Display + LowerExp>(value: F) -> String {
+    let float: f64 = value.into();
+    if float.is_nan() {
+        return String::from("NaN");
+    }
+    if float.is_infinite() {
+        return String::from(if float > 0.0 { "Infinity" } else { "-Infinity" });
+    }
+
+    let abs = float.abs();
+    if abs == 0.0 || (1e-3..1e7).contains(&abs) {
+        let string = value.to_string();
+        match string.contains('.') {
+            true => string,
+            false => string + ".0",
+        }
+    } else {
+        let string = format!("{value:e}");
+        let (mantissa, exponent) = string.split_once('e').unwrap_or((&string, "0"));
+        match mantissa.contains('.') {
+            true => format!("{mantissa}E{exponent}"),
+            false => format!("{mantissa}.0E{exponent}"),
+        }
+    }
+}
+
+/// How deep compounds & lists can be nested, the same limit as Minecraft.
+const MAX_DEPTH: usize = 512;
+
+/// A cursor over a SNBT string.
+pub(crate) struct Reader<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(input: &'a str) -> Self {
+        Self { input, position: 0 }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.position..].chars().next()
+    }
+
+    fn read_char(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += c.len_utf8();
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|c| c.is_whitespace()) {
+            self.read_char();
+        }
+    }
+
+    fn is_end(&self) -> bool {
+        self.position >= self.input.len()
+    }
+
+    fn read_while(&mut self, predicate: impl Fn(char) -> bool) -> &'a str {
+        let start = self.position;
+        while self.peek().is_some_and(&predicate) {
+            self.read_char();
+        }
+        &self.input[start..self.position]
+    }
+
+    /// An error at the current position, with a bit of the input around it for context.
+    fn error<S: Into<String>>(&self, reason: S) -> Error {
+        let mut start = self.position.saturating_sub(16);
+        while !self.input.is_char_boundary(start) {
+            start -= 1;
+        }
+        let mut end = (self.position + 16).min(self.input.len());
+        while !self.input.is_char_boundary(end) {
+            end += 1;
+        }
+        Error::InvalidSnbt {
+            position: self.position,
+            near: self.input[start..end].to_owned(),
+            reason: reason.into(),
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<()> {
+        self.skip_whitespace();
+        match self.read_char() {
+            Some(c) if c == expected => Ok(()),
+            Some(c) => Err(self.error(format!("expected '{expected}' but found '{c}'"))),
+            None => Err(self.error(format!("expected '{expected}' but the input ended"))),
+        }
+    }
+
+    /// Reads a quoted string, the reader has to be at the opening quote.
+    fn read_quoted(&mut self) -> Result<String> {
+        let quote = self
+            .read_char()
+            .ok_or_else(|| self.error("expected a string"))?;
+        let mut string = String::new();
+        loop {
+            match self.read_char() {
+                Some('\\') => match self.read_char() {
+                    Some(c) if c == '\\' || c == '"' || c == '\'' => string.push(c),
+                    Some('n') => string.push('\n'),
+                    Some('t') => string.push('\t'),
+                    Some(c) => return Err(self.error(format!("invalid escape sequence '\\{c}'"))),
+                    None => return Err(self.error("unterminated string")),
+                },
+                Some(c) if c == quote => return Ok(string),
+                Some(c) => string.push(c),
+                None => return Err(self.error("unterminated string")),
+            }
+        }
+    }
+
+    /// Reads a quoted or unquoted string.
+    fn read_string(&mut self) -> Result<String> {
+        match self.peek() {
+            Some('"' | '\'') => self.read_quoted(),
+            _ => Ok(self.read_while(is_unquoted).to_owned()),
+        }
+    }
+
+    /// Makes sure a compound or list at `depth` isn't nested too deep.
+    fn check_depth(&self, depth: usize) -> Result<()> {
+        match depth < MAX_DEPTH {
+            true => Ok(()),
+            false => Err(self.error(format!("nested deeper than {MAX_DEPTH} levels"))),
+        }
+    }
+
+    /// Reads a compound, the reader has to be at the opening `{`.
+    ///
+    /// `depth` is how many compounds & lists it's nested within.
+    pub(crate) fn read_compound(&mut self, depth: usize) -> Result<NbtCompound> {
+        self.check_depth(depth)?;
+        self.expect('{')?;
+        let mut compound = NbtCompound::new();
+
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.read_char();
+            return Ok(compound);
+        }
+
+        loop {
+            self.skip_whitespace();
+            let key = self.read_string()?;
+            if key.is_empty() {
+                return Err(self.error("expected a key"));
+            }
+            self.expect(':')?;
+            let value = self.read_value(depth + 1)?;
+            set_tag(&mut compound, &key, value);
+
+            self.skip_whitespace();
+            match self.read_char() {
+                Some(',') => continue,
+                Some('}') => return Ok(compound),
+                _ => return Err(self.error("expected ',' or '}' in compound")),
+            }
+        }
+    }
+
+    /// Reads any value.
+    pub(crate) fn read_value(&mut self, depth: usize) -> Result<NbtTag> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('{') => Ok(NbtTag::Compound(self.read_compound(depth)?)),
+            Some('[') => self.read_list(depth),
+            Some('"' | '\'') => Ok(NbtTag::String(self.read_quoted()?.as_str().into())),
+            Some(_) => {
+                let token = self.read_while(is_unquoted);
+                if token.is_empty() {
+                    return Err(self.error("expected a value"));
+                }
+                Ok(parse_unquoted(token))
+            }
+            None => Err(self.error("expected a value but the input ended")),
+        }
+    }
+
+    /// Reads a list or a typed array like `[I; 1, 2, 3]`.
+    fn read_list(&mut self, depth: usize) -> Result<NbtTag> {
+        self.check_depth(depth)?;
+        self.expect('[')?;
+        let rest = &self.input[self.position..];
+        let array = match rest.as_bytes() {
+            [kind @ (b'B' | b'I' | b'L'), b';', ..] => Some(*kind),
+            _ => None,
+        };
+        if array.is_some() {
+            self.position += 2;
+        }
+
+        let mut values = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.read_char();
+        } else {
+            loop {
+                values.push(self.read_value(depth + 1)?);
+                self.skip_whitespace();
+                match self.read_char() {
+                    Some(',') => continue,
+                    Some(']') => break,
+                    _ => return Err(self.error("expected ',' or ']' in list")),
+                }
+            }
+        }
+
+        match array {
+            Some(b'B') => Ok(NbtTag::ByteArray(
+                self.integers(values, i8::MIN as i64..=i8::MAX as i64)?
+                    .into_iter()
+                    .map(|v| v as i8 as u8)
+                    .collect(),
+            )),
+            Some(b'I') => Ok(NbtTag::IntArray(
+                self.integers(values, i32::MIN as i64..=i32::MAX as i64)?
+                    .into_iter()
+                    .map(|v| v as i32)
+                    .collect(),
+            )),
+            Some(_) => Ok(NbtTag::LongArray(
+                self.integers(values, i64::MIN..=i64::MAX)?,
+            )),
+            None => Ok(NbtTag::List(self.to_list(values)?)),
+        }
+    }
+
+    /// The values of a typed array, which all have to be integers within the range.
+    fn integers(
+        &self,
+        values: Vec<NbtTag>,
+        range: std::ops::RangeInclusive<i64>,
+    ) -> Result<Vec<i64>> {
+        values
+            .into_iter()
+            .map(|value| {
+                let integer = match value {
+                    NbtTag::Byte(v) => v as i64,
+                    NbtTag::Short(v) => v as i64,
+                    NbtTag::Int(v) => v as i64,
+                    NbtTag::Long(v) => v,
+                    _ => return Err(self.error("typed arrays can only contain integers")),
+                };
+                match range.contains(&integer) {
+                    true => Ok(integer),
+                    false => Err(self.error(format!("{integer} is out of range for the array"))),
+                }
+            })
+            .collect()
+    }
+
+    /// Turns the values into a list, where every value has to be of the same type.
+    fn to_list(&self, values: Vec<NbtTag>) -> Result<NbtList> {
+        macro_rules! list {
+            ($variant:ident) => {
+                NbtList::$variant(
+                    values
+                        .into_iter()
+                        .map(|value| match value {
+                            NbtTag::$variant(v) => Ok(v),
+                            _ => Err(self.error("a list can't mix different types")),
+                        })
+                        .collect::<Result<_>>()?,
+                )
+            };
+        }
+
+        Ok(match values.first() {
+            None => NbtList::Empty,
+            Some(NbtTag::Byte(_)) => list!(Byte),
+            Some(NbtTag::Short(_)) => list!(Short),
+            Some(NbtTag::Int(_)) => list!(Int),
+            Some(NbtTag::Long(_)) => list!(Long),
+            Some(NbtTag::Float(_)) => list!(Float),
+            Some(NbtTag::Double(_)) => list!(Double),
+            Some(NbtTag::ByteArray(_)) => list!(ByteArray),
+            Some(NbtTag::String(_)) => list!(String),
+            Some(NbtTag::List(_)) => list!(List),
+            Some(NbtTag::Compound(_)) => list!(Compound),
+            Some(NbtTag::IntArray(_)) => list!(IntArray),
+            Some(NbtTag::LongArray(_)) => list!(LongArray),
+        })
+    }
+}
+
+/// Characters that can be used in a string without quotes.
+fn is_unquoted(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '+')
+}
+
+/// Characters that can be used in a block id.
+fn is_block_id(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | ':' | '/')
+}
+
+/// Parses an unquoted value into a number, `true`/`false` into a byte or leaves it as a string.
+fn parse_unquoted(token: &str) -> NbtTag {
+    match token {
+        "true" => return NbtTag::Byte(1),
+        "false" => return NbtTag::Byte(0),
+        _ => (),
+    }
+
+    // only things that starts like a number can be one, so "inf" & "NaN" stays strings
+    let numeric = token
+        .strip_prefix(['-', '+'])
+        .unwrap_or(token)
+        .starts_with(|c: char| c.is_ascii_digit() || c == '.');
+    if numeric {
+        let (body, suffix) = token.split_at(token.len() - 1);
+        let parsed = match suffix {
+            "b" | "B" => body.parse().ok().map(NbtTag::Byte),
+            "s" | "S" => body.parse().ok().map(NbtTag::Short),
+            "l" | "L" => body.parse().ok().map(NbtTag::Long),
+            "f" | "F" => body.parse().ok().map(NbtTag::Float),
+            "d" | "D" => body.parse().ok().map(NbtTag::Double),
+            _ => match token.parse() {
+                Ok(int) => Some(NbtTag::Int(int)),
+                Err(_) if token.contains(['.', 'e', 'E']) => token.parse().ok().map(NbtTag::Double),
+                Err(_) => None,
+            },
+        };
+        if let Some(parsed) = parsed {
+            return parsed;
+        }
+    }
+
+    NbtTag::String(token.into())
+}
+
+/// Parses a block state string, `namespace:id[key=value,...]{block entity snbt}`.
+pub(crate) fn parse_block_state(input: &str) -> Result<(Block, Option<NbtCompound>)> {
+    let mut reader = Reader::new(input);
+    let error = |reader: &Reader, reason: String| Error::InvalidBlockState {
+        input: input.to_owned(),
+        position: reader.position,
+        reason,
+    };
+
+    reader.skip_whitespace();
+    let name = reader.read_while(is_block_id);
+    if name.is_empty() {
+        return Err(error(&reader, String::from("expected a block id")));
+    }
+    if name.matches(':').count() > 1 {
+        return Err(error(
+            &reader,
+            format!("'{name}' has more than one namespace"),
+        ));
+    }
+
+    let mut properties = None;
+    reader.skip_whitespace();
+    if reader.peek() == Some('[') {
+        reader.read_char();
+        let mut props: BTreeMap<NbtString, NbtString> = BTreeMap::new();
+
+        reader.skip_whitespace();
+        if reader.peek() == Some(']') {
+            reader.read_char();
+        } else {
+            loop {
+                reader.skip_whitespace();
+                let key = reader.read_string()?;
+                if key.is_empty() {
+                    return Err(error(&reader, String::from("expected a property name")));
+                }
+
+                reader.skip_whitespace();
+                if reader.read_char() != Some('=') {
+                    return Err(error(&reader, format!("expected '=' after '{key}'")));
+                }
+
+                reader.skip_whitespace();
+                let value = reader.read_string()?;
+                if value.is_empty() {
+                    return Err(error(&reader, format!("expected a value for '{key}'")));
+                }
+
+                let key = NbtString::from_str(&key)?;
+                if props.contains_key(&key) {
+                    return Err(error(&reader, format!("'{key:?}' is set more than once")));
+                }
+                props.insert(key, NbtString::from_str(&value)?);
+
+                reader.skip_whitespace();
+                match reader.read_char() {
+                    Some(',') => continue,
+                    Some(']') => break,
+                    _ => return Err(error(&reader, String::from("expected ',' or ']'"))),
+                }
+            }
+        }
+        properties = Some(props);
+    }
+
+    reader.skip_whitespace();
+    let nbt = match reader.peek() {
+        Some('{') => Some(reader.read_compound(0)?),
+        _ => None,
+    };
+
+    reader.skip_whitespace();
+    if !reader.is_end() {
+        return Err(error(
+            &reader,
+            String::from("unexpected characters after the block"),
+        ));
+    }
+
+    let block = Block {
+        name: name.into(),
+        properties,
+    };
+    Ok((block, nbt))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(input: &str) -> NbtCompound {
+        Reader::new(input).read_compound(0).unwrap()
+    }
+
+    #[test]
+    fn values() {
+        let nbt = parse(
+            r#"{byte: 1b, short: -3s, int: 42, long: 9L, float: 1.5f, double: 2.5, exp: 1e3,
+                flag: true, text: "a \"quoted\" string", single: 'it\'s', bare: minecraft.stone,
+                "quoted key": 1}"#,
+        );
+
+        assert_eq!(nbt.byte("byte"), Some(1));
+        assert_eq!(nbt.short("short"), Some(-3));
+        assert_eq!(nbt.int("int"), Some(42));
+        assert_eq!(nbt.long("long"), Some(9));
+        assert_eq!(nbt.float("float"), Some(1.5));
+        assert_eq!(nbt.double("double"), Some(2.5));
+        assert_eq!(nbt.double("exp"), Some(1000.0));
+        assert_eq!(nbt.byte("flag"), Some(1));
+        assert_eq!(nbt.string("text").unwrap().to_str(), r#"a "quoted" string"#);
+        assert_eq!(nbt.string("single").unwrap().to_str(), "it's");
+        assert_eq!(nbt.string("bare").unwrap().to_str(), "minecraft.stone");
+        assert_eq!(nbt.int("quoted key"), Some(1));
+    }
+
+    #[test]
+    fn lists_and_arrays() {
+        let nbt = parse(
+            "{list: [1, 2, 3], empty: [], items: [{id: a}, {id: b}], bytes: [B; 1b, -2b], ints: [I; 1, 2], longs: [L; 5L]}",
+        );
+
+        assert!(matches!(nbt.list("list"), Some(NbtList::Int(l)) if l == &vec![1, 2, 3]));
+        assert!(matches!(nbt.list("empty"), Some(NbtList::Empty)));
+        assert_eq!(nbt.list("items").unwrap().compounds().unwrap().len(), 2);
+        assert_eq!(nbt.byte_array("bytes").unwrap(), &[1, 254]);
+        assert_eq!(nbt.int_array("ints").unwrap(), &[1, 2]);
+        assert_eq!(nbt.long_array("longs").unwrap(), &[5]);
+    }
+
+    #[test]
+    fn errors() {
+        for invalid in [
+            "{a: 1",
+            "{a 1}",
+            "{a: [1, 2b]}",
+            "{a: [I; 1, b]}",
+            "{a: \"unterminated}",
+            "{: 1}",
+        ] {
+            let result = Reader::new(invalid).read_compound(0);
+            assert!(
+                matches!(result, Err(Error::InvalidSnbt { .. })),
+                "{invalid}"
+            );
+        }
+    }
+
+    #[test]
+    fn nesting_depth() -> Result<()> {
+        // the root compound & 511 lists within it are fine
+        let nested = format!("{{a: {}{}}}", "[".repeat(511), "]".repeat(511));
+        assert!(parse_snbt(&nested).is_ok());
+
+        let nested = format!("{{a: {}{}}}", "[".repeat(512), "]".repeat(512));
+        assert!(matches!(
+            parse_snbt(&nested),
+            Err(Error::InvalidSnbt { .. })
+        ));
+
+        // would overflow the stack without a limit
+        let nested = format!("{{a: {}", "{a: [".repeat(100_000));
+        assert!(matches!(
+            parse_snbt(&nested),
+            Err(Error::InvalidSnbt { .. })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn block_states() -> Result<()> {
+        let (block, nbt) = parse_block_state("oak_stairs[facing=east, half=top]")?;
+        assert_eq!(
+            block,
+            Block::new_with_props("oak_stairs", [("facing", "east"), ("half", "top")])
+        );
+        assert!(nbt.is_none());
+
+        let (block, nbt) = parse_block_state("minecraft:chest[facing=north]{Lock: \"key\"}")?;
+        assert_eq!(block.name, "minecraft:chest");
+        assert_eq!(nbt.unwrap().string("Lock").unwrap().to_str(), "key");
+
+        let (block, _) = parse_block_state("mymod:machine/part")?;
+        assert_eq!(block.properties, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn block_state_errors() {
+        for invalid in [
+            "",
+            "[facing=east]",
+            "a:b:c",
+            "stairs[facing]",
+            "stairs[facing=]",
+            "stairs[facing=east",
+            "stairs[facing=east,facing=west]",
+            "stone extra",
+        ] {
+            let result = parse_block_state(invalid);
+            assert!(
+                matches!(result, Err(Error::InvalidBlockState { .. })),
+                "{invalid}"
+            );
+        }
+    }
+
+    #[test]
+    fn print() -> Result<()> {
+        let nbt = parse_snbt(
+            r#"{b: 1b, s: 2s, i: 3, l: 4L, f: 0.5f, d: 1.0, big: 1.5e10, quote: 'say "hi"',
+                "with space": [], list: [1b, 2b], strings: ["a", 'b'], bytes: [B; 1B, -1B],
+                ints: [I; 1, 2], longs: [L; 3L], nested: {empty: {}}}"#,
+        )?;
+
+        assert_eq!(
+            to_snbt(&nbt),
+            r#"{b: 1b, s: 2s, i: 3, l: 4L, f: 0.5f, d: 1.0d, big: 1.5E10d, quote: 'say "hi"', "with space": [], list: [1b, 2b], strings: ["a", "b"], bytes: [B; 1B, -1B], ints: [I; 1, 2], longs: [L; 3L], nested: {empty: {}}}"#
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn print_pretty() -> Result<()> {
+        let nbt = parse_snbt("{id: chest, Items: [{Slot: 0b}, {Slot: 1b}], pos: [I; 1, 2, 3]}")?;
+        assert_eq!(
+            to_snbt_pretty(&nbt),
+            "{\n    id: \"chest\",\n    Items: [\n        {\n            Slot: 0b\n        },\n        {\n            Slot: 1b\n        }\n    ],\n    pos: [I; 1, 2, 3]\n}"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip() -> Result<()> {
+        let input =
+            r#"{a: 1b, b: [0.1f, 1.0E-5f], c: "it's \\ \"x\"", d: [[1, 2], [3]], e: [L; -9L]}"#;
+        let nbt = parse_snbt(input)?;
+        assert_eq!(parse_snbt(&to_snbt(&nbt))?, nbt);
+        assert_eq!(parse_snbt(&to_snbt_pretty(&nbt))?, nbt);
+
+        assert!(parse_snbt("{a: 1} trailing").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn chunk_to_snbt() {
+        let region = crate::Region::default();
+        let chunk = region.get_chunk(0, 0).unwrap().unwrap();
+        let snbt = chunk.to_snbt();
+
+        assert!(snbt.contains(r#"Status: "minecraft:full""#));
+        assert_eq!(parse_snbt(&snbt).unwrap(), chunk.nbt);
+    }
+}
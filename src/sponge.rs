@@ -0,0 +1,631 @@
+//! `sponge` reads & writes Sponge schematics (`.schem`), the format WorldEdit uses, as a [`Clipboard`].
+//!
+//! Both version 2 & 3 of the [specification](https://github.com/SpongePowered/Schematic-Specification) are supported.
+
+use crate::{
+    BiomeCell, Block, Clipboard, Error, NbtString, Result,
+    clipboard::schematic_volume,
+    nbt::{compounds, read_gzip_nbt, set_tag, write_file, write_gzip_nbt},
+};
+use ahash::AHashMap;
+use simdnbt::owned::{NbtCompound, NbtList, NbtTag};
+use std::{
+    fs::File,
+    io::{BufReader, Read, Write},
+    path::Path,
+};
+
+/// Which version of the Sponge schematic format to write.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SpongeVersion {
+    /// Version 2, biomes are only stored per column.
+    V2,
+    /// Version 3, used by WorldEdit since Minecraft 1.20.
+    #[default]
+    V3,
+}
+
+/// Tags of a chunk block entity that schematics store outside of it's data.
+const BLOCK_ENTITY_TAGS: [&str; 5] = ["id", "x", "y", "z", "keepPacked"];
+
+impl Clipboard {
+    /// Reads a gzip-compressed Sponge schematic from the given reader.
+    pub fn read_schem<R: Read>(reader: &mut R) -> Result<Self> {
+        let nbt = read_gzip_nbt(reader)?;
+
+        Self::from_schem_nbt(&nbt)
+    }
+
+    /// Reads a Sponge schematic from the given path.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// # use silverfish::{Clipboard, PasteOptions, Region};
+    /// # let mut region = Region::default();
+    /// let house = Clipboard::open_schem("schematics/house.schem")?;
+    /// house.paste(&mut region, (100, 64, 100), PasteOptions::default())?;
+    /// region.write_blocks()?;
+    /// region.write_biomes()?;
+    /// # Ok::<(), silverfish::Error>(())
+    /// ```
+    pub fn open_schem<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::read_schem(&mut BufReader::new(File::open(path)?))
+    }
+
+    /// Creates a [`Clipboard`] from the root compound of a Sponge schematic.
+    pub fn from_schem_nbt(nbt: &NbtCompound) -> Result<Self> {
+        // version 3 wraps everything within a `Schematic` compound
+        let schematic = nbt.compound("Schematic").unwrap_or(nbt);
+        let version = schematic
+            .int("Version")
+            .ok_or(Error::MissingNbtTag("Version"))?;
+
+        let size = (
+            schematic
+                .short("Width")
+                .ok_or(Error::MissingNbtTag("Width"))? as u16 as u32,
+            schematic
+                .short("Height")
+                .ok_or(Error::MissingNbtTag("Height"))? as u16 as u32,
+            schematic
+                .short("Length")
+                .ok_or(Error::MissingNbtTag("Length"))? as u16 as u32,
+        );
+        let volume = schematic_volume(size)?;
+
+        let (palette, data, block_entities) = match version {
+            2 => (
+                schematic.compound("Palette"),
+                schematic.byte_array("BlockData"),
+                schematic.list("BlockEntities"),
+            ),
+            3 => {
+                let blocks = schematic
+                    .compound("Blocks")
+                    .ok_or(Error::MissingNbtTag("Blocks"))?;
+                (
+                    blocks.compound("Palette"),
+                    blocks.byte_array("Data"),
+                    blocks.list("BlockEntities"),
+                )
+            }
+            _ => return Err(Error::UnsupportedSchematicVersion(version)),
+        };
+
+        let palette = read_palette(palette.ok_or(Error::MissingNbtTag("Palette"))?, |s| {
+            s.parse::<Block>()
+        })?;
+        let blocks = read_varints(data.ok_or(Error::MissingNbtTag("BlockData"))?, volume)?;
+        if let Some(index) = blocks.iter().find(|i| **i as usize >= palette.len()) {
+            return Err(Error::InvalidPaletteIndex(*index as i64));
+        }
+
+        let mut clipboard = Clipboard {
+            size,
+            data_version: schematic
+                .int("DataVersion")
+                .ok_or(Error::MissingNbtTag("DataVersion"))?,
+            palette,
+            blocks,
+            biomes: vec![],
+            block_entities: AHashMap::new(),
+        };
+
+        clipboard.read_schem_block_entities(block_entities, version)?;
+        clipboard.read_schem_biomes(schematic, version)?;
+
+        Ok(clipboard)
+    }
+
+    fn read_schem_block_entities(&mut self, list: Option<&NbtList>, version: i32) -> Result<()> {
+        for entry in compounds(list, "BlockEntities")? {
+            let (x, y, z) = match entry.int_array("Pos").ok_or(Error::MissingNbtTag("Pos"))? {
+                [x, y, z] => (*x, *y, *z),
+                _ => return Err(Error::InvalidNbtType("Pos")),
+            };
+            let id = entry.string("Id").ok_or(Error::MissingNbtTag("Id"))?;
+
+            // version 2 has the data next to `Pos` & `Id`, version 3 in a `Data` compound
+            let mut block_entity = match version {
+                2 => {
+                    let mut data = entry.clone();
+                    data.remove("Pos");
+                    data.remove("Id");
+                    data
+                }
+                _ => entry
+                    .compound("Data")
+                    .cloned()
+                    .unwrap_or_else(NbtCompound::new),
+            };
+            set_tag(
+                &mut block_entity,
+                "id",
+                NbtTag::String(id.to_str().as_ref().into()),
+            );
+            set_tag(&mut block_entity, "x", NbtTag::Int(x));
+            set_tag(&mut block_entity, "y", NbtTag::Int(y));
+            set_tag(&mut block_entity, "z", NbtTag::Int(z));
+
+            self.block_entities
+                .insert((x as u32, y as u32, z as u32), block_entity);
+        }
+
+        Ok(())
+    }
+
+    fn read_schem_biomes(&mut self, schematic: &NbtCompound, version: i32) -> Result<()> {
+        let (palette, data) = match version {
+            2 => (
+                schematic.compound("BiomePalette"),
+                schematic.byte_array("BiomeData"),
+            ),
+            _ => match schematic.compound("Biomes") {
+                Some(biomes) => (biomes.compound("Palette"), biomes.byte_array("Data")),
+                None => (None, None),
+            },
+        };
+        let (Some(palette), Some(data)) = (palette, data) else {
+            return Ok(());
+        };
+
+        let palette = read_palette(palette, NbtString::from_str)?;
+        // version 2 only has biomes per column
+        let height = if version == 2 { 1 } else { self.size.1 };
+        let biomes = read_varints(
+            data,
+            self.size.0 as usize * height as usize * self.size.2 as usize,
+        )?;
+
+        let cell_size = BiomeCell::CELL_SIZE as u32;
+        let cells = self.biome_size();
+        let mut cell_biomes = Vec::with_capacity((cells.0 * cells.1 * cells.2) as usize);
+        for y in 0..cells.1 {
+            for z in 0..cells.2 {
+                for x in 0..cells.0 {
+                    let y = if version == 2 { 0 } else { y * cell_size };
+                    let index =
+                        x * cell_size + z * cell_size * self.size.0 + y * self.size.0 * self.size.2;
+                    let biome = biomes[index as usize];
+                    cell_biomes.push(
+                        palette
+                            .get(biome as usize)
+                            .ok_or(Error::InvalidPaletteIndex(biome as i64))?
+                            .clone(),
+                    );
+                }
+            }
+        }
+        self.biomes = cell_biomes;
+
+        Ok(())
+    }
+
+    /// Writes the clipboard as a gzip-compressed Sponge schematic to the given writer.
+    pub fn write_schem<W: Write>(&self, writer: &mut W, version: SpongeVersion) -> Result<()> {
+        let name = match version {
+            SpongeVersion::V2 => "Schematic",
+            SpongeVersion::V3 => "",
+        };
+        write_gzip_nbt(writer, name, self.to_schem_nbt(version)?)
+    }
+
+    /// Writes the clipboard as a Sponge schematic to the given path.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// # use silverfish::{Clipboard, Region, SpongeVersion};
+    /// # let region = Region::default();
+    /// let house = Clipboard::copy(&region, (100, 64, 100), (120, 80, 115))?;
+    /// house.save_schem("schematics/house.schem", SpongeVersion::V3)?;
+    /// # Ok::<(), silverfish::Error>(())
+    /// ```
+    pub fn save_schem<P: AsRef<Path>>(&self, path: P, version: SpongeVersion) -> Result<()> {
+        write_file(path.as_ref(), |writer| self.write_schem(writer, version))
+    }
+
+    /// Converts the clipboard into the root compound of a Sponge schematic.
+    pub fn to_schem_nbt(&self, version: SpongeVersion) -> Result<NbtCompound> {
+        // the sizes are unsigned, but stored in the bits of a signed short
+        let side = |len: u32| {
+            u16::try_from(len).map(|len| len as i16).map_err(|_| {
+                Error::InvalidSchematic(format!("the size {:?} is too large", self.size))
+            })
+        };
+        let size = (side(self.size.0)?, side(self.size.1)?, side(self.size.2)?);
+
+        let mut schematic = NbtCompound::new();
+        let version_number = match version {
+            SpongeVersion::V2 => 2,
+            SpongeVersion::V3 => 3,
+        };
+        set_tag(&mut schematic, "Version", NbtTag::Int(version_number));
+        set_tag(
+            &mut schematic,
+            "DataVersion",
+            NbtTag::Int(self.data_version),
+        );
+        set_tag(&mut schematic, "Width", NbtTag::Short(size.0));
+        set_tag(&mut schematic, "Height", NbtTag::Short(size.1));
+        set_tag(&mut schematic, "Length", NbtTag::Short(size.2));
+        set_tag(&mut schematic, "Offset", NbtTag::IntArray(vec![0, 0, 0]));
+
+        let palette = write_palette(self.palette.iter().map(|b| b.to_string()));
+        let mut data = Vec::with_capacity(self.blocks.len());
+        for block in &self.blocks {
+            write_varint(&mut data, *block);
+        }
+        let block_entities = NbtList::Compound(self.schem_block_entities(version));
+
+        match version {
+            SpongeVersion::V2 => {
+                set_tag(
+                    &mut schematic,
+                    "PaletteMax",
+                    NbtTag::Int(self.palette.len() as i32),
+                );
+                set_tag(&mut schematic, "Palette", NbtTag::Compound(palette));
+                set_tag(&mut schematic, "BlockData", NbtTag::ByteArray(data));
+                set_tag(
+                    &mut schematic,
+                    "BlockEntities",
+                    NbtTag::List(block_entities),
+                );
+            }
+            SpongeVersion::V3 => {
+                let mut blocks = NbtCompound::new();
+                set_tag(&mut blocks, "Palette", NbtTag::Compound(palette));
+                set_tag(&mut blocks, "Data", NbtTag::ByteArray(data));
+                set_tag(&mut blocks, "BlockEntities", NbtTag::List(block_entities));
+                set_tag(&mut schematic, "Blocks", NbtTag::Compound(blocks));
+            }
+        }
+
+        if !self.biomes.is_empty() {
+            self.write_schem_biomes(&mut schematic, version);
+        }
+
+        Ok(match version {
+            SpongeVersion::V2 => schematic,
+            SpongeVersion::V3 => {
+                NbtCompound::from_values(vec![("Schematic".into(), NbtTag::Compound(schematic))])
+            }
+        })
+    }
+
+    fn schem_block_entities(&self, version: SpongeVersion) -> Vec<NbtCompound> {
+        let mut block_entities: Vec<_> = self.block_entities.iter().collect();
+        // keeps the output the same between runs
+        block_entities.sort_by_key(|((x, y, z), _)| (*y, *z, *x));
+
+        block_entities
+            .into_iter()
+            .map(|((x, y, z), block_entity)| {
+                let mut data = block_entity.clone();
+                for tag in BLOCK_ENTITY_TAGS {
+                    data.remove(tag);
+                }
+
+                let mut entry = match version {
+                    SpongeVersion::V2 => data,
+                    SpongeVersion::V3 => {
+                        NbtCompound::from_values(vec![("Data".into(), NbtTag::Compound(data))])
+                    }
+                };
+                set_tag(
+                    &mut entry,
+                    "Pos",
+                    NbtTag::IntArray(vec![*x as i32, *y as i32, *z as i32]),
+                );
+                if let Some(id) = block_entity.string("id") {
+                    set_tag(
+                        &mut entry,
+                        "Id",
+                        NbtTag::String(id.to_str().as_ref().into()),
+                    );
+                }
+
+                entry
+            })
+            .collect()
+    }
+
+    fn write_schem_biomes(&self, schematic: &mut NbtCompound, version: SpongeVersion) {
+        let mut palette: Vec<&NbtString> = vec![];
+        let mut lookup: AHashMap<&NbtString, u32> = AHashMap::new();
+        let mut data = vec![];
+
+        // version 2 only has biomes per column, so only the bottom layer is written
+        let height = match version {
+            SpongeVersion::V2 => 1,
+            SpongeVersion::V3 => self.size.1,
+        };
+        for y in 0..height {
+            for z in 0..self.size.2 {
+                for x in 0..self.size.0 {
+                    let Some(biome) = self.get_biome((x, y, z)) else {
+                        continue;
+                    };
+                    let index = *lookup.entry(biome).or_insert_with(|| {
+                        palette.push(biome);
+                        palette.len() as u32 - 1
+                    });
+                    write_varint(&mut data, index);
+                }
+            }
+        }
+
+        let palette = write_palette(palette.into_iter().map(|b| b.to_string()));
+        match version {
+            SpongeVersion::V2 => {
+                set_tag(
+                    schematic,
+                    "BiomePaletteMax",
+                    NbtTag::Int(lookup.len() as i32),
+                );
+                set_tag(schematic, "BiomePalette", NbtTag::Compound(palette));
+                set_tag(schematic, "BiomeData", NbtTag::ByteArray(data));
+            }
+            SpongeVersion::V3 => {
+                let mut biomes = NbtCompound::new();
+                set_tag(&mut biomes, "Palette", NbtTag::Compound(palette));
+                set_tag(&mut biomes, "Data", NbtTag::ByteArray(data));
+                set_tag(schematic, "Biomes", NbtTag::Compound(biomes));
+            }
+        }
+    }
+}
+
+/// Reads a palette of `{"<value>": <index>}` into a list ordered by index.
+fn read_palette<T>(palette: &NbtCompound, parse: impl Fn(&str) -> Result<T>) -> Result<Vec<T>> {
+    let mut entries: Vec<Option<T>> = (0..palette.len()).map(|_| None).collect();
+    for (key, index) in palette.iter() {
+        let index = index.int().ok_or(Error::InvalidNbtType("Palette"))?;
+        let entry = entries
+            .get_mut(index as usize)
+            .filter(|e| e.is_none())
+            .ok_or(Error::InvalidPaletteIndex(index as i64))?;
+        *entry = Some(parse(&key.to_str())?);
+    }
+
+    // every slot is filled since there are as many unique indexes as slots
+    Ok(entries.into_iter().flatten().collect())
+}
+
+fn write_palette(values: impl Iterator<Item = String>) -> NbtCompound {
+    NbtCompound::from_values(
+        values
+            .enumerate()
+            .map(|(i, value)| (value.as_str().into(), NbtTag::Int(i as i32)))
+            .collect(),
+    )
+}
+
+/// Reads `len` varints, each value is stored 7 bits at a time with the top bit set if more bytes follow.
+fn read_varints(data: &[u8], len: usize) -> Result<Vec<u32>> {
+    // every value takes up atleast a byte, so a bogus `len` can't allocate more than the data
+    let mut values = Vec::with_capacity(len.min(data.len()));
+    let (mut value, mut shift) = (0u32, 0);
+    for byte in data {
+        value |= ((byte & 0x7F) as u32) << shift;
+        if byte & 0x80 == 0 {
+            values.push(value);
+            (value, shift) = (0, 0);
+        } else {
+            shift += 7;
+            if shift > 28 {
+                return Err(Error::InvalidSchematic(String::from("varint is too long")));
+            }
+        }
+    }
+
+    if values.len() != len || shift != 0 {
+        return Err(Error::InvalidSchematic(format!(
+            "expected {len} entries but found {}",
+            values.len()
+        )));
+    }
+
+    Ok(values)
+}
+
+fn write_varint(data: &mut Vec<u8>, mut value: u32) {
+    while value >= 0x80 {
+        data.push((value & 0x7F) as u8 | 0x80);
+        value >>= 7;
+    }
+    data.push(value as u8);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{PasteOptions, Region, clipboard::test_region};
+
+    fn clipboard() -> Result<Clipboard> {
+        Clipboard::copy(&test_region()?, (0, 0, 0), (5, 2, 3))
+    }
+
+    #[test]
+    fn varints() -> Result<()> {
+        let values = [0, 1, 127, 128, 300, 16384, u32::MAX >> 4];
+        let mut data = vec![];
+        for value in values {
+            write_varint(&mut data, value);
+        }
+        assert_eq!(&data[..5], &[0, 1, 127, 0x80, 0x01]);
+        assert_eq!(read_varints(&data, values.len())?, values);
+        assert!(read_varints(&data, values.len() + 1).is_err());
+        assert!(read_varints(&[0x80], 1).is_err());
+        // a bogus length from the file doesn't allocate more than the data
+        assert!(read_varints(&data, usize::MAX).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_size() -> Result<()> {
+        for (width, height) in [(0, 3), (-1, -1)] {
+            let mut nbt = clipboard()?.to_schem_nbt(SpongeVersion::V3)?;
+            let schematic = nbt.compound_mut("Schematic").unwrap();
+            set_tag(schematic, "Width", NbtTag::Short(width));
+            set_tag(schematic, "Height", NbtTag::Short(height));
+            set_tag(schematic, "Length", NbtTag::Short(-1));
+
+            assert!(matches!(
+                Clipboard::from_schem_nbt(&nbt),
+                Err(Error::InvalidSchematic(_))
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn write_large_size() -> Result<()> {
+        let mut clipboard = Clipboard {
+            size: (40_000, 1, 1),
+            data_version: Region::MIN_DATA_VERSION,
+            palette: vec![Block::new("minecraft:stone")],
+            blocks: vec![0; 40_000],
+            biomes: vec![],
+            block_entities: AHashMap::new(),
+        };
+        let nbt = clipboard.to_schem_nbt(SpongeVersion::V3)?;
+        let schematic = nbt.compound("Schematic").unwrap();
+        assert_eq!(schematic.short("Width"), Some(40_000u16 as i16));
+        assert_eq!(Clipboard::from_schem_nbt(&nbt)?.size(), (40_000, 1, 1));
+
+        clipboard.size = (70_000, 1, 1);
+        assert!(matches!(
+            clipboard.to_schem_nbt(SpongeVersion::V3),
+            Err(Error::InvalidSchematic(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip() -> Result<()> {
+        let clipboard = clipboard()?;
+
+        for version in [SpongeVersion::V2, SpongeVersion::V3] {
+            let mut bytes = vec![];
+            clipboard.write_schem(&mut bytes, version)?;
+            let read = Clipboard::read_schem(&mut bytes.as_slice())?;
+
+            assert_eq!(read.size(), (6, 3, 4));
+            assert_eq!(read.data_version(), clipboard.data_version());
+            assert_eq!(
+                read.get_block((0, 0, 0)).unwrap().to_string(),
+                "minecraft:oak_stairs[facing=east,half=top]"
+            );
+            assert_eq!(
+                read.get_block((1, 1, 1)).unwrap().to_string(),
+                "minecraft:chest"
+            );
+            assert_eq!(
+                read.get_block((5, 2, 3)).unwrap().to_string(),
+                "minecraft:air"
+            );
+            assert_eq!(*read.get_biome((4, 0, 0)).unwrap(), "minecraft:desert");
+            assert_eq!(*read.get_biome((0, 0, 0)).unwrap(), "minecraft:plains");
+
+            let chest = read.get_block_entity((1, 1, 1)).unwrap();
+            assert_eq!(chest.string("id").unwrap().to_str(), "minecraft:chest");
+            assert_eq!(chest.string("CustomName").unwrap().to_str(), "\"Loot\"");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn layout() -> Result<()> {
+        let nbt = clipboard()?.to_schem_nbt(SpongeVersion::V3)?;
+        let schematic = nbt.compound("Schematic").unwrap();
+        assert_eq!(schematic.int("Version"), Some(3));
+        assert_eq!(schematic.short("Width"), Some(6));
+
+        let blocks = schematic.compound("Blocks").unwrap();
+        let palette = blocks.compound("Palette").unwrap();
+        assert_eq!(palette.int("minecraft:air"), Some(0));
+        assert_eq!(blocks.byte_array("Data").unwrap().len(), 6 * 3 * 4);
+
+        let chest = &blocks.list("BlockEntities").unwrap().compounds().unwrap()[0];
+        assert_eq!(chest.int_array("Pos"), Some(&[1, 1, 1][..]));
+        assert!(chest.compound("Data").unwrap().int("x").is_none());
+
+        let v2 = clipboard()?.to_schem_nbt(SpongeVersion::V2)?;
+        assert_eq!(v2.int("Version"), Some(2));
+        assert_eq!(v2.byte_array("BiomeData").unwrap().len(), 6 * 4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn versions() -> Result<()> {
+        let mut region = test_region()?;
+        region.set_biome((0, 4, 0), "minecraft:badlands")?;
+        region.write_biomes()?;
+        let clipboard = Clipboard::copy(&region, (0, 0, 0), (5, 5, 3))?;
+
+        let v2 = clipboard.to_schem_nbt(SpongeVersion::V2)?;
+        let chest = &v2.list("BlockEntities").unwrap().compounds().unwrap()[0];
+        assert_eq!(chest.string("Id").unwrap().to_str(), "minecraft:chest");
+        assert_eq!(chest.string("CustomName").unwrap().to_str(), "\"Loot\"");
+        assert!(chest.compound("Data").is_none());
+        // every column gets the biome of it's bottom cell
+        let read = Clipboard::from_schem_nbt(&v2)?;
+        assert_eq!(*read.get_biome((0, 4, 0)).unwrap(), "minecraft:plains");
+        assert_eq!(*read.get_biome((4, 4, 0)).unwrap(), "minecraft:desert");
+
+        let v3 = clipboard.to_schem_nbt(SpongeVersion::V3)?;
+        let blocks = v3
+            .compound("Schematic")
+            .unwrap()
+            .compound("Blocks")
+            .unwrap();
+        let chest = &blocks.list("BlockEntities").unwrap().compounds().unwrap()[0];
+        assert!(chest.string("CustomName").is_none());
+        let data = chest.compound("Data").unwrap();
+        assert_eq!(data.string("CustomName").unwrap().to_str(), "\"Loot\"");
+        let read = Clipboard::from_schem_nbt(&v3)?;
+        assert_eq!(*read.get_biome((0, 4, 0)).unwrap(), "minecraft:badlands");
+        assert_eq!(*read.get_biome((4, 4, 0)).unwrap(), "minecraft:plains");
+
+        Ok(())
+    }
+
+    #[test]
+    fn paste_schematic() -> Result<()> {
+        let mut bytes = vec![];
+        clipboard()?.write_schem(&mut bytes, SpongeVersion::V3)?;
+        let schematic = Clipboard::read_schem(&mut bytes.as_slice())?;
+
+        let mut region = Region::default();
+        schematic.paste(&mut region, (100, 10, 100), PasteOptions::default())?;
+        region.write_blocks()?;
+        region.write_biomes()?;
+
+        assert_eq!(
+            region.get_block((100, 10, 100))?,
+            Block::new_with_props(
+                "minecraft:oak_stairs",
+                [("facing", "east"), ("half", "top")]
+            )
+        );
+        assert!(region.get_block_entity((101, 11, 101))?.is_some());
+        assert_eq!(region.get_biome((104, 10, 100))?, "minecraft:desert");
+
+        Ok(())
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let nbt = NbtCompound::from_values(vec![("Version".into(), NbtTag::Int(1))]);
+        assert!(matches!(
+            Clipboard::from_schem_nbt(&nbt),
+            Err(Error::UnsupportedSchematicVersion(1))
+        ));
+    }
+}
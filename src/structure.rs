@@ -0,0 +1,847 @@
+//! `structure` reads & writes vanilla structure templates (`.nbt`), the files saved by structure blocks
+//! and used by datapacks in `data/<namespace>/structure`.
+
+use crate::{
+    BLOCKS_PER_REGION, Block, Clipboard, Coords, Entity, EntityRegion, Error, Mirror, PasteOptions,
+    Region, Result, Rotation,
+    clipboard::{rotated_size, transform_xz},
+    nbt::{compounds, read_gzip_nbt, set_tag, write_file, write_gzip_nbt},
+    transform::transform_direction,
+};
+use simdnbt::owned::{NbtCompound, NbtList, NbtTag};
+use std::{
+    fs::File,
+    io::{BufReader, Read, Write},
+    path::Path,
+};
+
+/// A vanilla structure template.
+///
+/// Any position without a block in [`StructureTemplate::blocks`] is a structure void,
+/// which leaves the block that's already there when placed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructureTemplate {
+    /// The `DataVersion` the structure was saved in
+    pub data_version: i32,
+    /// The size of the structure as `(x, y, z)`
+    pub size: (u32, u32, u32),
+    /// The block palettes, most structures only have one but some like shipwrecks have variants to pick between
+    pub palettes: Vec<Vec<Block>>,
+    /// The blocks within the structure
+    pub blocks: Vec<StructureBlock>,
+    /// The entities within the structure
+    pub entities: Vec<StructureEntity>,
+}
+
+/// A block within a [`StructureTemplate`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructureBlock {
+    /// The position relative to the structure's smallest corner
+    pub pos: (u32, u32, u32),
+    /// The index of the block within the palette
+    pub state: u32,
+    /// The block entity, without the `x`, `y` & `z` tags
+    pub nbt: Option<NbtCompound>,
+}
+
+/// An entity within a [`StructureTemplate`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructureEntity {
+    /// The exact position relative to the structure's smallest corner
+    pub pos: (f64, f64, f64),
+    /// The block position relative to the structure's smallest corner, can be outside of the size
+    pub block_pos: (i32, i32, i32),
+    /// The entity's NBT
+    pub nbt: NbtCompound,
+}
+
+/// The direction of the 3D `Facing` of item frames, by index.
+const FACING_3D: [&str; 6] = ["down", "up", "north", "south", "west", "east"];
+/// The direction of the 2D `facing` of paintings, by index.
+const FACING_2D: [&str; 4] = ["south", "west", "north", "east"];
+
+impl StructureTemplate {
+    /// Reads a gzip-compressed structure template from the given reader.
+    pub fn read<R: Read>(reader: &mut R) -> Result<Self> {
+        let nbt = read_gzip_nbt(reader)?;
+
+        Self::from_nbt(&nbt)
+    }
+
+    /// Reads a structure template from the given path.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// # use silverfish::{PasteOptions, Region, StructureTemplate};
+    /// # let mut region = Region::default();
+    /// let tower = StructureTemplate::open("generated/minecraft/structures/tower.nbt")?;
+    /// tower.place(&mut region, (40, 64, 40), PasteOptions::default())?;
+    /// region.write_blocks()?;
+    /// # Ok::<(), silverfish::Error>(())
+    /// ```
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::read(&mut BufReader::new(File::open(path)?))
+    }
+
+    /// Creates a [`StructureTemplate`] from it's root compound.
+    pub fn from_nbt(nbt: &NbtCompound) -> Result<Self> {
+        let size = match nbt.list("size").and_then(|l| l.ints()) {
+            Some([x, y, z]) if *x >= 0 && *y >= 0 && *z >= 0 => (*x as u32, *y as u32, *z as u32),
+            Some([x, y, z]) => {
+                return Err(Error::InvalidSchematic(format!(
+                    "the size {:?} is negative",
+                    (x, y, z)
+                )));
+            }
+            Some(_) => return Err(Error::InvalidNbtType("size")),
+            None => return Err(Error::MissingNbtTag("size")),
+        };
+
+        let palettes = match nbt.list("palettes") {
+            Some(NbtList::List(palettes)) => palettes
+                .iter()
+                .map(read_palette)
+                .collect::<Result<Vec<_>>>()?,
+            Some(_) => return Err(Error::InvalidNbtList("palettes")),
+            None => vec![read_palette(
+                nbt.list("palette").ok_or(Error::MissingNbtTag("palette"))?,
+            )?],
+        };
+
+        let mut blocks = vec![];
+        for block in compounds(nbt.list("blocks"), "blocks")? {
+            let pos = match block.list("pos").and_then(|l| l.ints()) {
+                Some([x, y, z]) => position_within((*x, *y, *z), size)?,
+                _ => return Err(Error::MissingNbtTag("pos")),
+            };
+            let state = block.int("state").ok_or(Error::MissingNbtTag("state"))?;
+            if palettes.iter().any(|p| state as usize >= p.len()) {
+                return Err(Error::InvalidPaletteIndex(state as i64));
+            }
+
+            blocks.push(StructureBlock {
+                pos,
+                state: state as u32,
+                nbt: block.compound("nbt").cloned(),
+            });
+        }
+
+        let mut entities = vec![];
+        for entity in compounds(nbt.list("entities"), "entities")? {
+            let pos = match entity.list("pos").and_then(|l| l.doubles()) {
+                Some([x, y, z]) => (*x, *y, *z),
+                _ => return Err(Error::MissingNbtTag("pos")),
+            };
+            // vanilla collects entities by their bounding box, so the block they're in can be outside the size
+            let block_pos = match entity.list("blockPos").and_then(|l| l.ints()) {
+                Some([x, y, z]) => (*x, *y, *z),
+                _ => return Err(Error::MissingNbtTag("blockPos")),
+            };
+
+            entities.push(StructureEntity {
+                pos,
+                block_pos,
+                nbt: entity
+                    .compound("nbt")
+                    .ok_or(Error::MissingNbtTag("nbt"))?
+                    .clone(),
+            });
+        }
+
+        Ok(Self {
+            data_version: nbt
+                .int("DataVersion")
+                .ok_or(Error::MissingNbtTag("DataVersion"))?,
+            size,
+            palettes,
+            blocks,
+            entities,
+        })
+    }
+
+    /// Creates a [`StructureTemplate`] from the cuboid between two corners *(inclusive & local to within the region)*.
+    ///
+    /// ## Example
+    /// ```
+    /// # use silverfish::{Region, StructureTemplate};
+    /// # let region = Region::default();
+    /// let structure = StructureTemplate::from_region(&region, (10, 64, 10), (14, 68, 14))?;
+    /// assert_eq!(structure.size, (5, 5, 5));
+    /// # Ok::<(), silverfish::Error>(())
+    /// ```
+    pub fn from_region<C: Into<Coords>>(region: &Region, from: C, to: C) -> Result<Self> {
+        Ok(Self::from_clipboard(&Clipboard::copy(region, from, to)?))
+    }
+
+    /// Creates a [`StructureTemplate`] from the blocks & block entities in a [`Clipboard`].
+    ///
+    /// Structure voids in the clipboard are left out, just like when saving with a structure block.
+    pub fn from_clipboard(clipboard: &Clipboard) -> Self {
+        let (width, height, length) = clipboard.size;
+        let mut blocks = vec![];
+        for y in 0..height {
+            for z in 0..length {
+                for x in 0..width {
+                    let state = clipboard.blocks[clipboard.index((x, y, z))];
+                    if clipboard.palette[state as usize]
+                        .name
+                        .into_cow_namespaced()
+                        .to_str()
+                        == "minecraft:structure_void"
+                    {
+                        continue;
+                    }
+
+                    let nbt = clipboard.block_entities.get(&(x, y, z)).map(|be| {
+                        let mut be = be.clone();
+                        for tag in ["x", "y", "z"] {
+                            be.remove(tag);
+                        }
+                        be
+                    });
+                    blocks.push(StructureBlock {
+                        pos: (x, y, z),
+                        state,
+                        nbt,
+                    });
+                }
+            }
+        }
+
+        Self {
+            data_version: clipboard.data_version,
+            size: clipboard.size,
+            palettes: vec![clipboard.palette.clone()],
+            blocks,
+            entities: vec![],
+        }
+    }
+
+    /// Converts the structure into it's root compound.
+    ///
+    /// The palette entries are written with [`Block::to_compound`].
+    pub fn to_nbt(&self) -> Result<NbtCompound> {
+        let mut nbt = NbtCompound::new();
+        set_tag(&mut nbt, "DataVersion", NbtTag::Int(self.data_version));
+        set_tag(
+            &mut nbt,
+            "size",
+            NbtTag::List(NbtList::Int(vec![
+                self.size.0 as i32,
+                self.size.1 as i32,
+                self.size.2 as i32,
+            ])),
+        );
+
+        let mut palettes = self
+            .palettes
+            .iter()
+            .map(|palette| {
+                palette
+                    .iter()
+                    .map(|block| block.clone().to_compound())
+                    .collect::<Result<Vec<_>>>()
+                    .map(NbtList::Compound)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        match palettes.len() {
+            1 => set_tag(&mut nbt, "palette", NbtTag::List(palettes.remove(0))),
+            _ => set_tag(&mut nbt, "palettes", NbtTag::List(NbtList::List(palettes))),
+        }
+
+        let blocks = self
+            .blocks
+            .iter()
+            .map(|block| {
+                let mut compound = NbtCompound::new();
+                set_tag(&mut compound, "state", NbtTag::Int(block.state as i32));
+                set_tag(
+                    &mut compound,
+                    "pos",
+                    NbtTag::List(NbtList::Int(vec![
+                        block.pos.0 as i32,
+                        block.pos.1 as i32,
+                        block.pos.2 as i32,
+                    ])),
+                );
+                if let Some(block_entity) = &block.nbt {
+                    set_tag(&mut compound, "nbt", NbtTag::Compound(block_entity.clone()));
+                }
+                compound
+            })
+            .collect();
+        set_tag(&mut nbt, "blocks", NbtTag::List(NbtList::Compound(blocks)));
+
+        let entities = self
+            .entities
+            .iter()
+            .map(|entity| {
+                let mut compound = NbtCompound::new();
+                set_tag(
+                    &mut compound,
+                    "pos",
+                    NbtTag::List(NbtList::Double(vec![
+                        entity.pos.0,
+                        entity.pos.1,
+                        entity.pos.2,
+                    ])),
+                );
+                set_tag(
+                    &mut compound,
+                    "blockPos",
+                    NbtTag::List(NbtList::Int(vec![
+                        entity.block_pos.0,
+                        entity.block_pos.1,
+                        entity.block_pos.2,
+                    ])),
+                );
+                set_tag(&mut compound, "nbt", NbtTag::Compound(entity.nbt.clone()));
+                compound
+            })
+            .collect();
+        set_tag(
+            &mut nbt,
+            "entities",
+            NbtTag::List(NbtList::Compound(entities)),
+        );
+
+        Ok(nbt)
+    }
+
+    /// Writes the structure gzip-compressed to the given writer.
+    pub fn write<W: Write>(&self, writer: &mut W) -> Result<()> {
+        write_gzip_nbt(writer, "", self.to_nbt()?)
+    }
+
+    /// Writes the structure to the given path.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        write_file(path.as_ref(), |writer| self.write(writer))
+    }
+
+    /// Places the structure's blocks with it's smallest corner at `to` *(local to within the region)*, using the first palette.
+    ///
+    /// The blocks are set like with [`Region::set_block`], so call [`Region::write_blocks`] afterwards.
+    ///
+    /// ## Example
+    /// ```
+    /// # use silverfish::{PasteOptions, Region, Rotation, StructureTemplate};
+    /// # let mut region = Region::default();
+    /// let structure = StructureTemplate::from_region(&region, (10, 64, 10), (14, 68, 14))?;
+    /// let options = PasteOptions {
+    ///     rotation: Rotation::Clockwise180,
+    ///     ..Default::default()
+    /// };
+    /// structure.place(&mut region, (100, 64, 100), options)?;
+    /// region.write_blocks()?;
+    /// # Ok::<(), silverfish::Error>(())
+    /// ```
+    pub fn place<C: Into<Coords>>(
+        &self,
+        region: &mut Region,
+        to: C,
+        options: PasteOptions,
+    ) -> Result<()> {
+        self.place_palette(0, region, to, options)
+    }
+
+    /// Places the structure's blocks like [`StructureTemplate::place`], but with the palette at `palette`.
+    pub fn place_palette<C: Into<Coords>>(
+        &self,
+        palette: usize,
+        region: &mut Region,
+        to: C,
+        options: PasteOptions,
+    ) -> Result<()> {
+        let to: Coords = to.into();
+        let palette = self.palettes.get(palette).ok_or(Error::OutOfBounds {
+            len: self.palettes.len(),
+            index: palette,
+        })?;
+
+        if self.size.0 == 0 || self.size.1 == 0 || self.size.2 == 0 {
+            return Ok(());
+        }
+
+        let size = rotated_size(self.size, options.rotation);
+        let max = (to.x + size.0 - 1, to.z + size.2 - 1);
+        if max.0 >= BLOCKS_PER_REGION || max.1 >= BLOCKS_PER_REGION {
+            return Err(Error::CoordinatesOutOfRegionBounds(max.0, max.1));
+        }
+
+        let palette: Vec<Block> = palette
+            .iter()
+            .map(|b| b.clone().mirror(options.mirror).rotate(options.rotation))
+            .collect();
+
+        for block in &self.blocks {
+            let state = palette
+                .get(block.state as usize)
+                .ok_or(Error::InvalidPaletteIndex(block.state as i64))?;
+            if options.skips(state) {
+                continue;
+            }
+
+            let (x, z) = transform_xz((block.pos.0, block.pos.2), self.size, options)?;
+            let coords = Coords::new(to.x + x, to.y + block.pos.1 as i32, to.z + z);
+            match &block.nbt {
+                Some(nbt) => region.set_block_with_entity(coords, state.clone(), nbt.clone())?,
+                None => region.set_block(coords, state.clone())?,
+            };
+        }
+
+        Ok(())
+    }
+
+    /// Places the structure's entities with the structure's smallest corner at `to` *(local to within the region)*.
+    ///
+    /// Just like Minecraft, the `UUID` of each entity is removed so they get a new one when loaded.
+    ///
+    /// ## Example
+    /// ```
+    /// # use silverfish::{EntityRegion, PasteOptions, StructureTemplate};
+    /// # let structure = StructureTemplate::from_region(&silverfish::Region::default(), (0, 0, 0), (1, 1, 1))?;
+    /// let mut entities = EntityRegion::empty((0, 0));
+    /// structure.place_entities(&mut entities, (100, 64, 100), PasteOptions::default())?;
+    /// # Ok::<(), silverfish::Error>(())
+    /// ```
+    pub fn place_entities<C: Into<Coords>>(
+        &self,
+        entities: &mut EntityRegion,
+        to: C,
+        options: PasteOptions,
+    ) -> Result<()> {
+        let to: Coords = to.into();
+        let origin = (
+            entities.region_coords.0 * BLOCKS_PER_REGION as i32 + to.x as i32,
+            to.y,
+            entities.region_coords.1 * BLOCKS_PER_REGION as i32 + to.z as i32,
+        );
+
+        for structure_entity in &self.entities {
+            let mut nbt = structure_entity.nbt.clone();
+            nbt.remove("UUID");
+            let mut entity = Entity::from_nbt(nbt);
+
+            let (x, y, z) = structure_entity.pos;
+            let (x, z) = transform_position((x, z), self.size, options);
+            entity.set_position((
+                origin.0 as f64 + x,
+                origin.1 as f64 + y,
+                origin.2 as f64 + z,
+            ));
+            transform_entity(
+                &mut entity.nbt,
+                structure_entity,
+                origin,
+                self.size,
+                options,
+            )?;
+
+            entities.add_entity(entity)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Checks that `pos` is within a cuboid of `size` starting at `0, 0, 0`.
+fn position_within(pos: (i32, i32, i32), size: (u32, u32, u32)) -> Result<(u32, u32, u32)> {
+    let within = |v: i32, len: u32| v >= 0 && (v as u32) < len;
+    match within(pos.0, size.0) && within(pos.1, size.1) && within(pos.2, size.2) {
+        true => Ok((pos.0 as u32, pos.1 as u32, pos.2 as u32)),
+        false => Err(Error::InvalidSchematic(format!(
+            "{pos:?} is outside of the size {size:?}"
+        ))),
+    }
+}
+
+fn read_palette(list: &NbtList) -> Result<Vec<Block>> {
+    compounds(Some(list), "palette")?
+        .iter()
+        .map(Block::from_compound)
+        .collect()
+}
+
+/// Like [`transform_xz`] but for exact positions within the cuboid.
+fn transform_position(
+    (x, z): (f64, f64),
+    size: (u32, u32, u32),
+    options: PasteOptions,
+) -> (f64, f64) {
+    let (width, length) = (size.0 as f64, size.2 as f64);
+    let (x, z) = match options.mirror {
+        Mirror::None => (x, z),
+        Mirror::LeftRight => (x, length - z),
+        Mirror::FrontBack => (width - x, z),
+    };
+
+    match options.rotation {
+        Rotation::None => (x, z),
+        Rotation::Clockwise90 => (length - z, x),
+        Rotation::Clockwise180 => (width - x, length - z),
+        Rotation::CounterClockwise90 => (z, width - x),
+    }
+}
+
+/// Like [`transform_xz`] but for block positions that can be outside of the cuboid.
+fn transform_block_xz(
+    (x, z): (i32, i32),
+    size: (u32, u32, u32),
+    options: PasteOptions,
+) -> (i64, i64) {
+    let (x, z) = (x as i64, z as i64);
+    let (flip_x, flip_z) = (
+        |x: i64| size.0 as i64 - 1 - x,
+        |z: i64| size.2 as i64 - 1 - z,
+    );
+
+    let (x, z) = match options.mirror {
+        Mirror::None => (x, z),
+        Mirror::LeftRight => (x, flip_z(z)),
+        Mirror::FrontBack => (flip_x(x), z),
+    };
+
+    match options.rotation {
+        Rotation::None => (x, z),
+        Rotation::Clockwise90 => (flip_z(z), x),
+        Rotation::Clockwise180 => (flip_x(x), flip_z(z)),
+        Rotation::CounterClockwise90 => (z, flip_x(x)),
+    }
+}
+
+/// Turns the entity's yaw, and for item frames & paintings the block they hang on & the way they face.
+fn transform_entity(
+    nbt: &mut NbtCompound,
+    entity: &StructureEntity,
+    origin: (i32, i32, i32),
+    size: (u32, u32, u32),
+    options: PasteOptions,
+) -> Result<()> {
+    let rotation = match nbt.list("Rotation").and_then(|l| l.floats()) {
+        Some([yaw, pitch]) => Some((*yaw, *pitch)),
+        _ => None,
+    };
+    if let Some((yaw, pitch)) = rotation {
+        let yaw = match options.mirror {
+            Mirror::None => yaw,
+            Mirror::LeftRight => 180.0 - yaw,
+            Mirror::FrontBack => -yaw,
+        };
+        let yaw = (yaw + 90.0 * options.rotation.quarter_turns() as f32).rem_euclid(360.0);
+        set_tag(
+            nbt,
+            "Rotation",
+            NbtTag::List(NbtList::Float(vec![yaw, pitch])),
+        );
+    }
+
+    if nbt.int("TileX").is_some() {
+        let (x, y, z) = entity.block_pos;
+        let (x, z) = transform_block_xz((x, z), size, options);
+        let tile = |origin: i32, v: i64| {
+            i32::try_from(origin as i64 + v).map_err(|_| {
+                Error::InvalidSchematic(format!("{:?} is too far away", entity.block_pos))
+            })
+        };
+        set_tag(nbt, "TileX", NbtTag::Int(tile(origin.0, x)?));
+        set_tag(nbt, "TileY", NbtTag::Int(tile(origin.1, y as i64)?));
+        set_tag(nbt, "TileZ", NbtTag::Int(tile(origin.2, z)?));
+    }
+
+    for (tag, directions) in [("Facing", &FACING_3D[..]), ("facing", &FACING_2D[..])] {
+        let Some(facing) = nbt.byte(tag) else {
+            continue;
+        };
+        let Some(direction) = directions.get(facing as usize) else {
+            continue;
+        };
+
+        let direction = transform_direction(direction, options.rotation, options.mirror);
+        if let Some(index) = directions.iter().position(|d| *d == direction) {
+            set_tag(nbt, tag, NbtTag::Byte(index as i8));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::clipboard::test_region;
+
+    fn structure() -> Result<StructureTemplate> {
+        let mut region = test_region()?;
+        region.set_block((1, 0, 2), "minecraft:structure_void")?;
+        region.write_blocks()?;
+
+        StructureTemplate::from_region(&region, (0, 0, 0), (2, 1, 2))
+    }
+
+    #[test]
+    fn from_region() -> Result<()> {
+        let structure = structure()?;
+        assert_eq!(structure.size, (3, 2, 3));
+        // one structure void is left out
+        assert_eq!(structure.blocks.len(), 3 * 2 * 3 - 1);
+
+        let chest = structure.blocks.iter().find(|b| b.nbt.is_some()).unwrap();
+        assert_eq!(chest.pos, (1, 1, 1));
+        let nbt = chest.nbt.as_ref().unwrap();
+        assert!(nbt.int("x").is_none());
+        assert_eq!(nbt.string("id").unwrap().to_str(), "minecraft:chest");
+
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip() -> Result<()> {
+        let mut structure = structure()?;
+        structure.entities.push(StructureEntity {
+            pos: (0.5, 0.0, 0.5),
+            block_pos: (0, 0, 0),
+            nbt: Entity::new("minecraft:pig", (0.5, 0.0, 0.5), 7).nbt,
+        });
+
+        let mut bytes = vec![];
+        structure.write(&mut bytes)?;
+        let read = StructureTemplate::read(&mut bytes.as_slice())?;
+        assert_eq!(read, structure);
+
+        // multiple palettes are written as `palettes`
+        structure.palettes.push(structure.palettes[0].clone());
+        let nbt = structure.to_nbt()?;
+        assert!(nbt.list("palette").is_none());
+        assert_eq!(StructureTemplate::from_nbt(&nbt)?.palettes.len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn place() -> Result<()> {
+        let structure = structure()?;
+        let mut region = Region::default();
+        region.set_block((101, 0, 102), "minecraft:gold_block")?;
+        region.write_blocks()?;
+
+        structure.place(&mut region, (100, 0, 100), PasteOptions::default())?;
+        region.write_blocks()?;
+        assert_eq!(
+            region.get_block((100, 0, 100))?,
+            Block::new_with_props(
+                "minecraft:oak_stairs",
+                [("facing", "east"), ("half", "top")]
+            )
+        );
+        // the structure void keeps the block that was there
+        assert_eq!(
+            region.get_block((101, 0, 102))?,
+            Block::new("minecraft:gold_block")
+        );
+        assert!(region.get_block_entity((101, 1, 101))?.is_some());
+
+        let mut region = Region::default();
+        let options = PasteOptions {
+            rotation: Rotation::Clockwise90,
+            ..Default::default()
+        };
+        structure.place(&mut region, (100, 0, 100), options)?;
+        region.write_blocks()?;
+        assert_eq!(
+            region.get_block((102, 0, 100))?,
+            Block::new_with_props(
+                "minecraft:oak_stairs",
+                [("facing", "south"), ("half", "top")]
+            )
+        );
+        assert!(region.get_block_entity((101, 1, 101))?.is_some());
+
+        assert!(
+            structure
+                .place_palette(1, &mut region, (0, 0, 0), options)
+                .is_err()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn multiple_palettes() -> Result<()> {
+        let mut structure = structure()?;
+        let stairs = structure
+            .blocks
+            .iter()
+            .find(|b| b.pos == (0, 0, 0))
+            .unwrap();
+        let mut planks = structure.palettes[0].clone();
+        planks[stairs.state as usize] = Block::new("minecraft:birch_planks");
+        structure.palettes.push(planks);
+
+        let mut region = Region::default();
+        structure.place_palette(1, &mut region, (0, 0, 0), PasteOptions::default())?;
+        structure.place_palette(0, &mut region, (10, 0, 0), PasteOptions::default())?;
+        region.write_blocks()?;
+        assert_eq!(
+            region.get_block((0, 0, 0))?,
+            Block::new("minecraft:birch_planks")
+        );
+        assert_eq!(
+            region.get_block((10, 0, 0))?,
+            Block::new_with_props(
+                "minecraft:oak_stairs",
+                [("facing", "east"), ("half", "top")]
+            )
+        );
+        assert!(region.get_block_entity((1, 1, 1))?.is_some());
+
+        // every palette has to have the block states used by the blocks
+        structure.palettes[1].truncate(1);
+        let nbt = structure.to_nbt()?;
+        assert!(matches!(
+            StructureTemplate::from_nbt(&nbt),
+            Err(Error::InvalidPaletteIndex(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn place_entities() -> Result<()> {
+        let mut structure = structure()?;
+        let mut pig = Entity::new("minecraft:pig", (0.5, 0.0, 0.5), 7);
+        set_tag(
+            &mut pig.nbt,
+            "Rotation",
+            NbtTag::List(NbtList::Float(vec![0.0, 10.0])),
+        );
+        let mut frame = Entity::new("minecraft:item_frame", (2.5, 1.5, 0.03), 8);
+        set_tag(&mut frame.nbt, "Facing", NbtTag::Byte(3));
+        set_tag(&mut frame.nbt, "TileX", NbtTag::Int(2));
+        set_tag(&mut frame.nbt, "TileY", NbtTag::Int(1));
+        set_tag(&mut frame.nbt, "TileZ", NbtTag::Int(0));
+        structure.entities = vec![
+            StructureEntity {
+                pos: (0.5, 0.0, 0.5),
+                block_pos: (0, 0, 0),
+                nbt: pig.nbt,
+            },
+            StructureEntity {
+                pos: (2.5, 1.5, 0.03),
+                block_pos: (2, 1, 0),
+                nbt: frame.nbt,
+            },
+        ];
+
+        let mut entities = EntityRegion::empty((1, 0));
+        let options = PasteOptions {
+            rotation: Rotation::Clockwise90,
+            ..Default::default()
+        };
+        structure.place_entities(&mut entities, (10, 64, 10), options)?;
+
+        let pig = entities
+            .entities()
+            .find(|e| e.id().unwrap() == "minecraft:pig")
+            .unwrap();
+        assert_eq!(pig.uuid(), None);
+        assert_eq!(pig.position(), Some((512.0 + 12.5, 64.0, 10.5)));
+        assert_eq!(
+            pig.nbt.list("Rotation").unwrap().floats(),
+            Some(&[90.0, 10.0][..])
+        );
+
+        let frame = entities
+            .entities()
+            .find(|e| e.id().unwrap() == "minecraft:item_frame")
+            .unwrap();
+        assert_eq!(frame.nbt.int("TileX"), Some(512 + 12));
+        assert_eq!(frame.nbt.int("TileZ"), Some(12));
+        // south turns west
+        assert_eq!(frame.nbt.byte("Facing"), Some(4));
+
+        // hanging on a block just outside of the structure
+        let mut entities = EntityRegion::empty((1, 0));
+        structure.entities.truncate(1);
+        structure.entities[0].nbt = frame.nbt.clone();
+        structure.entities[0].block_pos = (-1, 1, 0);
+        structure.place_entities(&mut entities, (10, 64, 10), options)?;
+        let frame = entities.entities().next().unwrap();
+        assert_eq!(frame.nbt.int("TileX"), Some(512 + 12));
+        assert_eq!(frame.nbt.int("TileZ"), Some(9));
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_positions() -> Result<()> {
+        let nbt = structure()?.to_nbt()?;
+        let mut blocks = nbt.list("blocks").unwrap().compounds().unwrap().to_vec();
+        let set_pos = |blocks: &mut Vec<NbtCompound>, pos: Vec<i32>| {
+            set_tag(&mut blocks[0], "pos", NbtTag::List(NbtList::Int(pos)));
+        };
+
+        for pos in [vec![3, 0, 0], vec![0, -1, 0], vec![0, 0, 3]] {
+            set_pos(&mut blocks, pos);
+            let mut nbt = nbt.clone();
+            set_tag(
+                &mut nbt,
+                "blocks",
+                NbtTag::List(NbtList::Compound(blocks.clone())),
+            );
+            assert!(matches!(
+                StructureTemplate::from_nbt(&nbt),
+                Err(Error::InvalidSchematic(_))
+            ));
+        }
+
+        let entity = NbtCompound::from_values(vec![
+            (
+                "pos".into(),
+                NbtTag::List(NbtList::Double(vec![0.5, 0.0, 0.5])),
+            ),
+            (
+                "blockPos".into(),
+                NbtTag::List(NbtList::Int(vec![0, 0, -1])),
+            ),
+            ("nbt".into(), NbtTag::Compound(NbtCompound::new())),
+        ]);
+        let mut with_entity = nbt.clone();
+        set_tag(
+            &mut with_entity,
+            "entities",
+            NbtTag::List(NbtList::Compound(vec![entity])),
+        );
+        // entities are collected by their bounding box, so their block can be outside of the size
+        assert_eq!(
+            StructureTemplate::from_nbt(&with_entity)?.entities[0].block_pos,
+            (0, 0, -1)
+        );
+
+        let mut negative = nbt.clone();
+        set_tag(
+            &mut negative,
+            "size",
+            NbtTag::List(NbtList::Int(vec![3, -2, 3])),
+        );
+        assert!(matches!(
+            StructureTemplate::from_nbt(&negative),
+            Err(Error::InvalidSchematic(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn place_empty() -> Result<()> {
+        let mut nbt = structure()?.to_nbt()?;
+        set_tag(&mut nbt, "size", NbtTag::List(NbtList::Int(vec![0, 0, 0])));
+        set_tag(&mut nbt, "blocks", NbtTag::List(NbtList::Empty));
+        let structure = StructureTemplate::from_nbt(&nbt)?;
+
+        let mut region = Region::default();
+        structure.place(&mut region, (0, 0, 0), PasteOptions::default())?;
+        assert!(structure.blocks.is_empty());
+
+        Ok(())
+    }
+}
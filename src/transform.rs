@@ -0,0 +1,400 @@
+//! `transform` rotates & mirrors the properties of a [`Block`], like when a structure is placed in a different orientation.
+//!
+//! It works on the property names and values alone, so modded blocks that use the standard names are transformed too.
+
+use crate::{Block, NbtString};
+use std::collections::BTreeMap;
+
+/// A clockwise rotation around the y axis, seen from above.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Rotation {
+    /// No rotation.
+    #[default]
+    None,
+    /// A quarter turn clockwise, north becomes east.
+    Clockwise90,
+    /// A half turn, north becomes south.
+    Clockwise180,
+    /// A quarter turn counterclockwise, north becomes west.
+    CounterClockwise90,
+}
+
+/// A mirror along one of the horizontal axes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Mirror {
+    /// No mirroring.
+    #[default]
+    None,
+    /// Mirrors the z axis, north & south swaps places.
+    LeftRight,
+    /// Mirrors the x axis, east & west swaps places.
+    FrontBack,
+}
+
+/// The horizontal directions in clockwise order.
+const HORIZONTAL: [&str; 4] = ["north", "east", "south", "west"];
+
+/// Properties whose value is one or more directions joined by `_`, like `facing=east` or `orientation=north_up`.
+const DIRECTION_PROPERTIES: [&str; 3] = ["facing", "orientation", "shape"];
+
+/// Properties that has a handedness which flips when mirrored, like `hinge=left`.
+const HANDED_PROPERTIES: [&str; 4] = ["hinge", "type", "shape", "side_chain"];
+
+/// Properties with a rotation in 16 steps, like signs & banners.
+const ROTATION_PROPERTIES: [&str; 1] = ["rotation"];
+
+impl Rotation {
+    /// How many clockwise quarter turns the rotation is.
+    pub fn quarter_turns(self) -> usize {
+        match self {
+            Rotation::None => 0,
+            Rotation::Clockwise90 => 1,
+            Rotation::Clockwise180 => 2,
+            Rotation::CounterClockwise90 => 3,
+        }
+    }
+
+    /// Returns the rotation of first rotating by `self` and then by `other`.
+    pub fn then(self, other: Rotation) -> Rotation {
+        Rotation::from_quarter_turns(self.quarter_turns() + other.quarter_turns())
+    }
+
+    pub(crate) fn from_quarter_turns(turns: usize) -> Rotation {
+        match turns % 4 {
+            0 => Rotation::None,
+            1 => Rotation::Clockwise90,
+            2 => Rotation::Clockwise180,
+            _ => Rotation::CounterClockwise90,
+        }
+    }
+}
+
+/// Either of the two transforms, which decides how each kind of property changes.
+#[derive(Clone, Copy)]
+enum Transform {
+    Rotate(Rotation),
+    Mirror(Mirror),
+}
+
+impl Transform {
+    fn is_identity(self) -> bool {
+        matches!(
+            self,
+            Transform::Rotate(Rotation::None) | Transform::Mirror(Mirror::None)
+        )
+    }
+
+    /// Transforms a horizontal direction, anything else is left as is.
+    fn direction(self, direction: &str) -> Option<&'static str> {
+        let index = HORIZONTAL.iter().position(|d| *d == direction)?;
+        let index = match self {
+            Transform::Rotate(rotation) => (index + rotation.quarter_turns()) % 4,
+            // north & south are 0 & 2, east & west are 1 & 3
+            Transform::Mirror(Mirror::LeftRight) if index % 2 == 0 => (index + 2) % 4,
+            Transform::Mirror(Mirror::FrontBack) if index % 2 == 1 => (index + 2) % 4,
+            Transform::Mirror(_) => index,
+        };
+
+        Some(HORIZONTAL[index])
+    }
+
+    fn axis(self, axis: &str) -> &str {
+        let quarter = matches!(
+            self,
+            Transform::Rotate(Rotation::Clockwise90 | Rotation::CounterClockwise90)
+        );
+        match axis {
+            "x" if quarter => "z",
+            "z" if quarter => "x",
+            _ => axis,
+        }
+    }
+
+    /// Transforms a rotation in 16 steps, where 0 is south and 4 is west.
+    ///
+    /// Invalid rotations above 15 are left unchanged.
+    fn rotation(self, rotation: usize) -> usize {
+        if rotation >= 16 {
+            return rotation;
+        }
+
+        match self {
+            Transform::Rotate(rotation_by) => (rotation + rotation_by.quarter_turns() * 4) % 16,
+            Transform::Mirror(Mirror::None) => rotation,
+            Transform::Mirror(Mirror::LeftRight) => (8 + 16 - rotation) % 16,
+            Transform::Mirror(Mirror::FrontBack) => (16 - rotation) % 16,
+        }
+    }
+
+    /// Transforms a value made of tokens joined by `_`, directions are transformed
+    /// and when mirroring `left` & `right` swaps places.
+    fn tokens(self, value: &str, directions: bool, handed: bool) -> String {
+        let mirrored = matches!(self, Transform::Mirror(_));
+        let tokens: Vec<&str> = value
+            .split('_')
+            .map(|token| match token {
+                "left" if handed && mirrored => "right",
+                "right" if handed && mirrored => "left",
+                _ if directions => self.direction(token).unwrap_or(token),
+                _ => token,
+            })
+            .collect();
+
+        // rail shapes are always named `north_*` or `south_*` and straight ones as `north_south` & `east_west`
+        match tokens.as_slice() {
+            [a, b] if HORIZONTAL.contains(a) && HORIZONTAL.contains(b) => {
+                let has_north = *a == "north" || *b == "north";
+                let mut pair = [*a, *b];
+                pair.sort_by_key(|d| match *d {
+                    "north" => 0,
+                    "south" if has_north => 1,
+                    "south" => 0,
+                    "east" => 2,
+                    _ => 3,
+                });
+                pair.join("_")
+            }
+            _ => tokens.join("_"),
+        }
+    }
+
+    fn apply(self, properties: &mut BTreeMap<NbtString, NbtString>) {
+        if self.is_identity() {
+            return;
+        }
+
+        let mut transformed = BTreeMap::new();
+        for (key, value) in properties.iter() {
+            let (key_str, value_str) = (key.to_str(), value.to_str());
+            let directions = DIRECTION_PROPERTIES.contains(&key_str.as_ref());
+            let handed = HANDED_PROPERTIES.contains(&key_str.as_ref());
+
+            let new_value = if key_str == "axis" {
+                self.axis(&value_str).to_owned()
+            } else if ROTATION_PROPERTIES.contains(&key_str.as_ref())
+                && let Ok(rotation) = value_str.parse::<usize>()
+            {
+                self.rotation(rotation).to_string()
+            } else if directions || handed {
+                self.tokens(&value_str, directions, handed)
+            } else {
+                value_str.to_string()
+            };
+
+            // connection properties like `north=true` moves to their new direction
+            let new_key = match self.direction(&key_str) {
+                Some(direction) => NbtString::from_str(direction),
+                None => Ok(key.clone()),
+            };
+
+            match (new_key, NbtString::from_str(&new_value)) {
+                (Ok(new_key), Ok(new_value)) => transformed.insert(new_key, new_value),
+                _ => transformed.insert(key.clone(), value.clone()),
+            };
+        }
+
+        *properties = transformed;
+    }
+}
+
+impl Block {
+    /// Rotates the block's properties like `facing`, `axis`, `rotation`, rail `shape`
+    /// and connections like `north=true` the way Minecraft does.
+    ///
+    /// ## Example
+    /// ```
+    /// # use silverfish::{Block, Rotation};
+    /// let stairs = Block::new_with_props("oak_stairs", [("facing", "north")]);
+    /// assert_eq!(
+    ///     stairs.rotate(Rotation::Clockwise90),
+    ///     Block::new_with_props("oak_stairs", [("facing", "east")])
+    /// );
+    /// ```
+    pub fn rotate(mut self, rotation: Rotation) -> Self {
+        if let Some(properties) = &mut self.properties {
+            Transform::Rotate(rotation).apply(properties);
+        }
+        self
+    }
+
+    /// Mirrors the block's properties, which also flips the handedness of things like door hinges & chest halves.
+    ///
+    /// ## Example
+    /// ```
+    /// # use silverfish::{Block, Mirror};
+    /// let door = Block::new_with_props("oak_door", [("facing", "north"), ("hinge", "left")]);
+    /// assert_eq!(
+    ///     door.mirror(Mirror::LeftRight),
+    ///     Block::new_with_props("oak_door", [("facing", "south"), ("hinge", "right")])
+    /// );
+    /// ```
+    pub fn mirror(mut self, mirror: Mirror) -> Self {
+        if let Some(properties) = &mut self.properties {
+            Transform::Mirror(mirror).apply(properties);
+        }
+        self
+    }
+}
+
+/// Mirrors & then rotates a direction like `north`, any other value is returned as is.
+pub(crate) fn transform_direction(direction: &str, rotation: Rotation, mirror: Mirror) -> &str {
+    let mirrored = Transform::Mirror(mirror)
+        .direction(direction)
+        .unwrap_or(direction);
+    Transform::Rotate(rotation)
+        .direction(mirrored)
+        .unwrap_or(mirrored)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn block<const N: usize>(properties: [(&str, &str); N]) -> Block {
+        Block::new_with_props("mymod:thing", properties)
+    }
+
+    #[test]
+    fn rotate_facing() {
+        let stairs = block([
+            ("facing", "north"),
+            ("half", "top"),
+            ("shape", "inner_left"),
+        ]);
+        assert_eq!(
+            stairs.clone().rotate(Rotation::Clockwise90),
+            block([("facing", "east"), ("half", "top"), ("shape", "inner_left")])
+        );
+        assert_eq!(
+            stairs.clone().rotate(Rotation::CounterClockwise90),
+            block([("facing", "west"), ("half", "top"), ("shape", "inner_left")])
+        );
+        assert_eq!(stairs.clone().rotate(Rotation::None), stairs);
+        assert_eq!(
+            block([("facing", "up")]).rotate(Rotation::Clockwise90),
+            block([("facing", "up")])
+        );
+        assert_eq!(
+            block([("orientation", "north_up")]).rotate(Rotation::Clockwise180),
+            block([("orientation", "south_up")])
+        );
+    }
+
+    #[test]
+    fn rotate_axis_and_rotation() {
+        assert_eq!(
+            block([("axis", "x")]).rotate(Rotation::Clockwise90),
+            block([("axis", "z")])
+        );
+        assert_eq!(
+            block([("axis", "x")]).rotate(Rotation::Clockwise180),
+            block([("axis", "x")])
+        );
+        assert_eq!(
+            block([("rotation", "14")]).rotate(Rotation::Clockwise90),
+            block([("rotation", "2")])
+        );
+        assert_eq!(
+            block([("rotation", "3")]).mirror(Mirror::LeftRight),
+            block([("rotation", "5")])
+        );
+        assert_eq!(
+            block([("rotation", "3")]).mirror(Mirror::FrontBack),
+            block([("rotation", "13")])
+        );
+
+        for invalid in ["16", "30"] {
+            assert_eq!(
+                block([("rotation", invalid)]).mirror(Mirror::LeftRight),
+                block([("rotation", invalid)])
+            );
+            assert_eq!(
+                block([("rotation", invalid)]).rotate(Rotation::Clockwise90),
+                block([("rotation", invalid)])
+            );
+        }
+    }
+
+    #[test]
+    fn connections() {
+        let fence = block([
+            ("north", "true"),
+            ("east", "false"),
+            ("south", "false"),
+            ("west", "false"),
+        ]);
+        assert_eq!(
+            fence.clone().rotate(Rotation::Clockwise90),
+            block([
+                ("north", "false"),
+                ("east", "true"),
+                ("south", "false"),
+                ("west", "false"),
+            ])
+        );
+        assert_eq!(
+            fence.clone().mirror(Mirror::LeftRight),
+            block([
+                ("north", "false"),
+                ("east", "false"),
+                ("south", "true"),
+                ("west", "false"),
+            ])
+        );
+        assert_eq!(fence.clone().mirror(Mirror::FrontBack), fence);
+    }
+
+    #[test]
+    fn rail_shapes() {
+        let cases = [
+            ("north_south", Rotation::Clockwise90, "east_west"),
+            ("south_east", Rotation::Clockwise90, "south_west"),
+            ("north_east", Rotation::Clockwise180, "south_west"),
+            ("north_west", Rotation::CounterClockwise90, "south_west"),
+            ("ascending_east", Rotation::Clockwise90, "ascending_south"),
+        ];
+        for (shape, rotation, expected) in cases {
+            assert_eq!(
+                block([("shape", shape)]).rotate(rotation),
+                block([("shape", expected)])
+            );
+        }
+
+        assert_eq!(
+            block([("shape", "south_east")]).mirror(Mirror::FrontBack),
+            block([("shape", "south_west")])
+        );
+    }
+
+    #[test]
+    fn mirror_handedness() {
+        assert_eq!(
+            block([("facing", "east"), ("type", "left")]).mirror(Mirror::FrontBack),
+            block([("facing", "west"), ("type", "right")])
+        );
+        assert_eq!(
+            block([("type", "bottom")]).mirror(Mirror::LeftRight),
+            block([("type", "bottom")])
+        );
+        assert_eq!(
+            block([("shape", "outer_right")]).mirror(Mirror::LeftRight),
+            block([("shape", "outer_left")])
+        );
+        assert_eq!(
+            block([("hinge", "left")]).rotate(Rotation::Clockwise90),
+            block([("hinge", "left")])
+        );
+    }
+
+    #[test]
+    fn combine_rotations() {
+        assert_eq!(
+            Rotation::Clockwise90.then(Rotation::Clockwise180),
+            Rotation::CounterClockwise90
+        );
+        assert_eq!(
+            Rotation::CounterClockwise90.then(Rotation::Clockwise90),
+            Rotation::None
+        );
+    }
+}
@@ -0,0 +1,442 @@
+//! `world` contains the [`World`] struct used to set/get blocks & biomes across an entire save.
+//!
+//! A [`World`] works with signed *global* coordinates and routes them to the right [`Region`],
+//! loading regions from disk the first time they are needed.
+
+use crate::{
+    BiomeCell, Block, Config, Dimension, Error, NbtString, PoiRegion, Region, Result,
+    to_region_coords, to_region_local,
+};
+use ahash::{AHashMap, AHashSet};
+use std::{
+    fmt::Debug,
+    path::{Path, PathBuf},
+};
+
+/// A Minecraft save that lazily loads it's [`Region`]s on demand.
+///
+/// Regions are opened via [`Region::open_lazy`], so only the chunks that are used gets read.
+/// All coordinates given to a [`World`] are global world coordinates, `(x, y, z)`.
+/// And only the regions that have been modified are written back on [`World::save`].
+pub struct World {
+    /// The folder containing all the `r.x.z.mca` files.
+    region_folder: PathBuf,
+    /// The folder containing the points of interest, next to the region folder.
+    poi_folder: PathBuf,
+    /// All the regions that have been loaded, mapped to their region coordinates.
+    pub(crate) regions: AHashMap<(i32, i32), Region>,
+    /// Regions that have been handed out mutably and needs to be written back.
+    pub(crate) touched: AHashSet<(i32, i32)>,
+    /// Config that is given to every region that gets loaded.
+    pub(crate) config: Config,
+}
+
+impl World {
+    /// Opens a Minecraft save folder, using the region files found in `<save>/region`.
+    ///
+    /// No regions are read until they're needed.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// # use silverfish::World;
+    /// let mut world = World::open("saves/New World")?;
+    /// world.set_block((-841, 64, 4821), "minecraft:diamond_block")?;
+    /// world.save()?;
+    /// # Ok::<(), silverfish::Error>(())
+    /// ```
+    pub fn open<P: AsRef<Path>>(save: P) -> Result<Self> {
+        Self::from_region_folder(save.as_ref().join("region"))
+    }
+
+    /// Opens a specific [`Dimension`] of a Minecraft save.
+    ///
+    /// The [`Config::get_world_height`] is set to the height of the dimension,
+    /// so every region loaded from it gets correctly sized internal buffers.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// # use silverfish::{Dimension, World};
+    /// let mut nether = World::open_dimension("saves/New World", Dimension::Nether)?;
+    /// let mut custom = World::open_dimension("saves/New World", Dimension::custom("my_pack:mining"))?;
+    /// # Ok::<(), silverfish::Error>(())
+    /// ```
+    pub fn open_dimension<P: AsRef<Path>>(save: P, dimension: Dimension) -> Result<Self> {
+        let save = save.as_ref();
+        let mut world = Self::from_region_folder(dimension.region_folder(save))?;
+        world.config.world_height = dimension.world_height(save)?;
+
+        Ok(world)
+    }
+
+    /// Opens a folder that directly contains `r.x.z.mca` files.
+    pub fn from_region_folder<P: AsRef<Path>>(folder: P) -> Result<Self> {
+        let folder = folder.as_ref().to_path_buf();
+        if !folder.is_dir() {
+            return Err(Error::MissingRegionFolder(folder));
+        }
+
+        let poi_folder = match folder.parent() {
+            Some(parent) => parent.join("poi"),
+            None => PathBuf::from("poi"),
+        };
+
+        Ok(Self {
+            region_folder: folder,
+            poi_folder,
+            regions: AHashMap::new(),
+            touched: AHashSet::new(),
+            config: Config::default(),
+        })
+    }
+
+    /// Returns the folder the region files are read from and written to.
+    pub fn get_region_folder(&self) -> &Path {
+        &self.region_folder
+    }
+
+    /// Returns the World's [`Config`]
+    pub fn get_config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Sets the [`Config`] used for all regions, including the ones already loaded.
+    ///
+    /// With [`Config::update_poi`] set, the POI regions for the already loaded regions are loaded as well.
+    ///
+    /// The world height always stays the one of the dimension, whatever the given config has.
+    pub fn set_config(&mut self, config: Config) -> Result<()> {
+        let config = Config {
+            world_height: self.config.world_height.clone(),
+            ..config
+        };
+        for (region_coords, region) in self.regions.iter_mut() {
+            region.set_config(config.clone())?;
+            if config.update_poi && region.get_poi_region().is_none() {
+                region.set_poi_region(Self::read_poi_region(&self.poi_folder, *region_coords)?)?;
+            }
+        }
+        self.config = config;
+
+        Ok(())
+    }
+
+    /// Returns the path to the region file for the given region coordinates.
+    pub fn region_file_path(&self, region_coords: (i32, i32)) -> PathBuf {
+        self.region_folder.join(format!(
+            "r.{}.{}.mca",
+            region_coords.0, region_coords.1
+        ))
+    }
+
+    /// Returns the path to the POI region file for the given region coordinates.
+    pub fn poi_file_path(&self, region_coords: (i32, i32)) -> PathBuf {
+        Self::poi_path(&self.poi_folder, region_coords)
+    }
+
+    /// Returns the path to the POI region file within a `poi` folder.
+    fn poi_path(poi_folder: &Path, region_coords: (i32, i32)) -> PathBuf {
+        poi_folder.join(format!("r.{}.{}.mca", region_coords.0, region_coords.1))
+    }
+
+    /// Reads a POI region from disk, or creates an empty one if there is no POI region file yet.
+    fn read_poi_region(poi_folder: &Path, region_coords: (i32, i32)) -> Result<PoiRegion> {
+        let path = Self::poi_path(poi_folder, region_coords);
+        if path.exists() {
+            PoiRegion::open(path)
+        } else {
+            Ok(PoiRegion::empty(region_coords))
+        }
+    }
+
+    /// Reads a region from disk, or creates an empty one if [`Config::create_chunk_if_missing`] is set.
+    fn load_region(&self, region_coords: (i32, i32)) -> Result<Region> {
+        let path = self.region_file_path(region_coords);
+
+        let mut region = if path.exists() {
+            Region::open_lazy(path)?
+        } else if self.config.create_chunk_if_missing {
+            Region::empty(region_coords)
+        } else {
+            return Err(Error::NoRegion(region_coords.0, region_coords.1));
+        };
+        region.set_config(self.config.clone())?;
+        if self.config.update_poi {
+            region.set_poi_region(Self::read_poi_region(&self.poi_folder, region_coords)?)?;
+        }
+
+        Ok(region)
+    }
+
+    /// Returns the region at the given region coordinates, loading it from disk if needed.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// # let mut world = silverfish::World::open("saves/New World")?;
+    /// let region = world.get_region((-2, 9))?;
+    /// # Ok::<(), silverfish::Error>(())
+    /// ```
+    pub fn get_region(&mut self, region_coords: (i32, i32)) -> Result<&Region> {
+        if !self.regions.contains_key(&region_coords) {
+            let region = self.load_region(region_coords)?;
+            self.regions.insert(region_coords, region);
+        }
+
+        // unwrap is fine since we just made sure it's loaded
+        Ok(self.regions.get(&region_coords).unwrap())
+    }
+
+    /// Returns a mutable reference to the region at the given region coordinates, loading it from disk if needed.
+    ///
+    /// The region gets marked as touched and will be written back on [`World::save`].
+    pub fn get_region_mut(&mut self, region_coords: (i32, i32)) -> Result<&mut Region> {
+        if !self.regions.contains_key(&region_coords) {
+            let region = self.load_region(region_coords)?;
+            self.regions.insert(region_coords, region);
+        }
+        self.touched.insert(region_coords);
+
+        Ok(self.regions.get_mut(&region_coords).unwrap())
+    }
+
+    /// Set a block at the specified **global** coordinates.
+    ///
+    /// Works just like [`Region::set_block`], the block is only written to
+    /// an internal buffer until [`World::write_blocks`] or [`World::save`] is called.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// # let mut world = silverfish::World::open("saves/New World")?;
+    /// let _ = world.set_block((-841, -17, 4821), "minecraft:deepslate")?;
+    /// # Ok::<(), silverfish::Error>(())
+    /// ```
+    pub fn set_block<B: Into<Block>>(
+        &mut self,
+        coords: (i32, i32, i32),
+        block: B,
+    ) -> Result<Option<()>> {
+        self.get_region_mut(to_region_coords(coords))?
+            .set_block(to_region_local(coords), block)
+    }
+
+    /// Returns the block at the specified **global** coordinates.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// # let mut world = silverfish::World::open("saves/New World")?;
+    /// let block = world.get_block((-841, -17, 4821))?;
+    /// # Ok::<(), silverfish::Error>(())
+    /// ```
+    pub fn get_block(&mut self, coords: (i32, i32, i32)) -> Result<Block> {
+        self.get_region(to_region_coords(coords))?
+            .get_block(to_region_local(coords))
+    }
+
+    /// Set the biome at the cell containing the specified **global** coordinates.
+    ///
+    /// Written to the NBT on [`World::write_biomes`] or [`World::save`].
+    pub fn set_biome<B: Into<NbtString>>(
+        &mut self,
+        coords: (i32, i32, i32),
+        biome: B,
+    ) -> Result<Option<()>> {
+        let cell: BiomeCell = to_region_local(coords).into();
+        self.get_region_mut(to_region_coords(coords))?
+            .set_biome(cell, biome)
+    }
+
+    /// Returns the biome at the specified **global** coordinates.
+    pub fn get_biome(&mut self, coords: (i32, i32, i32)) -> Result<NbtString> {
+        let cell: BiomeCell = to_region_local(coords).into();
+        self.get_region(to_region_coords(coords))?.get_biome(cell)
+    }
+
+    /// Writes all pending blocks in every touched region to their chunk NBT.
+    pub fn write_blocks(&mut self) -> Result<()> {
+        for region_coords in self.touched.iter() {
+            if let Some(region) = self.regions.get_mut(region_coords) {
+                region.write_blocks()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes all pending biomes in every touched region to their chunk NBT.
+    pub fn write_biomes(&mut self) -> Result<()> {
+        for region_coords in self.touched.iter() {
+            if let Some(region) = self.regions.get_mut(region_coords) {
+                region.write_biomes()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes all pending blocks & biomes and saves every touched region back to it's region file.
+    ///
+    /// Regions that were only read from are left alone on disk.
+    /// Saved regions are unloaded and will be read from disk again if used after this.
+    /// Modified POI regions are saved to the `poi` folder next to the region folder.
+    pub fn save(&mut self) -> Result<()> {
+        self.write_blocks()?;
+        self.write_biomes()?;
+
+        let touched = self.touched.drain().collect::<Vec<(i32, i32)>>();
+        for region_coords in touched {
+            let mut region = match self.regions.remove(&region_coords) {
+                Some(r) => r,
+                None => continue,
+            };
+
+            if let Some(poi) = region.take_poi_region() {
+                if poi.is_modified() {
+                    std::fs::create_dir_all(&self.poi_folder)?;
+                    poi.save(self.poi_file_path(region_coords))?;
+                }
+            }
+            region.save(self.region_file_path(region_coords))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Debug for World {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "World({:?})\n  > loaded regions: {}\n  > touched regions: {}\n  > {:?}",
+            self.region_folder,
+            self.regions.len(),
+            self.touched.len(),
+            self.config
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Creates an empty save folder with a `region` folder inside it.
+    fn temp_save(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("silverfish_world_{name}"));
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(path.join("region")).unwrap();
+        path
+    }
+
+    fn creating_config() -> Config {
+        Config {
+            create_chunk_if_missing: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn missing_region_folder() {
+        let path = std::env::temp_dir().join("silverfish_world_does_not_exist");
+        assert!(World::open(path).is_err());
+    }
+
+    #[test]
+    fn missing_region() -> Result<()> {
+        let save = temp_save("missing_region");
+        let mut world = World::open(&save)?;
+        assert!(world.get_block((5, 5, 5)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn nether_world_height() -> Result<()> {
+        let save = temp_save("nether_world_height");
+        std::fs::create_dir_all(save.join("DIM-1").join("region")).unwrap();
+
+        let mut world = World::open_dimension(&save, Dimension::Nether)?;
+        world.set_config(Config {
+            create_chunk_if_missing: true,
+            ..Default::default()
+        })?;
+        assert_eq!(world.get_config().get_world_height(), &(0..256));
+        assert!(world.get_config().create_chunk_if_missing);
+
+        world.set_block((-20, 5, 20), "minecraft:netherrack")?;
+        world.write_blocks()?;
+        let region = world.get_region((-1, 0))?;
+        assert_eq!(region.get_config().get_world_height(), &(0..256));
+        assert_eq!(
+            world.get_block((-20, 5, 20))?,
+            Block::new("minecraft:netherrack")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn routes_global_coordinates() -> Result<()> {
+        let save = temp_save("routes");
+        let mut world = World::open(&save)?;
+        world.set_config(creating_config())?;
+
+        world.set_block((-841, -17, 4821), "minecraft:deepslate")?;
+        world.set_block((12, 70, 3), "minecraft:stone")?;
+
+        assert_eq!(world.regions.len(), 2);
+        assert!(world.touched.contains(&(-2, 9)));
+        assert!(world.touched.contains(&(0, 0)));
+
+        world.write_blocks()?;
+        assert_eq!(
+            world.get_block((-841, -17, 4821))?,
+            Block::new("minecraft:deepslate")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn save_and_reopen() -> Result<()> {
+        let save = temp_save("save_and_reopen");
+        let mut world = World::open(&save)?;
+        world.set_config(creating_config())?;
+
+        world.set_block((-1, 64, -1), "minecraft:gold_block")?;
+        world.set_biome((-1, 64, -1), "minecraft:desert")?;
+        world.save()?;
+
+        assert!(save.join("region").join("r.-1.-1.mca").exists());
+        assert!(!save.join("region").join("r.0.0.mca").exists());
+
+        let mut world = World::open(&save)?;
+        assert_eq!(
+            world.get_block((-1, 64, -1))?,
+            Block::new("minecraft:gold_block")
+        );
+        assert_eq!(world.get_biome((-1, 64, -1))?, "minecraft:desert");
+
+        Ok(())
+    }
+
+    #[test]
+    fn saves_poi() -> Result<()> {
+        let save = temp_save("saves_poi");
+        let mut world = World::open(&save)?;
+        world.set_config(Config {
+            update_poi: true,
+            ..creating_config()
+        })?;
+
+        world.set_block((-10, 64, 20), "minecraft:bell")?;
+        world.set_block((600, 64, 20), "minecraft:stone")?;
+        world.save()?;
+
+        assert!(!save.join("poi").join("r.1.0.mca").exists());
+        let poi = PoiRegion::open(save.join("poi").join("r.-1.0.mca"))?;
+        assert_eq!(
+            poi.get_record((502, 64, 20)).unwrap().kind,
+            "minecraft:meeting"
+        );
+
+        Ok(())
+    }
+}
@@ -2,9 +2,12 @@
 //! to it's chunks within the [`Region`], handles batching, encoding/decoding section data, etc.  
 
 use crate::{
-    BiomeCell, Block, CHUNK_OP, Config, Error, NbtString, Region, Result,
+    BiomeCell, Block, CHUNK_OP, Config, Coords, Error, NbtString, Region, Result,
+    block_entity::{block_entities_mut, block_entity_position},
     chunk::ChunkData,
     data::{decode_data, encode_data},
+    heightmap::update_heightmaps as update_heightmaps_of,
+    poi::{PoiChange, PoiType, poi_of_block, poi_of_compound},
     region::{clean_palette, get_biome_bit_count, get_block_bit_count, is_valid_chunk},
 };
 use ahash::AHashMap;
@@ -18,7 +21,12 @@ impl Region {
     /// Takes all pending block writes and applies all the blocks to the actual chunk NBT
     ///
     /// This function writes all the chunks within the region in parallel.    
+    ///
+    /// With [`Config::update_poi`] set, any changed points of interest are written to the attached [`PoiRegion`](crate::PoiRegion).  
+    /// With [`Config::compute_lighting`] set, the changed chunks are relit afterwards.  
     pub fn write_blocks(&mut self) -> Result<()> {
+        self.check_poi_region()?;
+
         self.chunks
             .par_iter_mut()
             .filter(|c| c.dirty_blocks)
@@ -26,6 +34,11 @@ impl Region {
                 let coords = *ref_mut.key();
                 ref_mut.write_blocks(coords, &self.get_config())
             })?;
+        self.apply_poi_changes();
+
+        if self.config.compute_lighting {
+            self.compute_lighting()?;
+        }
 
         Ok(())
     }
@@ -81,12 +94,14 @@ impl Region {
     /// # Ok::<(), silverfish::Error>(())
     /// ```
     pub fn set_sections<B: Into<Block>>(&mut self, sections: Vec<((u8, u8), i8, B)>) -> Result<()> {
+        self.check_poi_region()?;
+
         sections
             .into_iter()
             // we have to map because of block and it's into
             // rayon doesnt like it otherwise
-            .map(|(cc, sy, b)| (cc, sy, b.into()))
-            .collect::<Vec<((u8, u8), i8, Block)>>()
+            .map(|(cc, sy, b)| Ok((cc, sy, self.prepare_block(b.into())?)))
+            .collect::<Result<Vec<((u8, u8), i8, Block)>>>()?
             .into_par_iter()
             .try_for_each(|(chunk_coords, section_y, block)| {
                 assert!(
@@ -95,16 +110,22 @@ impl Region {
                 );
 
                 // again, this part is just copied but hard to extrapolate
-                let update_lighting = self.get_config().update_lighting;
+                let update_lighting =
+                    self.get_config().update_lighting || self.get_config().compute_lighting;
+                let update_poi = self.get_config().update_poi;
+                let update_heightmaps = self.get_config().update_heightmaps;
                 let mut chunk_data = self.get_chunk_mut(chunk_coords.0, chunk_coords.1)?;
                 let nbt = &mut chunk_data.nbt;
 
                 is_valid_chunk(&nbt, chunk_coords)?;
 
                 // clear heightmaps if they exist since they can become outdated after this
-                if let Some(height_maps) = nbt.compound_mut("Heightmaps") {
-                    height_maps.clear();
-                };
+                // unless they're recomputed once the section is set
+                if !update_heightmaps {
+                    if let Some(height_maps) = nbt.compound_mut("Heightmaps") {
+                        height_maps.clear();
+                    };
+                }
 
                 if update_lighting {
                     *nbt.byte_mut("isLightOn")
@@ -142,7 +163,7 @@ impl Region {
                     })?
                     .ok_or(Error::MissingNbtTag("couldn't find section"))?;
 
-                if self.get_config().update_lighting {
+                if update_lighting {
                     section.remove("BlockLight");
                     section.remove("SkyLight");
                 }
@@ -151,6 +172,11 @@ impl Region {
                     .compound_mut("block_states")
                     .ok_or(Error::MissingNbtTag("block_states"))?;
 
+                if update_poi {
+                    let changes = section_poi_changes(state, chunk_coords, section_y, &block)?;
+                    chunk_data.poi_changes.extend(changes);
+                }
+
                 // when setting a single section, remove its data field and make sure
                 // the palette only has a single block inside it
                 state.remove("data");
@@ -164,42 +190,87 @@ impl Region {
 
                 assert_eq!(palette.len(), 1);
 
-                // TODO most block entity things doesnt have tests for them
-                // and im unsure if this actually works since i suck at math :)
-                for i in 0..block_entities.len() {
-                    let x = block_entities[i]
-                        .int("x")
-                        .ok_or(Error::MissingNbtTag("x"))?
-                        & CHUNK_OP;
-                    let y = block_entities[i]
-                        .int("y")
-                        .ok_or(Error::MissingNbtTag("y"))?
-                        & CHUNK_OP;
-                    let z = block_entities[i]
-                        .int("z")
-                        .ok_or(Error::MissingNbtTag("z"))?
-                        & CHUNK_OP;
-
-                    // check if x y z is within
-                    if (chunk_coords.0..chunk_coords.0 + ChunkData::WIDTH as u8)
-                        .contains(&(x as u8))
-                        || ((section_y * ChunkData::WIDTH as i8)
-                            ..(section_y * ChunkData::WIDTH as i8) + ChunkData::WIDTH as i8)
-                            .contains(&(y as i8))
-                        || (chunk_coords.1..chunk_coords.1 + ChunkData::WIDTH as u8)
-                            .contains(&(z as u8))
-                    {
-                        block_entities.remove(i);
-                    }
+                // every block entity in the chunk is within it's x/z,
+                // so only the ones within the section's y needs to be removed
+                for block_entity in block_entities.iter() {
+                    // makes sure the unwrap below never fails
+                    block_entity_position(block_entity)?;
+                }
+                block_entities.retain(|be| {
+                    let y = be.int("y").unwrap();
+                    y.div_euclid(ChunkData::WIDTH as i32) != section_y as i32
+                });
+
+                if update_heightmaps {
+                    let world_height = chunk_data.world_height.clone();
+                    update_heightmaps_of(&mut chunk_data.nbt, world_height)?;
                 }
 
                 Ok::<(), Error>(())
             })?;
+        self.apply_poi_changes();
+
+        if self.config.compute_lighting {
+            self.compute_lighting()?;
+        }
 
         Ok(())
     }
 }
 
+/// Collects the point of interest changes from replacing an entire section with a single block.  
+fn section_poi_changes(
+    state: &NbtCompound,
+    chunk_coords: (u8, u8),
+    section_y: i8,
+    block: &Block,
+) -> Result<Vec<PoiChange>> {
+    let palette = match state
+        .list("palette")
+        .ok_or(Error::MissingNbtTag("palette"))?
+    {
+        NbtList::Compound(c) => c,
+        _ => return Err(Error::InvalidNbtList("palette")),
+    };
+    let old_poi: Vec<Option<PoiType>> = palette.iter().map(poi_of_compound).collect();
+    let new_poi = poi_of_block(block);
+
+    // skip decoding the data if there is no point of interest before or after
+    if new_poi.is_none() && old_poi.iter().all(Option::is_none) {
+        return Ok(vec![]);
+    }
+
+    let mut indexes: [i64; Region::BLOCK_DATA_LEN] = [0; Region::BLOCK_DATA_LEN];
+    let data_len = decode_data(
+        &mut indexes,
+        get_block_bit_count(palette.len()),
+        state.long_array("data"),
+    );
+
+    let mut changes = vec![];
+    for (index, palette_index) in indexes[..data_len].iter().enumerate() {
+        let old = old_poi
+            .get(*palette_index as usize)
+            .ok_or(Error::InvalidPaletteIndex(*palette_index))?;
+        if *old == new_poi {
+            continue;
+        }
+
+        let width = ChunkData::WIDTH as u32;
+        let index = index as u32;
+        changes.push(PoiChange {
+            coords: Coords::new(
+                chunk_coords.0 as u32 * width + (index & CHUNK_OP as u32),
+                section_y as i32 * width as i32 + (index / (width * width)) as i32,
+                chunk_coords.1 as u32 * width + ((index / width) & CHUNK_OP as u32),
+            ),
+            poi: new_poi,
+        });
+    }
+
+    Ok(changes)
+}
+
 impl ChunkData {
     /// Writes the pending changes to the current chunk NBT
     pub fn write_blocks(&mut self, chunk_coords: (u8, u8), config: &Config) -> Result<()> {
@@ -209,15 +280,20 @@ impl ChunkData {
         let mut block_entity_cache: AHashMap<(i32, i32, i32), bool> = AHashMap::new();
 
         //  missing chunk etc is set via /set_block since pending is in chunks
+        self.mark_modified();
         let nbt = &mut self.nbt;
         is_valid_chunk(&nbt, chunk_coords)?;
 
         // clear heightmaps if they exist since they can become outdated after this
-        if let Some(height_maps) = nbt.compound_mut("Heightmaps") {
-            height_maps.clear();
-        };
+        // unless they're recomputed once all sections are written
+        if !config.update_heightmaps {
+            if let Some(height_maps) = nbt.compound_mut("Heightmaps") {
+                height_maps.clear();
+            };
+        }
 
-        if config.update_lighting {
+        let update_lighting = config.update_lighting || config.compute_lighting;
+        if update_lighting {
             *nbt.byte_mut("isLightOn")
                 .ok_or(Error::MissingNbtTag("isLightOn"))? = 0;
         }
@@ -246,12 +322,10 @@ impl ChunkData {
         };
 
         // a little cache so we can find the index directly and remove it instead of looking up the coords everytime
+        // x & z are made chunk local, y is kept as is since the cache is used across all sections
         for be in block_entities.iter() {
-            let x = be.int("x").ok_or(Error::MissingNbtTag("x"))? & CHUNK_OP;
-            let y = be.int("y").ok_or(Error::MissingNbtTag("y"))? & CHUNK_OP;
-            let z = be.int("z").ok_or(Error::MissingNbtTag("z"))? & CHUNK_OP;
-
-            block_entity_cache.insert((x, y, z), false);
+            let (x, y, z) = block_entity_position(be)?;
+            block_entity_cache.insert((x & CHUNK_OP, y, z & CHUNK_OP), false);
         }
 
         for section in sections.iter_mut() {
@@ -261,7 +335,7 @@ impl ChunkData {
                 None => continue,
             };
 
-            if config.update_lighting {
+            if update_lighting {
                 section.remove("BlockLight");
                 section.remove("SkyLight");
             }
@@ -292,7 +366,18 @@ impl ChunkData {
                 }
             }
 
+            // the point of interest of every palette entry before any block gets written
+            let old_poi: Vec<Option<PoiType>> = match config.update_poi {
+                true => palette.iter().map(poi_of_compound).collect(),
+                false => vec![],
+            };
+
             for block in pending_blocks {
+                let new_poi = match config.update_poi {
+                    true => poi_of_block(&block.block),
+                    false => None,
+                };
+
                 // micro perf thing would be to keep track of "unique blocks"
                 // and if its just 1 unique block for this entire .write_blocks()
                 // we dont need to do any of this pretty much
@@ -329,10 +414,21 @@ impl ChunkData {
                     + y as u32 * ChunkData::WIDTH as u32 * ChunkData::WIDTH as u32)
                     as usize;
 
+                if config.update_poi && old_poi[old_indexes[index] as usize] != new_poi {
+                    self.poi_changes.push(PoiChange {
+                        coords: Coords::new(
+                            chunk_coords.0 as u32 * ChunkData::WIDTH as u32 + x,
+                            block.coordinates.y,
+                            chunk_coords.1 as u32 * ChunkData::WIDTH as u32 + z,
+                        ),
+                        poi: new_poi,
+                    });
+                }
+
                 old_indexes[index] = palette_index;
 
                 // if block entity at these coords, mark for deletion
-                match block_entity_cache.get_mut(&(x as i32, y, z as i32)) {
+                match block_entity_cache.get_mut(&(x as i32, block.coordinates.y, z as i32)) {
                     Some(be) => *be = true,
                     None => (),
                 };
@@ -342,19 +438,6 @@ impl ChunkData {
 
             clean_palette(&mut old_indexes, data_len, palette);
 
-            // remove any marked block entities
-            block_entities.retain(|be| {
-                let x = be.int("x").unwrap() & CHUNK_OP;
-                let y = be.int("y").unwrap() & CHUNK_OP;
-                let z = be.int("z").unwrap() & CHUNK_OP;
-
-                match block_entity_cache.get(&(x, y, z)) {
-                    Some(delete) if *delete => false,
-                    _ => true,
-                }
-            });
-            block_entity_cache.clear();
-
             if palette.len() == 1 {
                 // if theres only 1 palette we can remove the data
                 state.remove("data");
@@ -369,6 +452,29 @@ impl ChunkData {
             );
         }
 
+        // remove any marked block entities
+        block_entities.retain(|be| {
+            let (x, y, z) = block_entity_position(be).unwrap();
+            match block_entity_cache.get(&(x & CHUNK_OP, y, z & CHUNK_OP)) {
+                Some(delete) if *delete => false,
+                _ => true,
+            }
+        });
+
+        // and add the block entities that were set together with their blocks
+        if !self.pending_block_entities.is_empty() {
+            let block_entities = block_entities_mut(unsafe { &mut *chunk_ptr })?;
+            for block_entity in self.pending_block_entities.drain(..) {
+                let position = block_entity_position(&block_entity)?;
+                block_entities.retain(|be| block_entity_position(be).ok() != Some(position));
+                block_entities.push(block_entity);
+            }
+        }
+
+        if config.update_heightmaps {
+            update_heightmaps_of(&mut self.nbt, self.world_height.clone())?;
+        }
+
         // we could to a per block unset of each incase this fails mid point it "could" be ran again
         self.seen_blocks.clear();
         // unmark it as dirt after processing
@@ -383,6 +489,7 @@ impl ChunkData {
         let mut old_indexes: [i64; Region::BIOME_DATA_LEN] = [0; Region::BIOME_DATA_LEN];
         let mut cached_palette_indexes: AHashMap<NbtString, i64> = AHashMap::new();
 
+        self.mark_modified();
         let nbt = &mut self.nbt;
         is_valid_chunk(&nbt, chunk_coords)?;
 